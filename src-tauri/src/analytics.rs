@@ -1,5 +1,6 @@
 use crate::{lobby::Lobby, region::RegionInfo, summoner::Summoner};
 use serde_json::json;
+use tracing::warn;
 
 pub async fn send_analytics_event(team: &Lobby, summoner: &Summoner, region: &RegionInfo) {
     let summoner_name = format!("{}#{}", summoner.game_name, summoner.tag_line);
@@ -18,6 +19,6 @@ pub async fn send_analytics_event(team: &Lobby, summoner: &Summoner, region: &Re
         .await;
 
     if resp.is_err() {
-        println!("Failed to send analytics event!");
+        warn!("Failed to send analytics event!");
     }
 }
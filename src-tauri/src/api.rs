@@ -0,0 +1,153 @@
+use crate::{
+    commands, config::LocalApiConfig, dodge::DodgeCost, error::AppError, storage::Encounter,
+    streamer, ManagedApi,
+};
+use axum::{
+    extract::{Path, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::net::{Ipv4Addr, SocketAddr};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info};
+
+#[derive(Clone)]
+struct ApiState {
+    app_handle: AppHandle,
+    token: String,
+}
+
+/// `AppError` as a response, with the same `{ "kind", "message" }` body commands return.
+struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        ApiError(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            AppError::LcuNotConnected => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::InvalidInput(_) | AppError::InvalidConfig(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct DodgeRequest {
+    /// Falls back to the configured grace period like the `dodge` command.
+    grace_seconds: Option<u32>,
+}
+
+/// Every request needs `Authorization: Bearer <token>`, since any local program or web page
+/// can reach localhost.
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token == state.token);
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// Scrubbed like events when streamer mode is on, since this usually ends up on screen.
+async fn lobby_report(State(state): State<ApiState>) -> ApiResult<Value> {
+    let report = commands::get_lobby_report(state.app_handle.clone()).await?;
+    Ok(Json(streamer::scrub(&state.app_handle, report).await))
+}
+
+async fn encounters(
+    State(state): State<ApiState>,
+    Path(puuid): Path<String>,
+) -> ApiResult<Vec<Encounter>> {
+    Ok(Json(
+        commands::get_encounters(puuid, state.app_handle).await?,
+    ))
+}
+
+async fn dodge(
+    State(state): State<ApiState>,
+    request: Option<Json<DodgeRequest>>,
+) -> ApiResult<()> {
+    let Json(request) = request.unwrap_or_default();
+    commands::dodge(state.app_handle, request.grace_seconds).await?;
+    Ok(Json(()))
+}
+
+/// Toggles like the tray's `Arm dodge`.
+async fn arm_dodge(State(state): State<ApiState>) -> ApiResult<()> {
+    commands::enable_dodge(state.app_handle).await?;
+    Ok(Json(()))
+}
+
+async fn cancel_dodge(State(state): State<ApiState>) -> ApiResult<bool> {
+    Ok(Json(commands::cancel_dodge(state.app_handle).await?))
+}
+
+async fn dodge_cost(State(state): State<ApiState>) -> ApiResult<DodgeCost> {
+    Ok(Json(commands::get_dodge_cost(state.app_handle).await?))
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/lobby/report", get(lobby_report))
+        .route("/encounters/:puuid", get(encounters))
+        .route("/dodge", post(dodge))
+        .route("/dodge/arm", post(arm_dodge))
+        .route("/dodge/cancel", post(cancel_dodge))
+        .route("/dodge/cost", get(dodge_cost))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state)
+}
+
+/// Stops the running server, if any, and starts one on localhost when `config` enables it.
+/// Called at startup and whenever the `localApi` config changes.
+pub async fn apply(app_handle: AppHandle, config: LocalApiConfig) {
+    let server = app_handle.state::<ManagedApi>();
+    let mut server = server.0.lock().await;
+    if let Some(handle) = server.take() {
+        handle.abort();
+        // Wait for the listener to close so the port can be bound again.
+        let _ = handle.await;
+        info!("Stopped the local API");
+    }
+    if !config.enabled {
+        return;
+    }
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to start the local API on {}: {}", addr, e);
+            return;
+        }
+    };
+
+    let router = router(ApiState {
+        app_handle: app_handle.clone(),
+        token: config.token,
+    });
+    info!("Local API listening on {}", addr);
+    *server = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            error!("Local API stopped: {}", e);
+        }
+    }));
+}
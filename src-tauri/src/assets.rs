@@ -0,0 +1,427 @@
+use crate::{error::AppError, ranked, ManagedAssets};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const DDRAGON_URL: &str = "https://ddragon.leagueoflegends.com";
+const CDRAGON_URL: &str = "https://raw.communitydragon.org/latest";
+const CDRAGON_GAME_DATA: &str = "plugins/rcp-be-lol-game-data/global/default";
+const CDRAGON_EMBLEMS: &str =
+    "plugins/rcp-fe-lol-static-assets/global/default/images/ranked-emblem";
+const ASSETS_DIR: &str = "assets";
+const PATCH_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionData {
+    /// Numeric id as used by the LCU.
+    pub id: i64,
+    /// Data Dragon key, e.g. `MonkeyKing` for Wukong.
+    pub key: String,
+    pub name: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub image_url: String,
+    /// Community Dragon roles, e.g. `fighter` or `support`.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinData {
+    pub id: i64,
+    pub champion_id: i64,
+    pub name: String,
+    pub is_base: bool,
+    pub rarity: String,
+    pub splash_url: String,
+    pub tile_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueData {
+    pub id: i64,
+    pub name: String,
+    pub short_name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SummonerSpellData {
+    pub id: i64,
+    pub key: String,
+    pub name: String,
+    pub image_url: String,
+}
+
+/// Everything we keep from Data Dragon for a single patch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetData {
+    pub version: String,
+    pub champions: Vec<ChampionData>,
+    pub summoner_spells: Vec<SummonerSpellData>,
+    #[serde(default)]
+    pub skins: Vec<SkinData>,
+    #[serde(default)]
+    pub queues: Vec<QueueData>,
+    /// Paths of the downloaded rank emblems, keyed by tier.
+    #[serde(default)]
+    pub rank_emblems: BTreeMap<String, PathBuf>,
+    /// Position in `champions` by champion id.
+    #[serde(skip)]
+    champion_index: HashMap<i64, usize>,
+}
+
+#[derive(Default)]
+pub struct AssetState {
+    pub data: Option<AssetData>,
+    /// When we last checked Data Dragon for a new patch.
+    checked: Option<Instant>,
+}
+
+/// Lower case with spaces and punctuation dropped, so `kaisa` finds Kai'Sa.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl AssetData {
+    fn indexed(mut self) -> Self {
+        self.champion_index = self
+            .champions
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.id, i))
+            .collect();
+        self
+    }
+
+    pub fn queue(&self, id: i64) -> Option<&QueueData> {
+        self.queues.iter().find(|q| q.id == id)
+    }
+
+    pub fn skins_for(&self, champion_id: i64) -> Vec<&SkinData> {
+        self.skins
+            .iter()
+            .filter(|s| s.champion_id == champion_id)
+            .collect()
+    }
+
+    pub fn champion(&self, id: i64) -> Option<&ChampionData> {
+        self.champion_index.get(&id).map(|i| &self.champions[*i])
+    }
+
+    /// Champions whose name or key contains `query`, exact and prefix matches first.
+    pub fn search_champions(&self, query: &str) -> Vec<&ChampionData> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(u8, &ChampionData)> = self
+            .champions
+            .iter()
+            .filter_map(|c| {
+                let name = normalize(&c.name);
+                let key = normalize(&c.key);
+                let rank = if name == query || key == query {
+                    0
+                } else if name.starts_with(&query) || key.starts_with(&query) {
+                    1
+                } else if name.contains(&query) || key.contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, c))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        matches.into_iter().map(|(_, c)| c).collect()
+    }
+
+    pub fn profile_icon_url(&self, icon_id: i64) -> String {
+        format!(
+            "{}/cdn/{}/img/profileicon/{}.png",
+            DDRAGON_URL, self.version, icon_id
+        )
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DDragonFile {
+    data: BTreeMap<String, DDragonEntry>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DDragonEntry {
+    id: String,
+    key: String,
+    name: String,
+    title: String,
+    tags: Vec<String>,
+    image: DDragonImage,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DDragonImage {
+    full: String,
+}
+
+async fn fetch_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T, AppError> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Provider(format!("Data Dragon request failed: {}", e)))?;
+    response
+        .json::<T>()
+        .await
+        .map_err(|e| AppError::Parse(e.to_string()))
+}
+
+async fn download(version: &str) -> Result<AssetData, AppError> {
+    let base = format!("{}/cdn/{}", DDRAGON_URL, version);
+    let champions: DDragonFile = fetch_json(&format!("{}/data/en_US/champion.json", base)).await?;
+    let spells: DDragonFile = fetch_json(&format!("{}/data/en_US/summoner.json", base)).await?;
+
+    Ok(AssetData {
+        version: version.to_string(),
+        champions: champions
+            .data
+            .into_values()
+            .map(|c| ChampionData {
+                id: c.key.parse().unwrap_or_default(),
+                image_url: format!("{}/img/champion/{}", base, c.image.full),
+                key: c.id,
+                name: c.name,
+                title: c.title,
+                tags: c.tags,
+                roles: Vec::new(),
+            })
+            .collect(),
+        summoner_spells: spells
+            .data
+            .into_values()
+            .map(|s| SummonerSpellData {
+                id: s.key.parse().unwrap_or_default(),
+                image_url: format!("{}/img/spell/{}", base, s.image.full),
+                key: s.id,
+                name: s.name,
+            })
+            .collect(),
+        skins: Vec::new(),
+        queues: Vec::new(),
+        rank_emblems: BTreeMap::new(),
+        champion_index: HashMap::new(),
+    })
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CDragonChampion {
+    id: i64,
+    roles: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CDragonSkin {
+    id: i64,
+    name: String,
+    is_base: bool,
+    rarity: String,
+    splash_path: String,
+    tile_path: String,
+}
+
+/// Community Dragon serves game data paths like `/lol-game-data/assets/ASSETS/...` under
+/// lower case URLs.
+fn cdragon_asset_url(path: &str) -> String {
+    let path = path
+        .strip_prefix("/lol-game-data/assets/")
+        .unwrap_or(path)
+        .to_lowercase();
+    format!("{}/{}/{}", CDRAGON_URL, CDRAGON_GAME_DATA, path)
+}
+
+/// Adds Community Dragon's champion roles, skins, queue names and rank emblems. These only
+/// enrich the Data Dragon data, so a failure leaves the assets usable.
+async fn enrich(assets: &mut AssetData, data_dir: &Path) -> Result<(), AppError> {
+    let game_data = format!("{}/{}/v1", CDRAGON_URL, CDRAGON_GAME_DATA);
+
+    let champions: Vec<CDragonChampion> =
+        fetch_json(&format!("{}/champion-summary.json", game_data)).await?;
+    for champion in &mut assets.champions {
+        if let Some(summary) = champions.iter().find(|c| c.id == champion.id) {
+            champion.roles = summary.roles.clone();
+        }
+    }
+
+    let skins: BTreeMap<String, CDragonSkin> =
+        fetch_json(&format!("{}/skins.json", game_data)).await?;
+    assets.skins = skins
+        .into_values()
+        .map(|s| SkinData {
+            champion_id: s.id / 1000,
+            splash_url: cdragon_asset_url(&s.splash_path),
+            tile_url: cdragon_asset_url(&s.tile_path),
+            id: s.id,
+            name: s.name,
+            is_base: s.is_base,
+            rarity: s.rarity,
+        })
+        .collect();
+
+    assets.queues = fetch_json(&format!("{}/queues.json", game_data)).await?;
+
+    let emblem_dir = data_dir.join(ASSETS_DIR).join("emblems");
+    std::fs::create_dir_all(&emblem_dir)?;
+    for tier in ranked::TIERS {
+        let tier = tier.to_lowercase();
+        let path = emblem_dir.join(format!("{}.png", tier));
+        if !path.exists() {
+            let url = format!("{}/{}/emblem-{}.png", CDRAGON_URL, CDRAGON_EMBLEMS, tier);
+            let bytes = reqwest::get(&url)
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| AppError::Provider(format!("Community Dragon request failed: {}", e)))?
+                .bytes()
+                .await
+                .map_err(|e| AppError::Provider(e.to_string()))?;
+            std::fs::write(&path, bytes)?;
+        }
+        assets.rank_emblems.insert(tier.to_uppercase(), path);
+    }
+
+    Ok(())
+}
+
+fn cache_path(data_dir: &Path, version: &str) -> PathBuf {
+    data_dir.join(ASSETS_DIR).join(format!("{}.json", version))
+}
+
+fn read_cache(path: &Path) -> Option<AssetData> {
+    let json = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<AssetData>(&json)
+        .ok()
+        .map(AssetData::indexed)
+}
+
+/// The most recently written cache file, for when Data Dragon can't be reached.
+fn newest_cache(data_dir: &Path) -> Option<AssetData> {
+    std::fs::read_dir(data_dir.join(ASSETS_DIR))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .and_then(|(_, path)| read_cache(&path))
+}
+
+/// Loads the current patch's data from the cache in the app data dir, downloading it from
+/// Data and Community Dragon when the patch changed. Falls back to the newest cached patch when offline.
+pub async fn load(data_dir: &Path) -> Result<AssetData, AppError> {
+    let version =
+        match fetch_json::<Vec<String>>(&format!("{}/api/versions.json", DDRAGON_URL)).await {
+            Ok(versions) => versions.into_iter().next(),
+            Err(e) => {
+                warn!("Failed to fetch the current patch: {}", e);
+                None
+            }
+        };
+    let Some(version) = version else {
+        return newest_cache(data_dir)
+            .ok_or_else(|| AppError::Provider("No cached Data Dragon assets".to_string()));
+    };
+
+    // A cache without queues is missing the Community Dragon data, so that part is retried.
+    let path = cache_path(data_dir, &version);
+    let mut assets = match read_cache(&path) {
+        Some(cached) if !cached.queues.is_empty() => return Ok(cached),
+        Some(cached) => cached,
+        None => {
+            info!("Downloading Data Dragon assets for patch {}", version);
+            download(&version).await?
+        }
+    };
+    if let Err(e) = enrich(&mut assets, data_dir).await {
+        warn!("Failed to fetch Community Dragon data: {}", e);
+    }
+    std::fs::create_dir_all(data_dir.join(ASSETS_DIR))?;
+    std::fs::write(&path, serde_json::to_string(&assets)?)?;
+    Ok(assets.indexed())
+}
+
+/// The loaded assets, loading them on first use. Every few hours Data Dragon is checked
+/// again, so a patch released while the app is running gets picked up.
+pub async fn get_assets(app_handle: &AppHandle) -> Result<AssetData, AppError> {
+    let assets = app_handle.state::<ManagedAssets>();
+    let mut assets = assets.0.lock().await;
+    let fresh = assets
+        .checked
+        .is_some_and(|checked| checked.elapsed() < PATCH_CHECK_INTERVAL);
+    if let (Some(loaded), true) = (assets.data.as_ref(), fresh) {
+        return Ok(loaded.clone());
+    }
+
+    let data_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| AppError::Io("Failed to resolve data directory".to_string()))?;
+    assets.checked = Some(Instant::now());
+    match load(&data_dir).await {
+        Ok(loaded) => {
+            if assets.data.as_ref().map(|d| &d.version) != Some(&loaded.version) {
+                info!("Loaded assets for patch {}", loaded.version);
+            }
+            assets.data = Some(loaded.clone());
+            Ok(loaded)
+        }
+        Err(e) => match assets.data.as_ref() {
+            Some(loaded) => {
+                warn!(
+                    "Failed to refresh assets, keeping patch {}: {}",
+                    loaded.version, e
+                );
+                Ok(loaded.clone())
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Display names by champion id, falling back to the id when the assets aren't available.
+pub async fn champion_names(app_handle: &AppHandle, champion_ids: &[i64]) -> BTreeMap<i64, String> {
+    let assets = get_assets(app_handle).await.ok();
+    champion_ids
+        .iter()
+        .map(|id| {
+            let name = assets
+                .as_ref()
+                .and_then(|a| a.champion(*id))
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| id.to_string());
+            (*id, name)
+        })
+        .collect()
+}
+
+pub async fn champion_name(app_handle: &AppHandle, champion_id: i64) -> String {
+    champion_names(app_handle, &[champion_id])
+        .await
+        .remove(&champion_id)
+        .unwrap_or_default()
+}
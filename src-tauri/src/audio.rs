@@ -0,0 +1,76 @@
+use crate::AppConfig;
+use rodio::{source::SineWave, Decoder, OutputStream, Sink, Source};
+use std::{fs::File, io::BufReader, time::Duration};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+/// Setting value that plays the built-in tone for an event instead of a file.
+pub const DEFAULT_SOUND: &str = "default";
+
+#[derive(Clone, Copy, Debug)]
+pub enum Sound {
+    QueuePop,
+    ChampSelectStart,
+    DodgeCountdown,
+    Blocklisted,
+    Vote,
+}
+
+impl Sound {
+    /// Built-in tones, pitched so the events can be told apart by ear.
+    fn tone(self) -> (f32, Duration) {
+        match self {
+            Sound::QueuePop => (880.0, Duration::from_millis(400)),
+            Sound::ChampSelectStart => (660.0, Duration::from_millis(250)),
+            Sound::DodgeCountdown => (1200.0, Duration::from_millis(80)),
+            Sound::Blocklisted => (330.0, Duration::from_millis(600)),
+            Sound::Vote => (520.0, Duration::from_millis(500)),
+        }
+    }
+}
+
+fn play_blocking(sound: Sound, file: &str, volume: f32) -> Result<(), String> {
+    // The output stream can't leave the thread it was opened on.
+    let (_stream, handle) = OutputStream::try_default().map_err(|e| e.to_string())?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    sink.set_volume(volume);
+
+    if file == DEFAULT_SOUND {
+        let (frequency, duration) = sound.tone();
+        sink.append(SineWave::new(frequency).take_duration(duration));
+    } else {
+        let file = File::open(file).map_err(|e| e.to_string())?;
+        let source = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+        sink.append(source);
+    }
+
+    sink.sleep_until_end();
+    Ok(())
+}
+
+/// Plays the sound configured for `sound` on its own thread, if there is one.
+pub async fn play(app_handle: &AppHandle, sound: Sound) {
+    let (file, volume) = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        let sounds = &config.sounds;
+        let file = match sound {
+            Sound::QueuePop => &sounds.queue_pop,
+            Sound::ChampSelectStart => &sounds.champ_select_start,
+            Sound::DodgeCountdown => &sounds.dodge_countdown,
+            Sound::Blocklisted => &sounds.blocklisted,
+            Sound::Vote => &sounds.vote,
+        };
+        (file.clone(), sounds.volume)
+    };
+    if file.is_empty() {
+        return;
+    }
+
+    debug!("Playing {:?} sound", sound);
+    std::thread::spawn(move || {
+        if let Err(e) = play_blocking(sound, &file, volume) {
+            warn!("Failed to play {:?} sound: {}", sound, e);
+        }
+    });
+}
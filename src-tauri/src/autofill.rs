@@ -0,0 +1,133 @@
+use crate::{
+    champ_select::{ChampSelectSession, Team},
+    match_history,
+    speech::{self, Announcement},
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const HISTORY_GAMES: usize = 20;
+/// Fewer known positions than this and we don't guess.
+const MIN_GAMES: usize = 5;
+/// Playing the assigned position in less than this share of recent games counts as autofill.
+const AUTOFILL_SHARE: f64 = 0.15;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionShare {
+    pub position: String,
+    pub games: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutofillFlag {
+    pub cell_id: i64,
+    pub puuid: String,
+    pub assigned_position: String,
+    /// Positions from recent games, most played first.
+    pub main_positions: Vec<PositionShare>,
+    pub games_checked: usize,
+    pub likely_autofilled: bool,
+}
+
+/// Maps match history lanes and roles onto the position names champ select uses.
+fn position_from_timeline(lane: &str, role: &str) -> Option<&'static str> {
+    match (lane, role) {
+        ("TOP", _) => Some("top"),
+        ("JUNGLE", _) => Some("jungle"),
+        ("MIDDLE" | "MID", _) => Some("middle"),
+        ("BOTTOM" | "BOT", "DUO_SUPPORT" | "SUPPORT") => Some("utility"),
+        ("BOTTOM" | "BOT", _) => Some("bottom"),
+        _ => None,
+    }
+}
+
+async fn check_player(remoting_client: &RESTClient, player: &Team) -> AutofillFlag {
+    let games =
+        match match_history::get_match_history(remoting_client, &player.puuid, HISTORY_GAMES).await
+        {
+            Ok(games) => games,
+            Err(e) => {
+                warn!(
+                    "Failed to fetch match history for cell {}: {:?}",
+                    player.cell_id, e
+                );
+                Vec::new()
+            }
+        };
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for participant in games.iter().flat_map(|g| g.participants.first()) {
+        if let Some(position) =
+            position_from_timeline(&participant.timeline.lane, &participant.timeline.role)
+        {
+            *counts.entry(position).or_default() += 1;
+        }
+    }
+
+    let games_checked: usize = counts.values().sum();
+    let assigned = counts
+        .get(player.assigned_position.as_str())
+        .copied()
+        .unwrap_or_default();
+    let likely_autofilled =
+        games_checked >= MIN_GAMES && (assigned as f64) < games_checked as f64 * AUTOFILL_SHARE;
+
+    let mut main_positions: Vec<PositionShare> = counts
+        .into_iter()
+        .map(|(position, games)| PositionShare {
+            position: position.to_string(),
+            games,
+        })
+        .collect();
+    main_positions.sort_by(|a, b| b.games.cmp(&a.games));
+
+    AutofillFlag {
+        cell_id: player.cell_id,
+        puuid: player.puuid.clone(),
+        assigned_position: player.assigned_position.clone(),
+        main_positions,
+        games_checked,
+        likely_autofilled,
+    }
+}
+
+/// Checks every teammate with a known puuid and assigned position. Queues without positions
+/// yield no flags.
+pub async fn get_autofill_flags(
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+) -> Vec<AutofillFlag> {
+    join_all(
+        session
+            .my_team
+            .iter()
+            .filter(|p| !p.puuid.is_empty() && !p.assigned_position.is_empty())
+            .map(|player| check_player(remoting_client, player)),
+    )
+    .await
+}
+
+/// Emits `autofill_update` with a flag for every teammate and announces any autofills.
+pub async fn emit_autofill_flags(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+) {
+    let flags = get_autofill_flags(remoting_client, session).await;
+    if flags.is_empty() {
+        return;
+    }
+
+    let autofilled = flags.iter().filter(|f| f.likely_autofilled).count();
+    info!("{} teammates look autofilled", autofilled);
+    app_handle.emit_all("autofill_update", flags).unwrap();
+    if autofilled > 0 {
+        speech::announce(app_handle, Announcement::Autofills(autofilled)).await;
+    }
+}
@@ -0,0 +1,53 @@
+use auto_launch::{AutoLaunch, AutoLaunchBuilder};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info};
+
+/// Passed by the startup entry so the app starts in the tray instead of on screen.
+const MINIMIZED_ARG: &str = "--minimized";
+
+fn launcher(app_handle: &AppHandle) -> Result<AutoLaunch, String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    AutoLaunchBuilder::new()
+        .set_app_name(&app_handle.package_info().name)
+        .set_app_path(&exe.to_string_lossy())
+        .set_use_launch_agent(true)
+        .set_args(&[MINIMIZED_ARG])
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Adds or removes the startup entry (registry run key, launch agent or autostart desktop
+/// file depending on the OS) to match `enabled`.
+pub fn apply(app_handle: &AppHandle, enabled: bool) {
+    let result = launcher(app_handle).and_then(|launcher| {
+        if launcher.is_enabled().map_err(|e| e.to_string())? == enabled {
+            return Ok(());
+        }
+        if enabled {
+            launcher.enable()
+        } else {
+            launcher.disable()
+        }
+        .map_err(|e| e.to_string())?;
+        info!(
+            "Launch on startup turned {}",
+            if enabled { "on" } else { "off" }
+        );
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        error!("Failed to update the startup entry: {}", e);
+    }
+}
+
+/// Hides the main window when we were started by the startup entry.
+pub fn hide_if_minimized(app_handle: &AppHandle) {
+    if !std::env::args().any(|arg| arg == MINIMIZED_ARG) {
+        return;
+    }
+    if let Some(window) = app_handle.get_window("main") {
+        info!("Started minimized");
+        let _ = window.hide();
+    }
+}
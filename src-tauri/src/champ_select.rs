@@ -1,9 +1,19 @@
-use crate::{analytics, lobby, region::RegionInfo, summoner, utils::display_champ_select, Config};
+use crate::{
+    analytics, assets, chat,
+    config::{AutoBanConfig, AutoPickConfig, Config},
+    dodge,
+    error::AppError,
+    lobby, mute, played_with, region, rules, storage, streamer, summoner,
+    utils::display_champ_select,
+    ManagedChampSelectState, ManagedHandledGames,
+};
 use serde::{Deserialize, Serialize};
 use shaco::rest::RESTClient;
+use std::collections::BTreeMap;
 use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ChampSelectSession {
     pub allow_battle_boost: bool,
@@ -21,15 +31,30 @@ pub struct ChampSelectSession {
     pub is_spectating: bool,
     pub local_player_cell_id: i64,
     pub locked_event_index: i64,
-    //pub my_team: Vec<Team>,
+    #[serde(default)]
+    pub actions: Vec<Vec<Action>>,
+    #[serde(default)]
+    pub bans: Bans,
+    #[serde(default)]
+    pub bench_champions: Vec<BenchChampion>,
+    #[serde(default)]
+    pub chat_details: ChatDetails,
+    #[serde(default)]
+    pub my_team: Vec<Team>,
+    #[serde(default)]
+    pub their_team: Vec<Team>,
+    #[serde(default)]
+    pub trades: Vec<Trade>,
+    #[serde(default)]
+    pub pick_order_swaps: Vec<PickOrderSwap>,
     pub recovery_counter: i64,
     pub rerolls_remaining: i64,
     pub skip_champion_select: bool,
     pub timer: Timer,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Action {
     pub actor_cell_id: i64,
     pub champion_id: i64,
@@ -37,37 +62,48 @@ pub struct Action {
     pub id: i64,
     pub is_ally_action: bool,
     pub is_in_progress: bool,
+    pub pick_turn: i64,
     #[serde(rename = "type")]
     pub action_type: Type,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum Type {
     Ban,
     Pick,
     #[serde(rename = "ten_bans_reveal")]
     TenBansReveal,
+    #[default]
+    #[serde(other)]
+    Unknown,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Bans {
-    pub my_team_bans: Vec<Option<serde_json::Value>>,
+    pub my_team_bans: Vec<i64>,
     pub num_bans: i64,
-    pub their_team_bans: Vec<Option<serde_json::Value>>,
+    pub their_team_bans: Vec<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BenchChampion {
+    pub champion_id: i64,
+    pub is_priority: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct ChatDetails {
     pub muc_jwt_dto: MucJwtDto,
     pub multi_user_chat_id: String,
     pub multi_user_chat_password: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct MucJwtDto {
     pub channel_claim: String,
     pub domain: String,
@@ -75,13 +111,15 @@ pub struct MucJwtDto {
     pub target_region: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Team {
     pub assigned_position: String,
     pub cell_id: i64,
     pub champion_id: i64,
     pub champion_pick_intent: i64,
+    pub game_name: String,
+    pub tag_line: String,
     pub name_visibility_type: NameVisibilityType,
     pub obfuscated_puuid: String,
     pub obfuscated_summoner_id: i64,
@@ -94,23 +132,34 @@ pub struct Team {
     pub ward_skin_id: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub enum NameVisibilityType {
+    #[default]
     #[serde(rename = "HIDDEN")]
     Hidden,
     #[serde(rename = "UNHIDDEN")]
     Unhidden,
+    #[serde(other)]
+    Unknown,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
 pub struct PickOrderSwap {
     pub cell_id: i64,
     pub id: i64,
     pub state: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Trade {
+    pub cell_id: i64,
+    pub id: i64,
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Timer {
     pub adjusted_time_left_in_phase: u64,
@@ -120,55 +169,599 @@ pub struct Timer {
     pub total_time_in_phase: i64,
 }
 
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ChampSelectChange {
+    #[serde(rename_all = "camelCase")]
+    Phase { phase: String },
+    #[serde(rename_all = "camelCase")]
+    Hover { cell_id: i64, champion_id: i64 },
+    #[serde(rename_all = "camelCase")]
+    Ban { cell_id: i64, champion_id: i64 },
+    #[serde(rename_all = "camelCase")]
+    Lock { cell_id: i64, champion_id: i64 },
+    #[serde(rename_all = "camelCase")]
+    Bench {
+        champion_ids: Vec<i64>,
+        rerolls_remaining: i64,
+    },
+    /// A champion trade with `cell_id` changed state, `RECEIVED` being an incoming request.
+    #[serde(rename_all = "camelCase")]
+    Trade {
+        id: i64,
+        cell_id: i64,
+        state: String,
+    },
+    /// A pick order swap with `cell_id` changed state, `RECEIVED` being an incoming request.
+    #[serde(rename_all = "camelCase")]
+    PickOrderSwap {
+        id: i64,
+        cell_id: i64,
+        state: String,
+    },
+}
+
+impl ChampSelectChange {
+    pub fn champion_ids(&self) -> Vec<i64> {
+        match self {
+            ChampSelectChange::Phase { .. }
+            | ChampSelectChange::Trade { .. }
+            | ChampSelectChange::PickOrderSwap { .. } => Vec::new(),
+            ChampSelectChange::Hover { champion_id, .. }
+            | ChampSelectChange::Ban { champion_id, .. }
+            | ChampSelectChange::Lock { champion_id, .. } => vec![*champion_id],
+            ChampSelectChange::Bench { champion_ids, .. } => champion_ids.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectUpdate {
+    pub session: ChampSelectSession,
+    pub changes: Vec<ChampSelectChange>,
+    /// Display names for the champions in `changes`.
+    pub champion_names: BTreeMap<i64, String>,
+}
+
+/// Trades and swaps start out `AVAILABLE` or `INVALID`, so only requests already in flight
+/// are worth reporting the first time one is seen.
+fn request_changed(previous: Option<&str>, state: &str) -> bool {
+    match previous {
+        Some(previous) => previous != state,
+        None => state == "RECEIVED" || state == "SENT",
+    }
+}
+
+/// Lists what changed between two snapshots of the same champ select. Everything counts as
+/// new when there is no previous snapshot or it belongs to another game.
+pub fn diff_sessions(
+    previous: Option<&ChampSelectSession>,
+    session: &ChampSelectSession,
+) -> Vec<ChampSelectChange> {
+    let previous = previous.filter(|p| p.game_id == session.game_id);
+    let mut changes = Vec::new();
+
+    if previous.map(|p| &p.timer.phase) != Some(&session.timer.phase) {
+        changes.push(ChampSelectChange::Phase {
+            phase: session.timer.phase.clone(),
+        });
+    }
+
+    if session.bench_enabled
+        && previous.map(|p| (&p.bench_champions, p.rerolls_remaining))
+            != Some((&session.bench_champions, session.rerolls_remaining))
+    {
+        changes.push(ChampSelectChange::Bench {
+            champion_ids: session
+                .bench_champions
+                .iter()
+                .map(|b| b.champion_id)
+                .collect(),
+            rerolls_remaining: session.rerolls_remaining,
+        });
+    }
+
+    for trade in &session.trades {
+        let previous_state = previous
+            .and_then(|p| p.trades.iter().find(|pt| pt.id == trade.id))
+            .map(|pt| pt.state.as_str());
+        if request_changed(previous_state, &trade.state) {
+            changes.push(ChampSelectChange::Trade {
+                id: trade.id,
+                cell_id: trade.cell_id,
+                state: trade.state.clone(),
+            });
+        }
+    }
+
+    for swap in &session.pick_order_swaps {
+        let previous_state = previous
+            .and_then(|p| p.pick_order_swaps.iter().find(|ps| ps.id == swap.id))
+            .map(|ps| ps.state.as_str());
+        if request_changed(previous_state, &swap.state) {
+            changes.push(ChampSelectChange::PickOrderSwap {
+                id: swap.id,
+                cell_id: swap.cell_id,
+                state: swap.state.clone(),
+            });
+        }
+    }
+
+    for player in session.my_team.iter().chain(session.their_team.iter()) {
+        let previous_intent = previous
+            .and_then(|p| {
+                p.my_team
+                    .iter()
+                    .chain(p.their_team.iter())
+                    .find(|pp| pp.cell_id == player.cell_id)
+            })
+            .map(|pp| pp.champion_pick_intent)
+            .unwrap_or(0);
+
+        if player.champion_pick_intent != 0 && player.champion_pick_intent != previous_intent {
+            changes.push(ChampSelectChange::Hover {
+                cell_id: player.cell_id,
+                champion_id: player.champion_pick_intent,
+            });
+        }
+    }
+
+    for action in session.actions.iter().flatten() {
+        let previous_action =
+            previous.and_then(|p| p.actions.iter().flatten().find(|pa| pa.id == action.id));
+
+        if action.is_in_progress
+            && action.champion_id != 0
+            && previous_action.map(|pa| pa.champion_id) != Some(action.champion_id)
+        {
+            changes.push(ChampSelectChange::Hover {
+                cell_id: action.actor_cell_id,
+                champion_id: action.champion_id,
+            });
+        }
+
+        if !action.completed || previous_action.is_some_and(|pa| pa.completed) {
+            continue;
+        }
+
+        let cell_id = action.actor_cell_id;
+        let champion_id = action.champion_id;
+        match action.action_type {
+            Type::Ban => changes.push(ChampSelectChange::Ban {
+                cell_id,
+                champion_id,
+            }),
+            Type::Pick => changes.push(ChampSelectChange::Lock {
+                cell_id,
+                champion_id,
+            }),
+            _ => {}
+        }
+    }
+
+    changes
+}
+
+pub async fn reroll(remoting_client: &RESTClient) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            "/lol-champ-select/v1/session/my-selection/reroll".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(())
+}
+
+pub async fn swap_with_bench(
+    remoting_client: &RESTClient,
+    champion_id: i64,
+) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            format!("/lol-champ-select/v1/session/bench/swap/{}", champion_id),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(())
+}
+
+pub async fn get_champ_select_session(
+    remoting_client: &RESTClient,
+) -> Result<ChampSelectSession, AppError> {
+    let session = remoting_client
+        .get("/lol-champ-select/v1/session".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(session)?)
+}
+
+/// Games each once-per-game champ select task already ran for, since
+/// `handle_champ_select_start` runs again on every session update.
+#[derive(Default)]
+pub struct HandledGames {
+    pub lobby: Option<u64>,
+    pub lane_intent: Option<u64>,
+    pub blocklist: Option<u64>,
+    pub auto_mute: Option<u64>,
+}
+
+/// Marks `game_id` as handled for the task `task` picks, returning whether it wasn't yet.
+pub async fn first_time_for_game(
+    app_handle: &AppHandle,
+    game_id: u64,
+    task: fn(&mut HandledGames) -> &mut Option<u64>,
+) -> bool {
+    let handled = app_handle.state::<ManagedHandledGames>();
+    let mut handled = handled.0.lock().await;
+    let handled_game = task(&mut handled);
+    if *handled_game == Some(game_id) {
+        return false;
+    }
+    *handled_game = Some(game_id);
+    true
+}
+
+/// The session `lcu_events` keeps, asking the client when there is none yet.
+async fn current_session(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+) -> Option<ChampSelectSession> {
+    let champ_select = app_handle.state::<ManagedChampSelectState>();
+    let session = champ_select.0.lock().await.clone();
+    match session {
+        Some(session) => Some(session),
+        None => get_champ_select_session(remoting_client).await.ok(),
+    }
+}
+
 pub async fn handle_champ_select_start(
     app_client: &RESTClient,
     remoting_client: &RESTClient,
     config: &Config,
     app_handle: &AppHandle,
 ) {
-    println!("=== Champion Select Started Handler ===");
+    debug!("=== Champion Select Started Handler ===");
     
-    let team = lobby::get_lobby_info(app_client).await;
+    let team = lobby::get_lobby_info(app_client, remoting_client).await;
     
     // Get region info with error handling
-    let region_info_result = app_client
-        .get("/riotclient/region-locale".to_string())
+    let region_info = match region::get_region_info(app_client).await {
+        Ok(region_info) => region_info,
+        Err(e) => {
+            error!("Error fetching region info: {:?}", e);
+            return;
+        }
+    };
+
+    info!("Region: {}", region_info.web_region);
+    let session = current_session(app_handle, remoting_client).await;
+    let game_id = session.as_ref().map(|s| s.game_id);
+    // The chat fills up over the first few updates, so wait for everyone before the tasks
+    // that only run once per game look at the lobby.
+    let lobby_loaded = session
+        .as_ref()
+        .is_some_and(|s| team.participants.len() >= s.my_team.len());
+
+    // Emit the event to frontend
+    debug!("Emitting champ_select_started event with {} participants", team.participants.len());
+    streamer::remember_lobby(app_handle, &team).await;
+    let payload = streamer::scrub(app_handle, &team).await;
+    if let Err(e) = app_handle.emit_all("champ_select_started", payload) {
+        error!("Error emitting champ_select_started event: {:?}", e);
+    } else {
+        debug!("Successfully emitted champ_select_started event!");
+    }
+
+    if config.auto_open && !config.streamer_mode {
+        if let Err(e) = display_champ_select(&team, region_info.multi_search_region(), config) {
+            error!("Failed to open multi-search: {}", e);
+        }
+    }
+
+    let summoner = match summoner::get_current_summoner(remoting_client).await {
+        Ok(summoner) => summoner,
+        Err(e) => {
+            error!("Error fetching current summoner: {:?}", e);
+            return;
+        }
+    };
+    let record_lobby = match game_id {
+        Some(game_id) if lobby_loaded => {
+            first_time_for_game(app_handle, game_id, |h| &mut h.lobby).await
+        }
+        _ => false,
+    };
+    if record_lobby {
+        storage::record_lobby(
+            app_handle,
+            remoting_client,
+            &team,
+            &summoner.puuid,
+            &region_info.region,
+        )
         .await;
-        
-    if let Err(e) = region_info_result {
-        println!("Error fetching region info: {:?}", e);
-        return;
+        tauri::async_runtime::spawn(played_with::emit_played_with(
+            app_handle.clone(),
+            remoting_client.clone(),
+            team.clone(),
+            summoner.puuid.clone(),
+        ));
+        storage::emit_lobby_annotations(app_handle, &team).await;
     }
+    let send_lane_intent = match game_id {
+        Some(game_id) if config.lane_intent.enabled => {
+            first_time_for_game(app_handle, game_id, |h| &mut h.lane_intent).await
+        }
+        _ => false,
+    };
+    if send_lane_intent {
+        tauri::async_runtime::spawn(chat::send_lane_intent(
+            app_handle.clone(),
+            remoting_client.clone(),
+            config.lane_intent.clone(),
+        ));
+    }
+    let blocklisted = storage::check_blocklist(app_handle, &team, game_id).await;
+    if let Some(game_id) = game_id.filter(|_| !config.auto_mute_tags.is_empty()) {
+        tauri::async_runtime::spawn(mute::auto_mute_tagged(
+            app_handle.clone(),
+            app_client.clone(),
+            remoting_client.clone(),
+            team.clone(),
+            config.auto_mute_tags.clone(),
+            game_id,
+        ));
+    }
+    if config.auto_dodge_blocklisted {
+        dodge::arm_for_blocklisted(
+            app_handle,
+            remoting_client,
+            &config.dodge_queue_ids,
+            blocklisted,
+        )
+        .await;
+    }
+    rules::evaluate(
+        app_handle,
+        remoting_client,
+        &team,
+        &config.dodge_rules,
+        &config.dodge_queue_ids,
+    )
+    .await;
+    analytics::send_analytics_event(&team, &summoner, &region_info).await;
     
-    let region_info: Result<RegionInfo, _> = serde_json::from_value(region_info_result.unwrap());
-    
-    if let Err(e) = region_info {
-        println!("Error parsing region info: {:?}", e);
+    debug!("=== Champion Select Handler Complete ===");
+}
+
+impl ChampSelectSession {
+    pub fn local_player(&self) -> Option<&Team> {
+        self.my_team
+            .iter()
+            .find(|p| p.cell_id == self.local_player_cell_id)
+    }
+
+    /// The local player's assigned position, or `default` in queues without positions.
+    pub fn local_position(&self) -> &str {
+        self.local_player()
+            .map(|p| p.assigned_position.as_str())
+            .filter(|p| !p.is_empty())
+            .unwrap_or("default")
+    }
+
+    /// The local player's pending action of the given type, if any.
+    pub fn local_action(&self, action_type: Type) -> Option<&Action> {
+        self.actions.iter().flatten().find(|a| {
+            a.actor_cell_id == self.local_player_cell_id
+                && a.action_type == action_type
+                && !a.completed
+        })
+    }
+
+    /// Where the cell's pick falls in the pick order, if it has one.
+    pub fn pick_index(&self, cell_id: i64) -> Option<usize> {
+        self.actions
+            .iter()
+            .flatten()
+            .filter(|a| a.action_type == Type::Pick)
+            .position(|a| a.actor_cell_id == cell_id)
+    }
+
+    /// Champions that can no longer be picked because they were banned or picked already.
+    pub fn unavailable_champions(&self) -> Vec<i64> {
+        let mut champions = self
+            .actions
+            .iter()
+            .flatten()
+            .filter(|a| a.completed && a.champion_id != 0)
+            .map(|a| a.champion_id)
+            .collect::<Vec<i64>>();
+
+        champions.extend(
+            self.my_team
+                .iter()
+                .filter(|p| p.cell_id != self.local_player_cell_id && p.champion_id != 0)
+                .map(|p| p.champion_id),
+        );
+
+        champions
+    }
+}
+
+/// The list for a position, falling back to the `default` list.
+pub fn priorities_for<'a>(
+    lists: &'a BTreeMap<String, Vec<i64>>,
+    position: &str,
+) -> Option<&'a Vec<i64>> {
+    lists.get(position).or_else(|| lists.get("default"))
+}
+
+async fn get_pickable_champions(remoting_client: &RESTClient) -> Vec<i64> {
+    match remoting_client
+        .get("/lol-champ-select/v1/pickable-champion-ids".to_string())
+        .await
+    {
+        Ok(ids) => serde_json::from_value(ids).unwrap_or_default(),
+        Err(e) => {
+            warn!("Error fetching pickable champions: {:?}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Declares the configured champion for the local player's position and, unless running in
+/// hover only mode, locks it in once it is our turn.
+pub async fn handle_auto_pick(
+    session: &ChampSelectSession,
+    remoting_client: &RESTClient,
+    config: &AutoPickConfig,
+) {
+    if !config.enabled {
         return;
     }
-    
-    let region_info = region_info.unwrap();
-    println!("Region: {}", region_info.web_region);
 
-    // Emit the event to frontend
-    println!("Emitting champ_select_started event with {} participants", team.participants.len());
-    if let Err(e) = app_handle.emit_all("champ_select_started", &team) {
-        println!("Error emitting champ_select_started event: {:?}", e);
-    } else {
-        println!("Successfully emitted champ_select_started event!");
+    let Some(action) = session.local_action(Type::Pick) else {
+        return;
+    };
+
+    let position = session.local_position();
+    let Some(priorities) = priorities_for(&config.champions, position) else {
+        return;
+    };
+
+    // Respect a champion the player hovered themselves.
+    if action.champion_id != 0 && !priorities.contains(&action.champion_id) {
+        return;
     }
 
-    if config.auto_open {
-        let region = match region_info.web_region.as_str() {
-            "SG2" => "SG",
-            _ => &region_info.web_region,
-        };
+    let unavailable = session.unavailable_champions();
+    let pickable = get_pickable_champions(remoting_client).await;
+    let Some(champion_id) = priorities
+        .iter()
+        .find(|id| !unavailable.contains(id) && pickable.contains(id))
+        .copied()
+    else {
+        debug!("No configured champion is available for {}", position);
+        return;
+    };
 
-        display_champ_select(&team, region, &config.multi_provider);
+    let lock = action.is_in_progress && !config.hover_only;
+    if action.champion_id == champion_id && !lock {
+        return;
     }
 
-    let summoner = summoner::get_current_summoner(remoting_client).await;
-    analytics::send_analytics_event(&team, &summoner, &region_info).await;
-    
-    println!("=== Champion Select Handler Complete ===");
+    info!(
+        "Auto pick {} champion {} for {}",
+        if lock { "locking" } else { "hovering" },
+        champion_id,
+        position
+    );
+
+    let resp = remoting_client
+        .patch(
+            format!("/lol-champ-select/v1/session/actions/{}", action.id),
+            serde_json::json!({ "championId": champion_id, "completed": lock }),
+        )
+        .await;
+
+    if let Err(e) = resp {
+        warn!("Failed to update pick action: {:?}", e);
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBanExecuted {
+    pub champion_id: i64,
+    pub champion_name: String,
+    pub position: String,
+}
+
+async fn get_bannable_champions(remoting_client: &RESTClient) -> Vec<i64> {
+    match remoting_client
+        .get("/lol-champ-select/v1/bannable-champion-ids".to_string())
+        .await
+    {
+        Ok(ids) => serde_json::from_value(ids).unwrap_or_default(),
+        Err(e) => {
+            warn!("Error fetching bannable champions: {:?}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Bans the first configured champion for the local player's position once the ban action
+/// is in progress, skipping anything a teammate is hovering.
+pub async fn handle_auto_ban(
+    session: &ChampSelectSession,
+    remoting_client: &RESTClient,
+    config: &AutoBanConfig,
+    app_handle: &AppHandle,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    let Some(action) = session.local_action(Type::Ban) else {
+        return;
+    };
+
+    if !action.is_in_progress {
+        return;
+    }
+
+    let position = session.local_position();
+    let Some(priorities) = priorities_for(&config.champions, position) else {
+        return;
+    };
+
+    let hovered = session
+        .my_team
+        .iter()
+        .flat_map(|p| [p.champion_pick_intent, p.champion_id])
+        .filter(|id| *id != 0)
+        .collect::<Vec<i64>>();
+    let unavailable = session.unavailable_champions();
+    let bannable = get_bannable_champions(remoting_client).await;
+
+    let Some(champion_id) = priorities
+        .iter()
+        .find(|id| !hovered.contains(id) && !unavailable.contains(id) && bannable.contains(id))
+        .copied()
+    else {
+        debug!("No configured ban is available for {}", position);
+        return;
+    };
+
+    let champion_name = assets::champion_name(app_handle, champion_id).await;
+    info!("Auto banning {} for {}", champion_name, position);
+
+    let resp = remoting_client
+        .patch(
+            format!("/lol-champ-select/v1/session/actions/{}", action.id),
+            serde_json::json!({ "championId": champion_id, "completed": true }),
+        )
+        .await;
+
+    if let Err(e) = resp {
+        warn!("Failed to submit ban: {:?}", e);
+        return;
+    }
+
+    app_handle
+        .emit_all(
+            "auto_ban_executed",
+            AutoBanExecuted {
+                champion_id,
+                champion_name,
+                position: position.to_string(),
+            },
+        )
+        .unwrap();
 }
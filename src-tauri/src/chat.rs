@@ -0,0 +1,213 @@
+use crate::{
+    champ_select,
+    config::LaneIntentConfig,
+    error::AppError,
+    i18n,
+    lobby::{self, Participant},
+    storage::ChatMessage,
+    streamer, ManagedChampSelectState, ManagedStorage,
+};
+use serde::Deserialize;
+use shaco::rest::RESTClient;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+/// The champ select conversation shows up a moment after champ select starts.
+const CONVERSATION_ATTEMPTS: u32 = 10;
+const CONVERSATION_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Conversation {
+    id: String,
+    #[serde(rename = "type")]
+    conversation_type: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LcuChatMessage {
+    id: String,
+    body: String,
+    from_pid: String,
+    timestamp: String,
+    #[serde(rename = "type")]
+    message_type: String,
+}
+
+pub async fn get_champ_select_conversation(
+    remoting_client: &RESTClient,
+) -> Result<Option<String>, AppError> {
+    let conversations = remoting_client
+        .get("/lol-chat/v1/conversations".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    let conversations: Vec<Conversation> = serde_json::from_value(conversations)?;
+
+    Ok(conversations
+        .into_iter()
+        .find(|c| c.conversation_type == "championSelect")
+        .map(|c| c.id))
+}
+
+pub async fn send_message(
+    remoting_client: &RESTClient,
+    conversation_id: &str,
+    body: &str,
+) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            format!(
+                "/lol-chat/v1/conversations/{}/messages",
+                urlencoding::encode(conversation_id)
+            ),
+            serde_json::json!({ "body": body, "type": "chat" }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+async fn wait_for_champ_select_conversation(remoting_client: &RESTClient) -> Option<String> {
+    for _ in 0..CONVERSATION_ATTEMPTS {
+        match get_champ_select_conversation(remoting_client).await {
+            Ok(Some(id)) => return Some(id),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to list chat conversations: {:?}", e),
+        }
+        tokio::time::sleep(CONVERSATION_INTERVAL).await;
+    }
+    None
+}
+
+/// Sends the configured lane intent message to the champ select chat and emits
+/// `lane_intent_sent`. `{position}` is replaced with our assigned position; without one the
+/// message is only sent if it doesn't mention the position.
+pub async fn send_lane_intent(
+    app_handle: AppHandle,
+    remoting_client: RESTClient,
+    config: LaneIntentConfig,
+) {
+    let message = if config.message.contains("{position}") {
+        let session = match champ_select::get_champ_select_session(&remoting_client).await {
+            Ok(session) => session,
+            Err(e) => {
+                warn!("No champ select session for the lane intent: {:?}", e);
+                return;
+            }
+        };
+        let position = session.local_position();
+        if position == "default" {
+            info!("No assigned position, not sending the lane intent");
+            return;
+        }
+        let position = i18n::t(&app_handle, &format!("position.{}", position)).await;
+        config.message.replace("{position}", &position)
+    } else {
+        config.message.clone()
+    };
+
+    tokio::time::sleep(Duration::from_millis(config.delay_ms as u64)).await;
+    let Some(conversation_id) = wait_for_champ_select_conversation(&remoting_client).await else {
+        warn!("Champ select chat never showed up, not sending the lane intent");
+        return;
+    };
+
+    if let Err(e) = send_message(&remoting_client, &conversation_id, &message).await {
+        warn!("Failed to send the lane intent: {}", e);
+        return;
+    }
+
+    info!("Sent lane intent: {}", message);
+    app_handle.emit_all("lane_intent_sent", &message).unwrap();
+}
+
+async fn get_messages(
+    remoting_client: &RESTClient,
+    conversation_id: &str,
+) -> Result<Vec<LcuChatMessage>, AppError> {
+    let messages = remoting_client
+        .get(format!(
+            "/lol-chat/v1/conversations/{}/messages",
+            urlencoding::encode(conversation_id)
+        ))
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(messages)?)
+}
+
+/// Matches a chat sender to a lobby player the same way premade detection does.
+fn sender<'a>(participants: &'a [Participant], from_pid: &str) -> Option<&'a Participant> {
+    participants
+        .iter()
+        .find(|p| p.pid == from_pid || (!p.puuid.is_empty() && from_pid.starts_with(&p.puuid)))
+}
+
+/// Stores the champ select chat messages we haven't seen yet and emits
+/// `champ_select_chat_message` for each of them.
+pub async fn capture_champ_select_chat(
+    app_handle: AppHandle,
+    app_client: RESTClient,
+    remoting_client: RESTClient,
+) {
+    let Some(game_id) = app_handle
+        .state::<ManagedChampSelectState>()
+        .0
+        .lock()
+        .await
+        .as_ref()
+        .map(|s| s.game_id as i64)
+    else {
+        return;
+    };
+
+    let conversation_id = match get_champ_select_conversation(&remoting_client).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to list chat conversations: {:?}", e);
+            return;
+        }
+    };
+    let messages = match get_messages(&remoting_client, &conversation_id).await {
+        Ok(messages) => messages,
+        Err(e) => {
+            warn!("Failed to fetch champ select chat messages: {:?}", e);
+            return;
+        }
+    };
+    let messages: Vec<LcuChatMessage> = messages
+        .into_iter()
+        .filter(|m| m.message_type == "chat" && !m.id.is_empty())
+        .collect();
+    if messages.is_empty() {
+        return;
+    }
+
+    let lobby = lobby::get_lobby_info(&app_client, &remoting_client).await;
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    for message in messages {
+        let participant = sender(&lobby.participants, &message.from_pid);
+        let message = ChatMessage {
+            message_id: message.id,
+            game_id,
+            puuid: participant.map(|p| p.puuid.clone()).unwrap_or_default(),
+            sender: participant
+                .map(|p| format!("{}#{}", p.game_name, p.game_tag))
+                .unwrap_or_default(),
+            body: message.body,
+            sent_at: message.timestamp,
+        };
+
+        match storage.record_chat_message(&message) {
+            Ok(true) => {
+                debug!("Recorded champ select message from {}", message.sender);
+                streamer::emit_all(&app_handle, "champ_select_chat_message", &message).await;
+            }
+            Ok(false) => {}
+            Err(e) => error!("Failed to record chat message: {}", e),
+        }
+    }
+}
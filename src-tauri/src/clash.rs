@@ -0,0 +1,147 @@
+use crate::{error::AppError, lobby::Participant, summoner};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TournamentStateInfo {
+    pub tournament_id: i64,
+    pub bracket_id: i64,
+    pub roster_id: i64,
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Bracket {
+    pub id: i64,
+    pub current_round: i64,
+    pub matches: Vec<BracketMatch>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BracketMatch {
+    pub id: i64,
+    pub round: i64,
+    pub roster_id1: i64,
+    pub roster_id2: i64,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Roster {
+    pub id: i64,
+    pub name: String,
+    pub short_name: String,
+    pub members: Vec<RosterMember>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RosterMember {
+    pub puuid: String,
+    pub summoner_id: i64,
+    pub position: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClashScout {
+    pub tournament_id: i64,
+    pub round: i64,
+    pub roster: Roster,
+    pub participants: Vec<Participant>,
+}
+
+async fn get_tournament_state(
+    remoting_client: &RESTClient,
+) -> Result<TournamentStateInfo, AppError> {
+    let states = remoting_client
+        .get("/lol-clash/v1/tournament-state-info".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    let states: Vec<TournamentStateInfo> = serde_json::from_value(states)?;
+    states
+        .into_iter()
+        .find(|s| s.bracket_id != 0 && s.roster_id != 0)
+        .ok_or_else(|| AppError::InvalidInput("Not registered in a Clash bracket".to_string()))
+}
+
+async fn get_bracket(remoting_client: &RESTClient, bracket_id: i64) -> Result<Bracket, AppError> {
+    let bracket = remoting_client
+        .get(format!("/lol-clash/v1/bracket/{}", bracket_id))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(bracket)?)
+}
+
+async fn get_roster(remoting_client: &RESTClient, roster_id: i64) -> Result<Roster, AppError> {
+    let roster = remoting_client
+        .get(format!("/lol-clash/v1/roster/{}", roster_id))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(roster)?)
+}
+
+/// Finds the roster we face in the current round of our bracket and resolves its members'
+/// Riot IDs so they can go through the same multi-search pipeline as a champ select lobby.
+pub async fn scout_opponents(remoting_client: &RESTClient) -> Result<ClashScout, AppError> {
+    let state = get_tournament_state(remoting_client).await?;
+    let bracket = get_bracket(remoting_client, state.bracket_id).await?;
+
+    let matchup = bracket
+        .matches
+        .iter()
+        .filter(|m| m.roster_id1 == state.roster_id || m.roster_id2 == state.roster_id)
+        .filter(|m| m.round >= bracket.current_round)
+        .min_by_key(|m| m.round)
+        .ok_or_else(|| AppError::InvalidInput("No upcoming Clash match found".to_string()))?;
+
+    let opponent_id = if matchup.roster_id1 == state.roster_id {
+        matchup.roster_id2
+    } else {
+        matchup.roster_id1
+    };
+
+    if opponent_id == 0 {
+        return Err(AppError::InvalidInput(
+            "Opponent for the next round is not decided yet".to_string(),
+        ));
+    }
+
+    let roster = get_roster(remoting_client, opponent_id).await?;
+    info!(
+        "Scouting Clash roster {} for round {}",
+        roster.name, matchup.round
+    );
+
+    let mut participants = Vec::new();
+    for member in &roster.members {
+        match summoner::get_summoner_by_puuid(remoting_client, &member.puuid).await {
+            Ok(summoner) => participants.push(Participant {
+                cid: String::new(),
+                game_name: summoner.game_name.clone(),
+                game_tag: summoner.tag_line.clone(),
+                muted: false,
+                name: summoner.game_name,
+                pid: String::new(),
+                puuid: member.puuid.clone(),
+                region: String::new(),
+            }),
+            Err(e) => warn!("Failed to resolve Clash member {}: {:?}", member.puuid, e),
+        }
+    }
+
+    Ok(ClashScout {
+        tournament_id: state.tournament_id,
+        round: matchup.round,
+        roster,
+        participants,
+    })
+}
@@ -1,40 +1,111 @@
 use crate::{
-    champ_select::ChampSelectSession, lobby::get_lobby_info, region::RegionInfo,
-    utils::display_champ_select, AppConfig, Config, ManagedDodgeState, LCU,
+    assets::{self, ChampionData, QueueData, SkinData},
+    autofill::{self, AutofillFlag},
+    champ_select::{self, ChampSelectSession},
+    clash::{self, ClashScout},
+    config::{self, Config, ExportedConfig},
+    crash::{self, CrashReport},
+    dodge::{self, DodgeAdvice, DodgeCost},
+    enemy_team::{self, EnemyTeam},
+    error::AppError,
+    export,
+    friends::{self, FriendStatus},
+    gameflow::GameflowPhase,
+    i18n,
+    invites::{self, Invitation},
+    item_sets,
+    livegame::{AllGameData, LiveEvent, LivePlayer},
+    lobby::{get_lobby_info, Lobby},
+    lolalytics::{self, ChampionStatLink},
+    mastery::{self, ParticipantMastery},
+    match_history::{self, ParticipantMatch},
+    mute::{self, MutedPlayer},
+    opgg::{self, BatchResult, McpTool},
+    penalties::{self, Penalties},
+    premades::{self, PremadeGroup},
+    presets::{self, Preset, PresetApplied},
+    profiles::{self, ProfileList, ProfileSwitched},
+    queue::{self, QueueStatus},
+    ranked::{self, ParticipantRank},
+    region,
+    replays::{self, ReplayDownloaded},
+    report::{self, LobbyReport},
+    requeue,
+    session_stats::SessionStats,
+    skins::{self, SkinSelected},
+    smurf::{self, SmurfScore},
+    spectate,
+    storage::{BlockedPlayer, ChatMessage, Encounter, PlayerAnnotations},
+    streamer,
+    trades::{self, RequestKind},
+    updater::{self, UpdateInfo},
+    utils::display_champ_select,
+    win_probability::{self, WinProbability},
+    AppConfig, ManagedChampSelectState, ManagedDodgeState, ManagedGameflowState, ManagedLiveGame,
+    ManagedOpgg, ManagedPresets, ManagedProfiles, ManagedSession, ManagedStorage, LCU,
 };
-use shaco::rest::{LCUClientInfo, RESTClient};
-use tauri::{AppHandle, Manager};
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use shaco::rest::{LCUClientInfo, RESTClient};
+use std::{collections::BTreeMap, path::PathBuf};
+use tauri::{AppHandle, ClipboardManager, Manager};
+use tracing::{debug, info, warn};
+
+const PARTICIPANT_MATCHES: usize = 20;
+
+/// Returns the shared app client, or an error while the League Client is not connected.
+async fn app_client(app_handle: &AppHandle) -> Result<RESTClient, AppError> {
+    let lcu_state = app_handle.state::<LCU>();
+    let lcu_state = lcu_state.0.lock().await;
+    lcu_state
+        .app_client
+        .clone()
+        .ok_or(AppError::LcuNotConnected)
+}
+
+/// Returns the shared remoting client, or an error while the League Client is not connected.
+async fn remoting_client(app_handle: &AppHandle) -> Result<RESTClient, AppError> {
+    let lcu_state = app_handle.state::<LCU>();
+    let lcu_state = lcu_state.0.lock().await;
+    lcu_state
+        .remoting_client
+        .clone()
+        .ok_or(AppError::LcuNotConnected)
+}
 
 #[tauri::command]
 pub async fn app_ready(
     app_handle: AppHandle,
     lcu: tauri::State<'_, LCU>,
     cfg: tauri::State<'_, AppConfig>,
-) -> Result<Config, ()> {
-    println!("App Ready!");
+) -> Result<Config, AppError> {
+    info!("App Ready!");
     let lcu = lcu.0.lock().await;
     let cfg = cfg.0.lock().await;
 
-    println!("LCU State: {}", lcu.connected);
-    println!("Config: {:?}", cfg);
+    debug!("LCU State: {}", lcu.connected);
+    debug!("Config: {:?}", cfg);
 
-    app_handle
-        .emit_all("lcu_state_update", lcu.connected)
-        .unwrap();
+    app_handle.emit_all("lcu_state_update", lcu.connected)?;
 
     Ok(cfg.clone())
 }
 
 #[tauri::command]
-pub async fn get_lcu_state(lcu: tauri::State<'_, LCU>) -> Result<bool, ()> {
+pub async fn get_lcu_state(lcu: tauri::State<'_, LCU>) -> Result<bool, AppError> {
     let lcu = lcu.0.lock().await;
     Ok(lcu.connected)
 }
 
 #[tauri::command]
-pub async fn get_config(cfg: tauri::State<'_, AppConfig>) -> Result<Config, ()> {
+pub async fn get_gameflow_phase(
+    gameflow: tauri::State<'_, ManagedGameflowState>,
+) -> Result<GameflowPhase, AppError> {
+    let gameflow = gameflow.0.lock().await;
+    Ok(*gameflow)
+}
+
+#[tauri::command]
+pub async fn get_config(cfg: tauri::State<'_, AppConfig>) -> Result<Config, AppError> {
     let cfg = cfg.0.lock().await;
     Ok(cfg.clone())
 }
@@ -44,168 +115,976 @@ pub async fn set_config(
     cfg: tauri::State<'_, AppConfig>,
     new_cfg: Config,
     app_handle: AppHandle,
-) -> Result<(), ()> {
-    println!("Setting Config: {:?}", new_cfg);
+) -> Result<(), AppError> {
+    info!("Setting Config: {:?}", new_cfg);
+    new_cfg.validate().map_err(AppError::InvalidConfig)?;
+
     let mut cfg = cfg.0.lock().await;
+    config::apply_changes(&app_handle, &cfg, &new_cfg).await?;
     *cfg = new_cfg;
 
     // Save config to disk
-    let cfg_folder = app_handle.path_resolver().app_config_dir().unwrap();
-    let cfg_path = cfg_folder.join("config.json");
-    let cfg_json = serde_json::to_string(&cfg.clone()).unwrap();
-    tokio::fs::write(&cfg_path, cfg_json).await.unwrap();
+    let cfg_folder = config::config_dir(&app_handle)?;
+    config::write_config(&cfg_folder.join("config.json"), &cfg).await
+}
 
+#[tauri::command]
+pub async fn set_auto_accept(
+    enabled: bool,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    let mut new_cfg = cfg.0.lock().await.clone();
+    new_cfg.auto_accept = enabled;
+    set_config(cfg, new_cfg.clone(), app_handle).await?;
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn set_auto_ban(
+    enabled: bool,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    let mut new_cfg = cfg.0.lock().await.clone();
+    new_cfg.auto_ban.enabled = enabled;
+    set_config(cfg, new_cfg.clone(), app_handle).await?;
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn set_auto_runes(
+    enabled: bool,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    let mut new_cfg = cfg.0.lock().await.clone();
+    new_cfg.runes.enabled = enabled;
+    set_config(cfg, new_cfg.clone(), app_handle).await?;
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn set_auto_item_sets(
+    enabled: bool,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    let mut new_cfg = cfg.0.lock().await.clone();
+    new_cfg.item_sets.enabled = enabled;
+    set_config(cfg, new_cfg.clone(), app_handle).await?;
+    Ok(new_cfg)
+}
+
+/// Removes every item set iSeeUAll generated. Returns how many were removed.
+#[tauri::command]
+pub async fn clear_item_sets(app_handle: AppHandle) -> Result<usize, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    item_sets::clear_generated(&remoting_client).await
+}
+
+#[tauri::command]
+pub async fn reset_config(
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    info!("Resetting config to defaults");
+    let new_cfg = Config::default().with_secrets_from(&cfg.0.lock().await.clone());
+    set_config(cfg, new_cfg.clone(), app_handle).await?;
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn set_log_level(
+    level: String,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    let mut new_cfg = cfg.0.lock().await.clone();
+    new_cfg.log_level = level;
+    set_config(cfg, new_cfg, app_handle).await
+}
+
+#[tauri::command]
+pub async fn export_config(
+    path: String,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    let exported = ExportedConfig {
+        app_version: app_handle.package_info().version.to_string(),
+        config: cfg.0.lock().await.without_secrets(),
+    };
+
+    let exported_json = serde_json::to_string_pretty(&exported)?;
+    tokio::fs::write(&path, exported_json).await?;
+
+    info!("Exported config to {}", path);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn open_opgg_link(app_handle: AppHandle) -> Result<(), ()> {
-    let lcu_state = app_handle.state::<LCU>();
-    let lcu_state = lcu_state.0.lock().await;
-    let app_client = RESTClient::new(lcu_state.data.clone().unwrap(), false).unwrap();
+pub async fn import_config(
+    path: String,
+    cfg: tauri::State<'_, AppConfig>,
+    app_handle: AppHandle,
+) -> Result<Config, AppError> {
+    let exported_json = tokio::fs::read_to_string(&path).await?;
+    let exported: ExportedConfig = serde_json::from_str(&exported_json)?;
+    info!(
+        "Importing config exported from version {}",
+        exported.app_version
+    );
+
+    let new_cfg = exported
+        .config
+        .with_secrets_from(&cfg.0.lock().await.clone());
+    set_config(cfg, new_cfg.clone(), app_handle.clone()).await?;
+
+    app_handle.emit_all("config_updated", &new_cfg)?;
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn list_profiles(
+    app_handle: AppHandle,
+    profile_state: tauri::State<'_, ManagedProfiles>,
+) -> Result<ProfileList, AppError> {
+    let cfg_folder = config::config_dir(&app_handle)?;
+    let profile_state = profile_state.0.lock().await;
+
+    Ok(ProfileList {
+        active: profile_state.active.clone(),
+        profiles: profiles::list(&cfg_folder, &profile_state.active).await?,
+    })
+}
+
+#[tauri::command]
+pub async fn switch_profile(
+    name: String,
+    app_handle: AppHandle,
+    cfg: tauri::State<'_, AppConfig>,
+    profile_state: tauri::State<'_, ManagedProfiles>,
+) -> Result<Config, AppError> {
+    profiles::validate_name(&name)?;
+    let cfg_folder = config::config_dir(&app_handle)?;
+
+    // Both locks are held for the whole swap so nothing can observe a half-switched profile.
+    let mut profile_state = profile_state.0.lock().await;
+    let mut cfg = cfg.0.lock().await;
+    if profile_state.active == name {
+        return Ok(cfg.clone());
+    }
+
+    let new_cfg = profiles::load(&cfg_folder, &name).await?;
+    profiles::save(&cfg_folder, &profile_state.active, &cfg).await?;
+
+    config::apply_changes(&app_handle, &cfg, &new_cfg).await?;
+    *cfg = new_cfg.clone();
+    config::write_config(&cfg_folder.join("config.json"), &cfg).await?;
+    profiles::set_active(&cfg_folder, &name).await?;
+    profile_state.active = name.clone();
+
+    info!("Switched to profile {}", name);
+    app_handle.emit_all(
+        "profile_switched",
+        ProfileSwitched {
+            name,
+            config: new_cfg.clone(),
+        },
+    )?;
+
+    Ok(new_cfg)
+}
+
+#[tauri::command]
+pub async fn duplicate_profile(
+    source: String,
+    name: String,
+    app_handle: AppHandle,
+    cfg: tauri::State<'_, AppConfig>,
+    profile_state: tauri::State<'_, ManagedProfiles>,
+) -> Result<(), AppError> {
+    profiles::validate_name(&source)?;
+    profiles::validate_name(&name)?;
+    let cfg_folder = config::config_dir(&app_handle)?;
+    let profile_state = profile_state.0.lock().await;
+
+    if name == profile_state.active || profiles::exists(&cfg_folder, &name) {
+        return Err(AppError::InvalidInput(format!(
+            "Profile '{}' already exists",
+            name
+        )));
+    }
+
+    // The active profile's file is only written on switch, so copy from memory instead.
+    let source_cfg = if source == profile_state.active {
+        cfg.0.lock().await.clone()
+    } else {
+        profiles::load(&cfg_folder, &source).await?
+    };
+
+    profiles::save(&cfg_folder, &name, &source_cfg).await
+}
+
+#[tauri::command]
+pub async fn open_opgg_link(app_handle: AppHandle) -> Result<(), AppError> {
+    let app_client = app_client(&app_handle).await?;
 
     let config = app_handle.state::<AppConfig>();
     let config = config.0.lock().await;
 
-    let team = get_lobby_info(&app_client).await;
-    let region_info: RegionInfo = serde_json::from_value(
-        app_client
-            .get("/riotclient/region-locale".to_string())
-            .await
-            .unwrap(),
+    let remoting_client = remoting_client(&app_handle).await?;
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    let region_info = region::get_region_info(&app_client).await?;
+
+    display_champ_select(&team, region_info.multi_search_region(), &config)
+}
+
+/// Formats the current lobby with the configured export format. `multiline` puts each
+/// player on their own line regardless of the configured separator.
+async fn lobby_export_text(app_handle: &AppHandle, multiline: bool) -> Result<String, AppError> {
+    let app_client = app_client(app_handle).await?;
+    let remoting_client = remoting_client(app_handle).await?;
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    if team.participants.is_empty() {
+        return Err(AppError::InvalidInput("No lobby to export".to_string()));
+    }
+
+    let mut format = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .export_format
+        .clone();
+    if multiline {
+        format.separator = "\n".to_string();
+    }
+    let region_info = region::get_region_info(&app_client).await?;
+
+    streamer::remember_lobby(app_handle, &team).await;
+    let text = export::export_lobby(
+        &remoting_client,
+        &team,
+        region_info.multi_search_region(),
+        &format,
+        &i18n::translator(app_handle).await,
     )
-    .unwrap();
+    .await;
+    Ok(streamer::scrub_text(app_handle, &text).await)
+}
 
-    let region = match region_info.web_region.as_str() {
-        "SG2" => "SG",
-        _ => &region_info.web_region,
-    };
+/// Copies the lobby in the configured export format and returns the copied text.
+#[tauri::command]
+pub async fn copy_lobby_to_clipboard(
+    app_handle: AppHandle,
+    multiline: Option<bool>,
+) -> Result<String, AppError> {
+    let text = lobby_export_text(&app_handle, multiline.unwrap_or(false)).await?;
+    app_handle.clipboard_manager().write_text(text.clone())?;
+    info!("Copied the lobby to the clipboard");
 
-    display_champ_select(&team, region, &config.multi_provider);
+    Ok(text)
+}
+
+#[tauri::command]
+pub async fn export_lobby(app_handle: AppHandle, path: String) -> Result<(), AppError> {
+    let text = lobby_export_text(&app_handle, false).await?;
+    tokio::fs::write(&path, text).await?;
 
+    info!("Exported the lobby to {}", path);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_lcu_info(lcu: tauri::State<'_, LCU>) -> Result<LCUClientInfo, ()> {
+pub async fn get_lobby_ranks(app_handle: AppHandle) -> Result<Vec<ParticipantRank>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    Ok(ranked::get_lobby_ranks(&remoting_client, &team).await)
+}
+
+#[tauri::command]
+pub async fn get_lobby_mastery(app_handle: AppHandle) -> Result<Vec<ParticipantMastery>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    let session = app_handle.state::<ManagedChampSelectState>();
+    let session = session.0.lock().await.clone();
+    Ok(mastery::get_lobby_mastery(&remoting_client, &team, session.as_ref()).await)
+}
+
+#[tauri::command]
+pub async fn get_lobby_report(app_handle: AppHandle) -> Result<LobbyReport, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+    let lobby = get_lobby_info(&app_client, &remoting_client).await;
+    let region_info = region::get_region_info(&app_client).await?;
+    let session = app_handle.state::<ManagedChampSelectState>();
+    let session = session.0.lock().await.clone();
+
+    Ok(report::get_lobby_report(
+        &app_handle,
+        &remoting_client,
+        &lobby,
+        session.as_ref(),
+        region_info.multi_search_region(),
+    )
+    .await)
+}
+
+#[tauri::command]
+pub async fn get_participant_matches(
+    puuid: String,
+    count: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<ParticipantMatch>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+    let region_info = region::get_region_info(&app_client).await?;
+
+    match_history::get_participant_matches(
+        &app_handle,
+        &remoting_client,
+        region_info.multi_search_region(),
+        &puuid,
+        count.unwrap_or(PARTICIPANT_MATCHES),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_session_stats(app_handle: AppHandle) -> Result<SessionStats, AppError> {
+    let session = app_handle.state::<ManagedSession>();
+    let session = session.0.lock().await;
+    Ok(session.stats())
+}
+
+#[tauri::command]
+pub async fn get_enemy_team(app_handle: AppHandle) -> Result<EnemyTeam, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    enemy_team::get_enemy_team(&app_handle, &remoting_client).await
+}
+
+/// Everything the game client reports, `None` outside of a game.
+#[tauri::command]
+pub async fn get_live_game(app_handle: AppHandle) -> Result<Option<AllGameData>, AppError> {
+    let live = app_handle.state::<ManagedLiveGame>();
+    let live = live.0.lock().await;
+    Ok(live.data.clone())
+}
+
+#[tauri::command]
+pub async fn get_live_players(app_handle: AppHandle) -> Result<Vec<LivePlayer>, AppError> {
+    let live = app_handle.state::<ManagedLiveGame>();
+    let live = live.0.lock().await;
+    Ok(live
+        .data
+        .as_ref()
+        .map(|d| d.all_players.clone())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_live_events(app_handle: AppHandle) -> Result<Vec<LiveEvent>, AppError> {
+    let live = app_handle.state::<ManagedLiveGame>();
+    let live = live.0.lock().await;
+    Ok(live
+        .data
+        .as_ref()
+        .map(|d| d.events.events.clone())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn detect_premades(app_handle: AppHandle) -> Result<Vec<PremadeGroup>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    Ok(premades::detect_premades(&remoting_client, &team).await)
+}
+
+#[tauri::command]
+pub async fn get_smurf_scores(app_handle: AppHandle) -> Result<Vec<SmurfScore>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    Ok(smurf::get_smurf_scores(&remoting_client, &team).await)
+}
+
+#[tauri::command]
+pub async fn get_autofill_flags(app_handle: AppHandle) -> Result<Vec<AutofillFlag>, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let session = champ_select::get_champ_select_session(&remoting_client).await?;
+    Ok(autofill::get_autofill_flags(&remoting_client, &session).await)
+}
+
+#[tauri::command]
+pub async fn get_win_probability(app_handle: AppHandle) -> Result<WinProbability, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    let session = app_handle.state::<ManagedChampSelectState>();
+    let session = session.0.lock().await.clone();
+    Ok(win_probability::get_win_probability(&remoting_client, &team, session.as_ref()).await)
+}
+
+#[tauri::command]
+pub async fn get_dodge_advice(app_handle: AppHandle) -> Result<DodgeAdvice, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let team = get_lobby_info(&app_client, &remoting_client).await;
+    let session = app_handle.state::<ManagedChampSelectState>();
+    let session = session.0.lock().await.clone();
+    Ok(dodge::get_dodge_advice(&app_handle, &remoting_client, &team, session.as_ref()).await)
+}
+
+#[tauri::command]
+pub async fn scout_clash(app_handle: AppHandle) -> Result<ClashScout, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let scout = clash::scout_opponents(&remoting_client).await?;
+    let region_info = region::get_region_info(&app_client).await?;
+
+    let config = app_handle.state::<AppConfig>();
+    let config = config.0.lock().await;
+
+    let team = Lobby {
+        participants: scout.participants.clone(),
+        is_arena: false,
+        subteams: Vec::new(),
+    };
+    display_champ_select(&team, region_info.multi_search_region(), &config)?;
+
+    Ok(scout)
+}
+
+#[tauri::command]
+pub async fn get_lcu_info(lcu: tauri::State<'_, LCU>) -> Result<LCUClientInfo, AppError> {
     let lcu = lcu.0.lock().await;
-    Ok(lcu.data.clone().unwrap())
+    lcu.data.clone().ok_or(AppError::LcuNotConnected)
 }
 
 #[tauri::command]
-pub async fn dodge(app_handle: AppHandle) {
-    let lcu_state = app_handle.state::<LCU>();
-    let lcu_state = lcu_state.0.lock().await;
-    let remoting_client = RESTClient::new(lcu_state.data.clone().unwrap(), true).unwrap();
-
-    println!("Attempting to quit champ select...");
-    let _resp = remoting_client
-        .post(
-            "/lol-login/v1/session/invoke?destination=lcdsServiceProxy&method=call&args=[\"\",\"teambuilder-draft\",\"quitV2\",\"\"]".to_string(),
-            serde_json::json!({}),
-        )
-        .await
-        .unwrap();
+pub async fn dodge(app_handle: AppHandle, grace_seconds: Option<u32>) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+
+    let (default_grace, allowed_queues) = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        (config.dodge_grace_seconds, config.dodge_queue_ids.clone())
+    };
+    let grace_seconds = grace_seconds.unwrap_or(default_grace);
+
+    dodge::dodge_with_grace(
+        &app_handle,
+        &remoting_client,
+        &allowed_queues,
+        std::time::Duration::from_secs(grace_seconds as u64),
+    )
+    .await
 }
 
 #[tauri::command]
-pub async fn enable_dodge(app_handle: AppHandle) -> Result<(), ()> {
-    let lcu_state = app_handle.state::<LCU>();
-    let lcu_state = lcu_state.0.lock().await;
-    let remoting_client = RESTClient::new(lcu_state.data.clone().unwrap(), true).unwrap();
+pub async fn get_dodge_cost(app_handle: AppHandle) -> Result<DodgeCost, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    dodge::get_dodge_cost(&app_handle, &remoting_client).await
+}
+
+/// Crash reports from earlier runs that haven't been uploaded, so the frontend can ask to
+/// send them.
+#[tauri::command]
+pub async fn get_crash_reports(app_handle: AppHandle) -> Result<Vec<CrashReport>, AppError> {
+    Ok(crash::pending(&app_handle)
+        .into_iter()
+        .map(|(_, report)| report)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_penalties(app_handle: AppHandle) -> Result<Penalties, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    Ok(penalties::get_penalties(&remoting_client).await)
+}
+
+#[tauri::command]
+pub async fn cancel_dodge(app_handle: AppHandle) -> Result<bool, AppError> {
+    Ok(dodge::cancel_dodge(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn cancel_requeue(app_handle: AppHandle) -> Result<bool, AppError> {
+    Ok(requeue::cancel_requeue(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn get_queue_status(app_handle: AppHandle) -> Result<QueueStatus, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    queue::get_queue_status(&remoting_client).await
+}
+
+#[tauri::command]
+pub async fn download_replay(
+    app_handle: AppHandle,
+    game_id: i64,
+) -> Result<ReplayDownloaded, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    replays::download_replay(&app_handle, &remoting_client, game_id).await
+}
+
+#[tauri::command]
+pub async fn spectate(app_handle: AppHandle, puuid: String) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    spectate::spectate(&app_handle, &remoting_client, &puuid).await
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app_handle: AppHandle) -> Result<UpdateInfo, AppError> {
+    updater::check_for_updates(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn install_update(app_handle: AppHandle) -> Result<(), AppError> {
+    updater::install_update(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn get_champion_data(app_handle: AppHandle) -> Result<Vec<ChampionData>, AppError> {
+    Ok(assets::get_assets(&app_handle).await?.champions)
+}
+
+#[tauri::command]
+pub async fn get_champion_skins(
+    app_handle: AppHandle,
+    champion_id: i64,
+) -> Result<Vec<SkinData>, AppError> {
+    let assets = assets::get_assets(&app_handle).await?;
+    Ok(assets.skins_for(champion_id).into_iter().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn get_queues(app_handle: AppHandle) -> Result<Vec<QueueData>, AppError> {
+    Ok(assets::get_assets(&app_handle).await?.queues)
+}
+
+/// Local paths of the rank emblem images, keyed by tier.
+#[tauri::command]
+pub async fn get_rank_emblems(
+    app_handle: AppHandle,
+) -> Result<BTreeMap<String, PathBuf>, AppError> {
+    Ok(assets::get_assets(&app_handle).await?.rank_emblems)
+}
+
+#[tauri::command]
+pub async fn lookup_champion(
+    app_handle: AppHandle,
+    champion_id: i64,
+) -> Result<Option<ChampionData>, AppError> {
+    let assets = assets::get_assets(&app_handle).await?;
+    Ok(assets.champion(champion_id).cloned())
+}
+
+#[tauri::command]
+pub async fn search_champion(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<Vec<ChampionData>, AppError> {
+    let assets = assets::get_assets(&app_handle).await?;
+    Ok(assets
+        .search_champions(&name)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_champion_stat_links(
+    app_handle: AppHandle,
+) -> Result<Vec<ChampionStatLink>, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let session = champ_select::get_champ_select_session(&remoting_client).await?;
+    lolalytics::get_champion_stat_links(&app_handle, &session).await
+}
+
+#[tauri::command]
+pub async fn get_champ_select_session(
+    app_handle: AppHandle,
+) -> Result<ChampSelectSession, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    champ_select::get_champ_select_session(&remoting_client).await
+}
+
+#[tauri::command]
+pub async fn reroll_champion(app_handle: AppHandle) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let session = champ_select::get_champ_select_session(&remoting_client).await?;
+    if !session.allow_rerolling || session.rerolls_remaining <= 0 {
+        return Err(AppError::InvalidInput("No rerolls remaining".to_string()));
+    }
+
+    info!("Rerolling champion");
+    champ_select::reroll(&remoting_client).await
+}
+
+/// Mutes or unmutes every teammate in champ select.
+#[tauri::command]
+pub async fn set_all_muted(
+    muted: bool,
+    app_handle: AppHandle,
+) -> Result<Vec<MutedPlayer>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+    mute::set_muted(&app_client, &remoting_client, None, muted).await
+}
+
+#[tauri::command]
+pub async fn set_players_muted(
+    puuids: Vec<String>,
+    muted: bool,
+    app_handle: AppHandle,
+) -> Result<Vec<MutedPlayer>, AppError> {
+    let app_client = app_client(&app_handle).await?;
+    let remoting_client = remoting_client(&app_handle).await?;
+    mute::set_muted(&app_client, &remoting_client, Some(&puuids), muted).await
+}
+
+/// Accepts or declines an incoming champion trade or pick order swap.
+#[tauri::command]
+pub async fn respond_to_trade(
+    kind: RequestKind,
+    id: i64,
+    accept: bool,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let session = champ_select::get_champ_select_session(&remoting_client).await?;
+    let state = match kind {
+        RequestKind::Trade => session
+            .trades
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.state.as_str()),
+        RequestKind::PickOrderSwap => session
+            .pick_order_swaps
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.state.as_str()),
+    };
+    if state != Some("RECEIVED") {
+        return Err(AppError::InvalidInput(format!("No pending request {}", id)));
+    }
+
+    info!(
+        "{} {:?} {}",
+        if accept { "Accepting" } else { "Declining" },
+        kind,
+        id
+    );
+    trades::respond(&remoting_client, kind, id, accept).await
+}
+
+#[tauri::command]
+pub async fn swap_bench_champion(app_handle: AppHandle, champion_id: i64) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let session = champ_select::get_champ_select_session(&remoting_client).await?;
+    if !session
+        .bench_champions
+        .iter()
+        .any(|b| b.champion_id == champion_id)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Champion {} is not on the bench",
+            champion_id
+        )));
+    }
+
+    info!("Swapping with bench champion {}", champion_id);
+    champ_select::swap_with_bench(&remoting_client, champion_id).await
+}
+
+#[tauri::command]
+pub async fn enable_dodge(app_handle: AppHandle) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    // Read before locking the dodge state, champ select start takes them the other way round.
+    let allowed_queues = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .dodge_queue_ids
+        .clone();
 
     let dodge_state = app_handle.state::<ManagedDodgeState>();
     let mut dodge_state = dodge_state.0.lock().await;
 
     if dodge_state.enabled.is_some() {
         dodge_state.enabled = None;
+        dodge::save_state(&app_handle, &dodge_state);
         return Ok(());
     }
 
-    let champ_select = serde_json::from_value::<ChampSelectSession>(
-        remoting_client
-            .get("/lol-champ-select/v1/session".to_string())
-            .await
-            .unwrap(),
-    )
-    .unwrap();
+    dodge::check_queue(&remoting_client, &allowed_queues).await?;
+    let champ_select = champ_select::get_champ_select_session(&remoting_client).await?;
 
     dodge_state.enabled = Some(champ_select.game_id);
+    dodge::save_state(&app_handle, &dodge_state);
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
-struct MCPRequest {
-    jsonrpc: String,
-    id: u64,
-    method: String,
-    params: MCPParams,
+#[tauri::command]
+pub async fn call_opgg_api(
+    app_handle: AppHandle,
+    function_name: String,
+    params: Value,
+) -> Result<Value, AppError> {
+    opgg::call_tool_cached(&app_handle, &function_name, params).await
 }
 
-#[derive(Serialize, Deserialize)]
-struct MCPParams {
-    name: String,
-    arguments: Value,
-}
-
-#[derive(Serialize, Deserialize)]
-struct MCPResponse {
-    jsonrpc: String,
-    id: u64,
-    result: Option<Value>,
-    error: Option<Value>,
-}
-
-#[tauri::command]
-pub async fn call_opgg_api(function_name: String, params: Value) -> Result<Value, String> {
-    let client = reqwest::Client::new();
-    let mcp_url = "https://mcp-api.op.gg/mcp";
-    
-    let request_id = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_nanos() as u64;
-
-    let request = MCPRequest {
-        jsonrpc: "2.0".to_string(),
-        id: request_id,
-        method: "tools/call".to_string(),
-        params: MCPParams {
-            name: function_name.clone(),
-            arguments: params,
-        },
-    };
-
-    println!("Calling OP.GG API function: {} with params: {:?}", function_name, request.params.arguments);
+/// Calls the same OP.GG tool once per argument set, for looking up a whole lobby at once.
+#[tauri::command]
+pub async fn call_opgg_api_batch(
+    app_handle: AppHandle,
+    function_name: String,
+    arguments: Vec<Value>,
+) -> Result<Vec<BatchResult>, AppError> {
+    let results = opgg::call_tool_batch(&app_handle, &function_name, arguments).await;
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    if failed > 0 {
+        warn!(
+            "{} of {} {} calls failed",
+            failed,
+            results.len(),
+            function_name
+        );
+    }
+    Ok(results)
+}
 
-    match client.post(mcp_url)
-        .json(&request)
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-    {
-        Ok(response) => {
-            match response.json::<MCPResponse>().await {
-                Ok(mcp_response) => {
-                    if let Some(error) = mcp_response.error {
-                        println!("OP.GG API error: {:?}", error);
-                        Err(format!("OP.GG API error: {:?}", error))
-                    } else if let Some(result) = mcp_response.result {
-                        println!("OP.GG API success: {:?}", result);
-                        Ok(result)
-                    } else {
-                        Err("No result or error from OP.GG API".to_string())
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to parse OP.GG API response: {:?}", e);
-                    Err(format!("Failed to parse response: {:?}", e))
-                }
-            }
-        }
-        Err(e) => {
-            println!("Failed to call OP.GG API: {:?}", e);
-            Err(format!("Network error: {:?}", e))
+/// Lists OP.GG's MCP tools, cached after the first call unless `refresh` is set.
+#[tauri::command]
+pub async fn list_opgg_tools(
+    app_handle: AppHandle,
+    refresh: Option<bool>,
+) -> Result<Vec<McpTool>, AppError> {
+    let opgg = app_handle.state::<ManagedOpgg>();
+    if !refresh.unwrap_or(false) {
+        if let Some(cached) = opgg.0.lock().await.tools.clone() {
+            return Ok(cached);
         }
     }
+
+    let fetched = opgg::list_tools(&app_handle).await?;
+    info!("OP.GG offers {} MCP tools", fetched.len());
+    opgg.0.lock().await.tools = Some(fetched.clone());
+    Ok(fetched)
+}
+
+#[tauri::command]
+pub async fn get_encounters(
+    puuid: String,
+    app_handle: AppHandle,
+) -> Result<Vec<Encounter>, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.get_encounters(&puuid)
+}
+
+/// Champ select chat messages recorded from the player, newest first.
+#[tauri::command]
+pub async fn get_player_chat(
+    puuid: String,
+    app_handle: AppHandle,
+) -> Result<Vec<ChatMessage>, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.get_chat_messages(&puuid)
+}
+
+#[tauri::command]
+pub async fn add_player_note(
+    puuid: String,
+    note: String,
+    app_handle: AppHandle,
+) -> Result<PlayerAnnotations, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.add_player_note(&puuid, &note)?;
+    storage.get_player_annotations(&puuid)
+}
+
+#[tauri::command]
+pub async fn set_player_tags(
+    puuid: String,
+    tags: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<PlayerAnnotations, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let mut storage = storage.0.lock().await;
+    storage.set_player_tags(&puuid, &tags)?;
+    storage.get_player_annotations(&puuid)
+}
+
+#[tauri::command]
+pub async fn get_player_annotations(
+    puuid: String,
+    app_handle: AppHandle,
+) -> Result<PlayerAnnotations, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.get_player_annotations(&puuid)
+}
+
+#[tauri::command]
+pub async fn add_to_blocklist(
+    puuid: Option<String>,
+    riot_id: Option<String>,
+    reason: String,
+    app_handle: AppHandle,
+) -> Result<BlockedPlayer, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    let entry = storage.add_to_blocklist(puuid.as_deref(), riot_id.as_deref(), &reason)?;
+    info!("Added blocklist entry {}", entry.id);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn remove_from_blocklist(
+    id: i64,
+    app_handle: AppHandle,
+) -> Result<Vec<BlockedPlayer>, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.remove_from_blocklist(id)?;
+    storage.get_blocklist()
+}
+
+#[tauri::command]
+pub async fn get_blocklist(app_handle: AppHandle) -> Result<Vec<BlockedPlayer>, AppError> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage.get_blocklist()
+}
+
+#[tauri::command]
+pub async fn list_presets(
+    presets: tauri::State<'_, ManagedPresets>,
+) -> Result<Vec<Preset>, AppError> {
+    Ok(presets.0.lock().await.values().cloned().collect())
+}
+
+/// Creates or replaces the preset for `preset.champion_id`.
+#[tauri::command]
+pub async fn save_preset(
+    preset: Preset,
+    app_handle: AppHandle,
+    presets: tauri::State<'_, ManagedPresets>,
+) -> Result<Vec<Preset>, AppError> {
+    preset.validate()?;
+    let cfg_folder = config::config_dir(&app_handle)?;
+    let mut presets = presets.0.lock().await;
+    presets.insert(preset.champion_id, preset);
+    presets::save(&cfg_folder, &presets).await?;
+    Ok(presets.values().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn delete_preset(
+    champion_id: i64,
+    app_handle: AppHandle,
+    presets: tauri::State<'_, ManagedPresets>,
+) -> Result<Vec<Preset>, AppError> {
+    let cfg_folder = config::config_dir(&app_handle)?;
+    let mut presets = presets.0.lock().await;
+    if presets.remove(&champion_id).is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "No preset for champion {}",
+            champion_id
+        )));
+    }
+    presets::save(&cfg_folder, &presets).await?;
+    Ok(presets.values().cloned().collect())
+}
+
+/// Applies a preset in champ select, by default the one for the champion we have picked.
+#[tauri::command]
+pub async fn apply_preset(
+    champion_id: Option<i64>,
+    app_handle: AppHandle,
+) -> Result<PresetApplied, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let champion_id = match champion_id {
+        Some(champion_id) => champion_id,
+        None => champ_select::get_champ_select_session(&remoting_client)
+            .await?
+            .local_player()
+            .map(|p| p.champion_id)
+            .filter(|id| *id != 0)
+            .ok_or_else(|| AppError::InvalidInput("No champion picked".to_string()))?,
+    };
+
+    let preset = app_handle
+        .state::<ManagedPresets>()
+        .0
+        .lock()
+        .await
+        .get(&champion_id)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("No preset for champion {}", champion_id)))?;
+    presets::apply(&app_handle, &remoting_client, &preset).await
+}
+
+/// Selects a random owned skin for the champion we have picked, honoring the exclusions in
+/// `random_skin` even when it is disabled.
+#[tauri::command]
+pub async fn randomize_skin(
+    app_handle: AppHandle,
+    cfg: tauri::State<'_, AppConfig>,
+) -> Result<SkinSelected, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    let champion_id = champ_select::get_champ_select_session(&remoting_client)
+        .await?
+        .local_player()
+        .map(|p| p.champion_id)
+        .filter(|id| *id != 0)
+        .ok_or_else(|| AppError::InvalidInput("No champion picked".to_string()))?;
+
+    let config = cfg.0.lock().await.random_skin.clone();
+    skins::select_random_skin(&app_handle, &remoting_client, &config, champion_id).await
+}
+
+/// The friend list with each friend's activity, online friends first.
+#[tauri::command]
+pub async fn get_friends(app_handle: AppHandle) -> Result<Vec<FriendStatus>, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    friends::refresh(&app_handle, &remoting_client).await
+}
+
+#[tauri::command]
+pub async fn get_invitations(app_handle: AppHandle) -> Result<Vec<Invitation>, AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    invites::get_invitations(&remoting_client).await
+}
+
+#[tauri::command]
+pub async fn respond_to_invite(
+    invitation_id: String,
+    accept: bool,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    let remoting_client = remoting_client(&app_handle).await?;
+    info!(
+        "{} invite {}",
+        if accept { "Accepting" } else { "Declining" },
+        invitation_id
+    );
+    invites::respond(&remoting_client, &invitation_id, accept).await
 }
@@ -0,0 +1,1368 @@
+use crate::{
+    api, audio, autostart, crash, error::AppError, hotkeys, i18n, obs, opgg, streamer, tray,
+    AppConfig, ManagedLogging,
+};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+use tracing_subscriber::EnvFilter;
+
+pub const PROVIDERS: [&str; 8] = [
+    "opgg",
+    "deeplol",
+    "ugg",
+    "tracker",
+    "porofessor",
+    "mobalytics",
+    "lolalytics",
+    "leagueofgraphs",
+];
+
+/// Built-in providers with a profile page per player, see `utils::create_profile_link`.
+pub const PROFILE_PROVIDERS: [&str; 7] = [
+    "opgg",
+    "deeplol",
+    "ugg",
+    "tracker",
+    "porofessor",
+    "mobalytics",
+    "leagueofgraphs",
+];
+
+/// `recommend` only notifies, `auto` also arms the last-second dodge.
+pub const RULE_MODES: [&str; 3] = ["off", "recommend", "auto"];
+
+/// Where imported rune pages and item sets come from.
+pub const BUILD_SOURCES: [&str; 2] = ["opgg", "url"];
+
+/// What to do with the honor ballot after a game.
+pub const HONOR_MODES: [&str; 4] = ["off", "skip", "highestVision", "highestKda"];
+
+/// Assigned positions as reported by champ select, plus `default` for queues without roles.
+pub const ROLES: [&str; 6] = ["top", "jungle", "middle", "bottom", "utility", "default"];
+
+/// Follows the League Client's locale.
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// Languages with a locale file, see `i18n`.
+pub const LANGUAGES: [&str; 4] = [AUTO_LANGUAGE, "en", "es", "fr"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    pub auto_open: bool,
+    /// Open the multi-search for the enemy team once the game starts.
+    #[serde(default)]
+    pub auto_open_enemies: bool,
+    /// Emit alerts in game when a surrender or remake vote can be called.
+    #[serde(default)]
+    pub vote_alerts: bool,
+    /// Show an always on top window with the lobby report and dodge countdown in champ select.
+    #[serde(default)]
+    pub overlay: bool,
+    /// Start minimized to the tray when the OS starts.
+    #[serde(default)]
+    pub launch_on_startup: bool,
+    /// Replace teammate names in events, exports and the overlay, and don't open links that
+    /// contain them.
+    #[serde(default)]
+    pub streamer_mode: bool,
+    pub auto_accept: bool,
+    pub accept_delay: u32,
+    /// Accept incoming pick order swaps that move our pick earlier.
+    #[serde(default)]
+    pub auto_accept_earlier_swaps: bool,
+    #[serde(default = "default_provider")]
+    pub multi_provider: String,
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Language of notifications, announcements, exports and chat messages we write.
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub auto_pick: AutoPickConfig,
+    #[serde(default)]
+    pub auto_ban: AutoBanConfig,
+    /// Seconds before the end of a champ select phase to emit a warning, 0 to disable.
+    #[serde(default = "default_timer_warning_seconds")]
+    pub timer_warning_seconds: u32,
+    /// Arm the last-second dodge when a blocklisted player shows up in champ select.
+    #[serde(default)]
+    pub auto_dodge_blocklisted: bool,
+    /// Seconds before finalization ends to fire an armed dodge.
+    #[serde(default = "default_dodge_lead_seconds")]
+    pub dodge_lead_seconds: u32,
+    /// Grace period before a manual dodge goes through, 0 to dodge immediately.
+    #[serde(default)]
+    pub dodge_grace_seconds: u32,
+    #[serde(default)]
+    pub dodge_rules: DodgeRulesConfig,
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
+    /// Mute players in champ select that carry any of these tags in our notes.
+    #[serde(default)]
+    pub auto_mute_tags: Vec<String>,
+    /// Queue ids the dodge features work in. Empty allows every queue but Clash and customs.
+    #[serde(default)]
+    pub dodge_queue_ids: Vec<i64>,
+    /// Extra providers selectable through `multi_provider` by their id.
+    #[serde(default)]
+    pub custom_providers: Vec<CustomProvider>,
+    /// Built-in providers that open one profile page per player instead of a multi-search.
+    #[serde(default)]
+    pub per_player_providers: Vec<String>,
+    #[serde(default)]
+    pub export_format: ExportFormat,
+    #[serde(default)]
+    pub browser: BrowserConfig,
+    #[serde(default)]
+    pub replays: ReplayConfig,
+    #[serde(default)]
+    pub crash_reports: CrashReportConfig,
+    #[serde(default)]
+    pub local_api: LocalApiConfig,
+    #[serde(default)]
+    pub obs: ObsConfig,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub sounds: SoundConfig,
+    #[serde(default)]
+    pub speech: SpeechConfig,
+    #[serde(default)]
+    pub opgg_cache: OpggCacheConfig,
+    #[serde(default)]
+    pub opgg_mcp: McpConfig,
+    #[serde(default)]
+    pub riot_api: RiotApiConfig,
+    #[serde(default)]
+    pub honor: HonorConfig,
+    #[serde(default)]
+    pub runes: RuneConfig,
+    #[serde(default)]
+    pub spells: SpellConfig,
+    #[serde(default)]
+    pub item_sets: ItemSetConfig,
+    #[serde(default)]
+    pub random_skin: RandomSkinConfig,
+    #[serde(default)]
+    pub lane_intent: LaneIntentConfig,
+    #[serde(default)]
+    pub invites: InviteConfig,
+    #[serde(default)]
+    pub requeue: RequeueConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoPickConfig {
+    pub enabled: bool,
+    /// Only declare the pick intent and never lock it in.
+    pub hover_only: bool,
+    /// Champion ids in priority order, keyed by assigned position.
+    pub champions: BTreeMap<String, Vec<i64>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBanConfig {
+    pub enabled: bool,
+    /// Champion ids in priority order, keyed by assigned position.
+    pub champions: BTreeMap<String, Vec<i64>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeRulesConfig {
+    pub mode: String,
+    pub rules: Vec<DodgeRule>,
+}
+
+/// A rule fires when all of its conditions hold.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeRule {
+    pub name: String,
+    pub conditions: Vec<RuleCondition>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RuleCondition {
+    #[serde(rename_all = "camelCase")]
+    Autofills {
+        at_least: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    AverageWinrateBelow {
+        percent: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    PlayerWinrateBelow {
+        percent: f64,
+        min_games: i64,
+    },
+    Blocklisted,
+    #[serde(rename_all = "camelCase")]
+    SmurfScoreAbove {
+        score: u8,
+    },
+    #[serde(rename_all = "camelCase")]
+    WinProbabilityBelow {
+        percent: f64,
+    },
+}
+
+/// A stat site described by a URL template. `{region}` is replaced with the web region,
+/// `{names_csv}` with every Riot ID joined by commas and `{riot_id}` with a single Riot ID,
+/// which opens one page per player.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomProvider {
+    pub id: String,
+    pub name: String,
+    pub url_template: String,
+}
+
+/// How lobbies look when copied or exported.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportFormat {
+    pub separator: String,
+    /// Prefix the list with the region, like `[EUW]`.
+    pub include_region: bool,
+    /// Add each player's solo queue rank after their name.
+    pub include_rank: bool,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        ExportFormat {
+            separator: ", ".to_string(),
+            include_region: false,
+            include_rank: false,
+        }
+    }
+}
+
+/// Which browser opens provider links. An empty path uses the system default, which can't
+/// be asked for a private window.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BrowserConfig {
+    pub path: String,
+    pub private: bool,
+}
+
+/// Sounds per event: empty is silent, `default` plays the built-in tone and anything else is
+/// the path of an audio file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SoundConfig {
+    /// From 0.0 to 1.0.
+    pub volume: f32,
+    pub queue_pop: String,
+    pub champ_select_start: String,
+    /// Ticks every second for the last seconds before a scheduled dodge.
+    pub dodge_countdown: String,
+    pub blocklisted: String,
+    pub vote: String,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        SoundConfig {
+            volume: 0.8,
+            queue_pop: String::new(),
+            champ_select_start: String::new(),
+            dodge_countdown: String::new(),
+            blocklisted: String::new(),
+            vote: String::new(),
+        }
+    }
+}
+
+impl SoundConfig {
+    fn files(&self) -> [(&str, &str); 5] {
+        [
+            ("sounds.queuePop", &self.queue_pop),
+            ("sounds.champSelectStart", &self.champ_select_start),
+            ("sounds.dodgeCountdown", &self.dodge_countdown),
+            ("sounds.blocklisted", &self.blocklisted),
+            ("sounds.vote", &self.vote),
+        ]
+    }
+}
+
+/// Which events are announced with text to speech.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechConfig {
+    pub queue_pop: bool,
+    /// Announces how many teammates look autofilled.
+    pub autofills: bool,
+    pub dodge_countdown: bool,
+}
+
+/// Which events show a native notification.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationConfig {
+    pub blocklisted: bool,
+    pub queue_pop: bool,
+    pub dodge_executed: bool,
+    pub lobby_report: bool,
+    pub vote: bool,
+}
+
+/// Where downloaded replays are copied. Empty leaves them in the client's replay folder.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplayConfig {
+    pub output_folder: String,
+}
+
+/// Crash reports are always written locally, and only sent to `url` after opting in.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportConfig {
+    pub upload: bool,
+    pub url: String,
+}
+
+/// The localhost REST API for external tools, see `api`.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Expected as a bearer token on every request.
+    pub token: String,
+}
+
+impl Default for LocalApiConfig {
+    fn default() -> Self {
+        LocalApiConfig {
+            enabled: false,
+            port: 4765,
+            token: String::new(),
+        }
+    }
+}
+
+/// The localhost WebSocket and browser source for OBS, see `obs`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObsConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        ObsConfig {
+            enabled: false,
+            port: 4766,
+        }
+    }
+}
+
+/// How long OP.GG results are reused, in seconds. 0 disables caching.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpggCacheConfig {
+    pub default_seconds: u64,
+    /// Overrides keyed by tool name.
+    pub tools: BTreeMap<String, u64>,
+}
+
+impl Default for OpggCacheConfig {
+    fn default() -> Self {
+        OpggCacheConfig {
+            default_seconds: 300,
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl OpggCacheConfig {
+    pub fn ttl(&self, tool: &str) -> Duration {
+        let seconds = self
+            .tools
+            .get(tool)
+            .copied()
+            .unwrap_or(self.default_seconds);
+        Duration::from_secs(seconds)
+    }
+}
+
+/// Where OP.GG MCP requests go, for self-hosted or authenticated deployments.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpConfig {
+    pub url: String,
+    /// Sent as a bearer token when set.
+    pub api_key: String,
+    /// Extra headers sent with every request.
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        McpConfig {
+            url: "https://mcp-api.op.gg/mcp".to_string(),
+            api_key: String::new(),
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+/// A personal key from the Riot developer portal, used where the LCU falls short.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RiotApiConfig {
+    pub api_key: String,
+}
+
+/// Stands in for a secret in `Debug` output, since configs get logged.
+fn redacted(secret: &str) -> &'static str {
+    if secret.is_empty() {
+        ""
+    } else {
+        "<redacted>"
+    }
+}
+
+impl fmt::Debug for LocalApiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalApiConfig")
+            .field("enabled", &self.enabled)
+            .field("port", &self.port)
+            .field("token", &redacted(&self.token))
+            .finish()
+    }
+}
+
+impl fmt::Debug for McpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("McpConfig")
+            .field("url", &self.url)
+            .field("api_key", &redacted(&self.api_key))
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl fmt::Debug for RiotApiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RiotApiConfig")
+            .field("api_key", &redacted(&self.api_key))
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HonorConfig {
+    pub mode: String,
+}
+
+impl Default for HonorConfig {
+    fn default() -> Self {
+        HonorConfig {
+            mode: "off".to_string(),
+        }
+    }
+}
+
+/// Rune import for the champion we lock in. The `url` source fetches `url_template` with
+/// `{champion}`, `{champion_id}` and `{position}` filled in and expects a page in the LCU's
+/// shape back.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuneConfig {
+    pub enabled: bool,
+    pub source: String,
+    pub url_template: String,
+    /// The page we own and overwrite, other pages are left alone.
+    pub page_name: String,
+}
+
+impl Default for RuneConfig {
+    fn default() -> Self {
+        RuneConfig {
+            enabled: false,
+            source: "opgg".to_string(),
+            url_template: String::new(),
+            page_name: "iSeeUAll".to_string(),
+        }
+    }
+}
+
+/// Item set import for the champion we lock in, from the same kind of sources as runes. A
+/// `url` source may answer with LCU item set `blocks` directly.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ItemSetConfig {
+    pub enabled: bool,
+    pub source: String,
+    pub url_template: String,
+    /// Prepended to the champion and position in the set's title.
+    pub title_prefix: String,
+    /// Removes the sets generated for other champions before writing a new one.
+    pub clear_previous: bool,
+}
+
+impl Default for ItemSetConfig {
+    fn default() -> Self {
+        ItemSetConfig {
+            enabled: false,
+            source: "opgg".to_string(),
+            url_template: String::new(),
+            title_prefix: "iSeeUAll".to_string(),
+            clear_previous: true,
+        }
+    }
+}
+
+/// Play again, and optionally queue up, once the end of game screen shows.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequeueConfig {
+    pub enabled: bool,
+    /// Seconds to wait first, during which the requeue can be cancelled.
+    pub delay_seconds: u32,
+    /// Start the queue search too, instead of stopping in the lobby.
+    pub start_queue: bool,
+}
+
+impl Default for RequeueConfig {
+    fn default() -> Self {
+        RequeueConfig {
+            enabled: false,
+            delay_seconds: 10,
+            start_queue: true,
+        }
+    }
+}
+
+/// Rules for lobby invites, matched against the tags in our notes. Declining wins when a
+/// sender matches both lists.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InviteConfig {
+    /// Accept invites from friends carrying any of these tags.
+    pub auto_accept_tags: Vec<String>,
+    /// Decline invites from anyone carrying any of these tags.
+    pub auto_decline_tags: Vec<String>,
+}
+
+/// Message sent to the champ select chat when champ select starts. `{position}` is replaced
+/// with our assigned position.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LaneIntentConfig {
+    pub enabled: bool,
+    pub message: String,
+    /// Wait before sending, so the message doesn't land before everyone has joined.
+    pub delay_ms: u32,
+}
+
+impl Default for LaneIntentConfig {
+    fn default() -> Self {
+        LaneIntentConfig {
+            enabled: false,
+            message: "{position} preferred, can fill".to_string(),
+            delay_ms: 3000,
+        }
+    }
+}
+
+/// Random skin selection for the champion we lock in, from the skins we own.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RandomSkinConfig {
+    pub enabled: bool,
+    pub include_chromas: bool,
+    /// Skin and chroma ids never to pick, keyed by champion id.
+    pub excluded: BTreeMap<i64, Vec<i64>>,
+}
+
+/// Summoner spells set when we lock in, as pairs of spell ids.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpellConfig {
+    pub enabled: bool,
+    /// Keyed by assigned position.
+    pub positions: BTreeMap<String, Vec<i64>>,
+    /// Keyed by champion id, takes precedence over the position.
+    pub champions: BTreeMap<i64, Vec<i64>>,
+    pub counters: Vec<SpellCounter>,
+    /// `d` or `f` to keep Flash on that key, empty to leave the order alone.
+    pub flash_key: String,
+}
+
+/// Takes `spell_id` instead of our non-Flash spell when any of the enemy champions is picked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellCounter {
+    pub enemy_champion_ids: Vec<i64>,
+    pub spell_id: i64,
+}
+
+/// Global shortcuts as Tauri accelerators like `CmdOrCtrl+Shift+D`, empty to leave unbound.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyConfig {
+    pub dodge: String,
+    pub enable_dodge: String,
+    pub open_opgg_link: String,
+    pub cancel_dodge: String,
+    /// Further shortcuts for any of the actions `hotkeys::HotkeyAction::from_name` knows.
+    pub custom: Vec<HotkeyBinding>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyBinding {
+    pub accelerator: String,
+    /// e.g. `copyLobby`, `muteAll` or `toggleAutoAccept`.
+    pub action: String,
+}
+
+impl HotkeyConfig {
+    fn bindings(&self) -> Vec<(&str, &str)> {
+        let mut bindings = vec![
+            ("hotkeys.dodge", self.dodge.as_str()),
+            ("hotkeys.enableDodge", self.enable_dodge.as_str()),
+            ("hotkeys.openOpggLink", self.open_opgg_link.as_str()),
+            ("hotkeys.cancelDodge", self.cancel_dodge.as_str()),
+        ];
+        bindings.extend(
+            self.custom
+                .iter()
+                .map(|b| ("hotkeys.custom", b.accelerator.as_str())),
+        );
+        bindings
+    }
+}
+
+impl Default for DodgeRulesConfig {
+    fn default() -> Self {
+        DodgeRulesConfig {
+            mode: "off".to_string(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Default for AutoPickConfig {
+    fn default() -> Self {
+        AutoPickConfig {
+            enabled: false,
+            hover_only: true,
+            champions: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            auto_open: true,
+            auto_open_enemies: false,
+            vote_alerts: false,
+            overlay: false,
+            launch_on_startup: false,
+            streamer_mode: false,
+            auto_accept: false,
+            accept_delay: 2000,
+            auto_accept_earlier_swaps: false,
+            multi_provider: default_provider(),
+            log_level: default_log_level(),
+            language: default_language(),
+            auto_pick: AutoPickConfig::default(),
+            auto_ban: AutoBanConfig::default(),
+            timer_warning_seconds: default_timer_warning_seconds(),
+            auto_dodge_blocklisted: false,
+            dodge_lead_seconds: default_dodge_lead_seconds(),
+            dodge_grace_seconds: 0,
+            dodge_rules: DodgeRulesConfig::default(),
+            hotkeys: HotkeyConfig::default(),
+            auto_mute_tags: Vec::new(),
+            dodge_queue_ids: Vec::new(),
+            custom_providers: Vec::new(),
+            per_player_providers: Vec::new(),
+            export_format: ExportFormat::default(),
+            browser: BrowserConfig::default(),
+            replays: ReplayConfig::default(),
+            crash_reports: CrashReportConfig::default(),
+            local_api: LocalApiConfig::default(),
+            obs: ObsConfig::default(),
+            notifications: NotificationConfig::default(),
+            sounds: SoundConfig::default(),
+            speech: SpeechConfig::default(),
+            opgg_cache: OpggCacheConfig::default(),
+            opgg_mcp: McpConfig::default(),
+            riot_api: RiotApiConfig::default(),
+            honor: HonorConfig::default(),
+            runes: RuneConfig::default(),
+            spells: SpellConfig::default(),
+            item_sets: ItemSetConfig::default(),
+            random_skin: RandomSkinConfig::default(),
+            lane_intent: LaneIntentConfig::default(),
+            invites: InviteConfig::default(),
+            requeue: RequeueConfig::default(),
+        }
+    }
+}
+
+fn default_provider() -> String {
+    "opgg".to_string()
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_language() -> String {
+    AUTO_LANGUAGE.to_string()
+}
+
+fn default_timer_warning_seconds() -> u32 {
+    5
+}
+
+fn default_dodge_lead_seconds() -> u32 {
+    1
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        ConfigIssue {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Config {
+    /// Checks every field and returns all problems at once, so the frontend can highlight
+    /// each invalid setting instead of failing on the first one.
+    pub fn validate(&self) -> Result<(), Vec<ConfigIssue>> {
+        let mut issues = Vec::new();
+
+        // The ready check lasts 10 seconds and we subtract a second before accepting.
+        if !(1000..=10000).contains(&self.accept_delay) {
+            issues.push(ConfigIssue::new(
+                "acceptDelay",
+                "Accept delay must be between 1000 and 10000 ms",
+            ));
+        }
+
+        validate_custom_providers(&self.custom_providers, &mut issues);
+
+        if !(0.0..=1.0).contains(&self.sounds.volume) {
+            issues.push(ConfigIssue::new(
+                "sounds.volume",
+                "Volume must be between 0 and 1",
+            ));
+        }
+        for (field, file) in self.sounds.files() {
+            if !file.is_empty() && file != audio::DEFAULT_SOUND && !Path::new(file).is_file() {
+                issues.push(ConfigIssue::new(
+                    field,
+                    format!("Sound file '{}' does not exist", file),
+                ));
+            }
+        }
+
+        let custom = self
+            .custom_providers
+            .iter()
+            .any(|p| p.id == self.multi_provider);
+        if !custom && !PROVIDERS.contains(&self.multi_provider.as_str()) {
+            issues.push(ConfigIssue::new(
+                "multiProvider",
+                format!("Unknown provider '{}'", self.multi_provider),
+            ));
+        }
+
+        for provider in &self.per_player_providers {
+            if !PROFILE_PROVIDERS.contains(&provider.as_str()) {
+                issues.push(ConfigIssue::new(
+                    "perPlayerProviders",
+                    format!("'{}' has no profile pages", provider),
+                ));
+            }
+        }
+
+        if self.browser.private && self.browser.path.is_empty() {
+            issues.push(ConfigIssue::new(
+                "browser.private",
+                "Private windows need a browser path",
+            ));
+        } else if !self.browser.path.is_empty() && !Path::new(&self.browser.path).is_file() {
+            issues.push(ConfigIssue::new(
+                "browser.path",
+                format!("Browser not found at '{}'", self.browser.path),
+            ));
+        }
+
+        let url = &self.crash_reports.url;
+        if self.crash_reports.upload && !url.starts_with("https://") && !url.starts_with("http://")
+        {
+            issues.push(ConfigIssue::new(
+                "crashReports.url",
+                "Crash report uploads need an http(s) URL",
+            ));
+        }
+
+        if self.local_api.enabled {
+            if self.local_api.port < 1024 {
+                issues.push(ConfigIssue::new(
+                    "localApi.port",
+                    "Port must be between 1024 and 65535",
+                ));
+            }
+            if self.local_api.token.len() < 16 {
+                issues.push(ConfigIssue::new(
+                    "localApi.token",
+                    "Token must be at least 16 characters",
+                ));
+            }
+        }
+
+        if self.obs.enabled {
+            if self.obs.port < 1024 {
+                issues.push(ConfigIssue::new(
+                    "obs.port",
+                    "Port must be between 1024 and 65535",
+                ));
+            }
+            if self.local_api.enabled && self.obs.port == self.local_api.port {
+                issues.push(ConfigIssue::new(
+                    "obs.port",
+                    "Port is already used by the local API",
+                ));
+            }
+        }
+
+        let url = &self.opgg_mcp.url;
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            issues.push(ConfigIssue::new(
+                "opggMcp.url",
+                "MCP endpoint must be an http(s) URL",
+            ));
+        }
+
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            issues.push(ConfigIssue::new(
+                "language",
+                format!("Unknown language '{}'", self.language),
+            ));
+        }
+
+        if !HONOR_MODES.contains(&self.honor.mode.as_str()) {
+            issues.push(ConfigIssue::new(
+                "honor.mode",
+                format!("Unknown honor mode '{}'", self.honor.mode),
+            ));
+        }
+
+        if !BUILD_SOURCES.contains(&self.runes.source.as_str()) {
+            issues.push(ConfigIssue::new(
+                "runes.source",
+                format!("Unknown rune source '{}'", self.runes.source),
+            ));
+        } else if self.runes.source == "url" && !self.runes.url_template.starts_with("http") {
+            issues.push(ConfigIssue::new(
+                "runes.urlTemplate",
+                "Rune source URL must be an http(s) URL",
+            ));
+        }
+        if self.runes.page_name.trim().is_empty() {
+            issues.push(ConfigIssue::new(
+                "runes.pageName",
+                "Rune page name cannot be empty",
+            ));
+        }
+
+        if !BUILD_SOURCES.contains(&self.item_sets.source.as_str()) {
+            issues.push(ConfigIssue::new(
+                "itemSets.source",
+                format!("Unknown item set source '{}'", self.item_sets.source),
+            ));
+        } else if self.item_sets.source == "url" && !self.item_sets.url_template.starts_with("http")
+        {
+            issues.push(ConfigIssue::new(
+                "itemSets.urlTemplate",
+                "Item set source URL must be an http(s) URL",
+            ));
+        }
+
+        if let Err(e) = EnvFilter::try_new(&self.log_level) {
+            issues.push(ConfigIssue::new(
+                "logLevel",
+                format!("Invalid log level: {}", e),
+            ));
+        }
+
+        if self.timer_warning_seconds > 30 {
+            issues.push(ConfigIssue::new(
+                "timerWarningSeconds",
+                "Timer warning must be 30 seconds or less",
+            ));
+        }
+
+        if self.dodge_lead_seconds > 30 {
+            issues.push(ConfigIssue::new(
+                "dodgeLeadSeconds",
+                "Dodge lead time must be 30 seconds or less",
+            ));
+        }
+
+        if self.dodge_grace_seconds > 10 {
+            issues.push(ConfigIssue::new(
+                "dodgeGraceSeconds",
+                "Dodge grace period must be 10 seconds or less",
+            ));
+        }
+
+        validate_role_lists("autoPick.champions", &self.auto_pick.champions, &mut issues);
+        validate_role_lists("autoBan.champions", &self.auto_ban.champions, &mut issues);
+        validate_dodge_rules(&self.dodge_rules, &mut issues);
+        validate_spells(&self.spells, &mut issues);
+
+        if self.lane_intent.enabled && self.lane_intent.message.trim().is_empty() {
+            issues.push(ConfigIssue::new(
+                "laneIntent.message",
+                "Lane intent message cannot be empty",
+            ));
+        }
+
+        // Skin ids are the champion id followed by a three digit skin number.
+        for (champion_id, skin_ids) in &self.random_skin.excluded {
+            if let Some(skin_id) = skin_ids.iter().find(|id| *id / 1000 != *champion_id) {
+                issues.push(ConfigIssue::new(
+                    "randomSkin.excluded",
+                    format!("Skin {} is not a skin of champion {}", skin_id, champion_id),
+                ));
+            }
+        }
+
+        for binding in &self.hotkeys.custom {
+            if hotkeys::HotkeyAction::from_name(&binding.action).is_none() {
+                issues.push(ConfigIssue::new(
+                    "hotkeys.custom",
+                    format!("Unknown hotkey action '{}'", binding.action),
+                ));
+            }
+        }
+
+        let bindings = self.hotkeys.bindings();
+        for (i, (field, accelerator)) in bindings.iter().enumerate() {
+            let taken = bindings[..i].iter().any(|(_, other)| {
+                !accelerator.is_empty() && other.eq_ignore_ascii_case(accelerator)
+            });
+            if taken {
+                issues.push(ConfigIssue::new(
+                    field,
+                    format!("Hotkey '{}' is already bound", accelerator),
+                ));
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedConfig {
+    pub app_version: String,
+    pub config: Config,
+}
+
+impl Config {
+    /// Returns a copy that is safe to share, with anything tied to this machine or account
+    /// removed. Secrets that get blanked here are kept from the current config on import.
+    pub fn without_secrets(&self) -> Config {
+        let mut config = self.clone();
+        config.opgg_mcp.api_key.clear();
+        config.riot_api.api_key.clear();
+        config.local_api.token.clear();
+        for value in config.opgg_mcp.headers.values_mut() {
+            value.clear();
+        }
+        config
+    }
+
+    /// Copies the secrets stripped by `without_secrets` from `current` into an imported config.
+    pub fn with_secrets_from(mut self, current: &Config) -> Config {
+        if self.riot_api.api_key.is_empty() {
+            self.riot_api.api_key = current.riot_api.api_key.clone();
+        }
+        if self.opgg_mcp.api_key.is_empty() {
+            self.opgg_mcp.api_key = current.opgg_mcp.api_key.clone();
+        }
+        if self.local_api.token.is_empty() {
+            self.local_api.token = current.local_api.token.clone();
+        }
+        for (name, value) in self.opgg_mcp.headers.iter_mut() {
+            if value.is_empty() {
+                if let Some(current) = current.opgg_mcp.headers.get(name) {
+                    *value = current.clone();
+                }
+            }
+        }
+        self
+    }
+}
+
+pub fn config_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path_resolver()
+        .app_config_dir()
+        .ok_or_else(|| AppError::Io("Failed to resolve config directory".to_string()))
+}
+
+fn validate_custom_providers(providers: &[CustomProvider], issues: &mut Vec<ConfigIssue>) {
+    for (i, provider) in providers.iter().enumerate() {
+        if provider.id.is_empty() || PROVIDERS.contains(&provider.id.as_str()) {
+            issues.push(ConfigIssue::new(
+                "customProviders",
+                format!("Provider id '{}' is empty or reserved", provider.id),
+            ));
+        } else if providers[..i].iter().any(|p| p.id == provider.id) {
+            issues.push(ConfigIssue::new(
+                "customProviders",
+                format!("Duplicate provider id '{}'", provider.id),
+            ));
+        }
+
+        let template = &provider.url_template;
+        if !template.starts_with("https://") && !template.starts_with("http://") {
+            issues.push(ConfigIssue::new(
+                "customProviders",
+                format!(
+                    "URL template for '{}' must start with http:// or https://",
+                    provider.id
+                ),
+            ));
+        }
+        if !template.contains("{names_csv}") && !template.contains("{riot_id}") {
+            issues.push(ConfigIssue::new(
+                "customProviders",
+                format!(
+                    "URL template for '{}' needs a {{names_csv}} or {{riot_id}} placeholder",
+                    provider.id
+                ),
+            ));
+        }
+    }
+}
+
+fn validate_role_lists(
+    field: &str,
+    lists: &BTreeMap<String, Vec<i64>>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    for (role, champions) in lists {
+        if !ROLES.contains(&role.as_str()) {
+            issues.push(ConfigIssue::new(field, format!("Unknown role '{}'", role)));
+        }
+
+        if champions.iter().any(|id| *id <= 0) {
+            issues.push(ConfigIssue::new(
+                field,
+                format!("Invalid champion id in '{}' list", role),
+            ));
+        }
+    }
+}
+
+fn validate_spells(spells: &SpellConfig, issues: &mut Vec<ConfigIssue>) {
+    let pairs = spells
+        .positions
+        .iter()
+        .map(|(role, pair)| (role.clone(), pair))
+        .chain(
+            spells
+                .champions
+                .iter()
+                .map(|(id, pair)| (id.to_string(), pair)),
+        );
+    for (key, pair) in pairs {
+        if pair.len() != 2 || pair[0] == pair[1] || pair.iter().any(|id| *id <= 0) {
+            issues.push(ConfigIssue::new(
+                "spells",
+                format!("'{}' needs two different spells", key),
+            ));
+        }
+    }
+
+    for role in spells.positions.keys() {
+        if !ROLES.contains(&role.as_str()) {
+            issues.push(ConfigIssue::new(
+                "spells.positions",
+                format!("Unknown role '{}'", role),
+            ));
+        }
+    }
+
+    if !["", "d", "f"].contains(&spells.flash_key.as_str()) {
+        issues.push(ConfigIssue::new(
+            "spells.flashKey",
+            "Flash key must be d, f or empty",
+        ));
+    }
+}
+
+fn validate_dodge_rules(rules: &DodgeRulesConfig, issues: &mut Vec<ConfigIssue>) {
+    if !RULE_MODES.contains(&rules.mode.as_str()) {
+        issues.push(ConfigIssue::new(
+            "dodgeRules.mode",
+            format!("Unknown rule mode '{}'", rules.mode),
+        ));
+    }
+
+    for rule in &rules.rules {
+        if rule.conditions.is_empty() {
+            issues.push(ConfigIssue::new(
+                "dodgeRules.rules",
+                format!("Rule '{}' has no conditions", rule.name),
+            ));
+        }
+
+        for condition in &rule.conditions {
+            let percent = match condition {
+                RuleCondition::AverageWinrateBelow { percent }
+                | RuleCondition::PlayerWinrateBelow { percent, .. }
+                | RuleCondition::WinProbabilityBelow { percent } => Some(*percent),
+                _ => None,
+            };
+            if percent.is_some_and(|p| !(0.0..=100.0).contains(&p)) {
+                issues.push(ConfigIssue::new(
+                    "dodgeRules.rules",
+                    format!("Rule '{}' has a percentage outside 0-100", rule.name),
+                ));
+            }
+        }
+    }
+}
+
+/// Resets the fields named by `issues` to their defaults, then the whole section of any that
+/// are still invalid, since some only fail together with a sibling like `localApi.token`.
+/// Falls back to `Config::default()` if that isn't enough either.
+fn reset_invalid(cfg: &Config, mut issues: Vec<ConfigIssue>) -> Config {
+    let defaults = serde_json::to_value(Config::default()).unwrap();
+    let mut cfg_json = serde_json::to_value(cfg).unwrap();
+
+    for reset_section in [false, true] {
+        for issue in &issues {
+            let field = if reset_section {
+                issue.field.split('.').next().unwrap_or_default()
+            } else {
+                issue.field.as_str()
+            };
+            warn!("Resetting {} in config.json: {}", field, issue.message);
+            let pointer = format!("/{}", field.replace('.', "/"));
+            if let (Some(value), Some(default)) =
+                (cfg_json.pointer_mut(&pointer), defaults.pointer(&pointer))
+            {
+                *value = default.clone();
+            }
+        }
+
+        let Ok(cfg) = serde_json::from_value::<Config>(cfg_json.clone()) else {
+            break;
+        };
+        match cfg.validate() {
+            Ok(()) => return cfg,
+            Err(remaining) => issues = remaining,
+        }
+    }
+
+    warn!("config.json is still invalid, resetting to defaults");
+    Config::default()
+}
+
+/// Loads `config.json`, regenerating it from `Config::default()` when it is missing or
+/// corrupted. Invalid settings are reset one by one, so a deleted sound file doesn't cost
+/// every other setting. The original is kept as `config.json.bak` so hand edits are not lost.
+pub fn load_or_default(cfg_path: &Path) -> Config {
+    let cfg = match std::fs::read_to_string(cfg_path) {
+        Ok(cfg_json) => match serde_json::from_str::<Config>(&cfg_json) {
+            Ok(cfg) => match cfg.validate() {
+                Ok(()) => return cfg,
+                Err(issues) => reset_invalid(&cfg, issues),
+            },
+            Err(e) => {
+                warn!("config.json is corrupted, resetting to defaults: {}", e);
+                Config::default()
+            }
+        },
+        Err(_) => Config::default(),
+    };
+
+    if cfg_path.exists() {
+        if let Err(e) = std::fs::copy(cfg_path, cfg_path.with_extension("json.bak")) {
+            error!("Failed to back up config.json, leaving it as is: {:?}", e);
+            return cfg;
+        }
+    }
+
+    let cfg_json = serde_json::to_string(&cfg).unwrap();
+    if let Err(e) = std::fs::write(cfg_path, cfg_json) {
+        error!("Failed to write config.json: {:?}", e);
+    }
+
+    cfg
+}
+
+pub async fn write_config(cfg_path: &Path, cfg: &Config) -> Result<(), AppError> {
+    let cfg_json = serde_json::to_string(cfg)?;
+    tokio::fs::write(cfg_path, cfg_json).await?;
+    Ok(())
+}
+
+/// Syncs everything that lives outside `AppConfig` with a config change, like hotkeys, the
+/// tray, the log level and the local servers. Called by every path that replaces the config
+/// before it stores `new`.
+pub async fn apply_changes(
+    app_handle: &AppHandle,
+    old: &Config,
+    new: &Config,
+) -> Result<(), AppError> {
+    if old.hotkeys != new.hotkeys {
+        hotkeys::register(app_handle, &new.hotkeys);
+    }
+    if old.auto_accept != new.auto_accept {
+        tray::set_auto_accept(app_handle, new.auto_accept);
+    }
+    if old.launch_on_startup != new.launch_on_startup {
+        autostart::apply(app_handle, new.launch_on_startup);
+    }
+    if old.streamer_mode != new.streamer_mode {
+        streamer::set_enabled(app_handle, new.streamer_mode).await;
+    }
+    if old.language != new.language {
+        i18n::set_language(app_handle, &new.language).await;
+    }
+    if new.crash_reports.upload && !old.crash_reports.upload {
+        tauri::async_runtime::spawn(crash::upload_pending(app_handle.clone()));
+    }
+    if old.local_api != new.local_api {
+        tauri::async_runtime::spawn(api::apply(app_handle.clone(), new.local_api.clone()));
+    }
+    if old.obs != new.obs {
+        tauri::async_runtime::spawn(obs::apply(app_handle.clone(), new.obs.clone()));
+    }
+    if old.opgg_mcp != new.opgg_mcp {
+        opgg::reset(app_handle).await;
+    }
+    if old.log_level != new.log_level {
+        let logging = app_handle.state::<ManagedLogging>();
+        logging
+            .0
+            .set_level(&new.log_level)
+            .map_err(AppError::Parse)?;
+    }
+    Ok(())
+}
+
+/// Watches the config directory and reloads `AppConfig` whenever `config.json` is edited by
+/// hand, emitting `config_updated`. Writes made by `set_config` are ignored since the parsed
+/// file already matches the managed config.
+pub fn watch_config(app_handle: AppHandle, cfg_path: PathBuf) {
+    std::thread::spawn(move || {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                error!("Failed to create config watcher: {:?}", e);
+                return;
+            }
+        };
+
+        let cfg_folder = cfg_path.parent().unwrap().to_path_buf();
+        if let Err(e) = watcher.watch(&cfg_folder, RecursiveMode::NonRecursive) {
+            error!("Failed to watch config directory: {:?}", e);
+            return;
+        }
+
+        for event in rx {
+            let event: Event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Config watcher error: {:?}", e);
+                    continue;
+                }
+            };
+
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                || !event.paths.iter().any(|p| p == &cfg_path)
+            {
+                continue;
+            }
+
+            tauri::async_runtime::block_on(reload_config(&app_handle, &cfg_path));
+        }
+    });
+}
+
+async fn reload_config(app_handle: &AppHandle, cfg_path: &Path) {
+    let cfg_json = match tokio::fs::read_to_string(cfg_path).await {
+        Ok(cfg_json) => cfg_json,
+        Err(e) => {
+            warn!("Failed to read config.json: {:?}", e);
+            return;
+        }
+    };
+
+    // Editors often write the file in several steps, so a partial file is simply skipped.
+    let new_cfg: Config = match serde_json::from_str(&cfg_json) {
+        Ok(new_cfg) => new_cfg,
+        Err(e) => {
+            warn!("Ignoring unparsable config.json: {}", e);
+            return;
+        }
+    };
+
+    if let Err(issues) = new_cfg.validate() {
+        warn!("Ignoring invalid config.json: {:?}", issues);
+        return;
+    }
+
+    let cfg = app_handle.state::<AppConfig>();
+    let mut cfg = cfg.0.lock().await;
+    if *cfg == new_cfg {
+        return;
+    }
+
+    if let Err(e) = apply_changes(app_handle, &cfg, &new_cfg).await {
+        warn!("Failed to apply config.json: {:?}", e);
+    }
+    *cfg = new_cfg.clone();
+    drop(cfg);
+
+    info!("Reloaded config.json from disk");
+    app_handle.emit_all("config_updated", new_cfg).unwrap();
+}
@@ -0,0 +1,143 @@
+use crate::{
+    friends,
+    gameflow::{self, GameflowPhase},
+    i18n, lcu_events, state, tray, LCU,
+};
+use shaco::rest::RESTClient;
+use shaco::utils::process_info;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+fn next_backoff(backoff: Duration) -> Duration {
+    (backoff * 2).min(MAX_BACKOFF)
+}
+
+async fn set_connected(app_handle: &AppHandle, connected: bool) {
+    let lcu = app_handle.state::<LCU>();
+    let mut lcu = lcu.0.lock().await;
+    lcu.connected = connected;
+    if !connected {
+        lcu.data = None;
+        lcu.app_client = None;
+        lcu.remoting_client = None;
+    }
+    drop(lcu);
+
+    if !connected {
+        gameflow::update_phase(app_handle, GameflowPhase::None).await;
+    }
+
+    tray::set_connected(app_handle, connected);
+    app_handle.emit_all("lcu_state_update", connected).unwrap();
+}
+
+/// Watches for the League Client and keeps the managed `LCU` state in sync with it.
+/// Whenever the client closes or restarts, the auth info is re-read and the websocket is
+/// reconnected, backing off exponentially while the client is not ready yet.
+pub async fn watch_lcu(app_handle: AppHandle) {
+    let mut connected = true;
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        let args = process_info::get_league_process_args();
+        if args.is_none() {
+            if connected {
+                info!("Waiting for League Client to open...");
+                connected = false;
+                set_connected(&app_handle, false).await;
+            }
+
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            continue;
+        }
+
+        let args = args.unwrap();
+
+        let lcu_info = match process_info::get_auth_info(args) {
+            Ok(lcu_info) => lcu_info,
+            Err(e) => {
+                warn!(
+                    "Failed to read League Client auth info, retrying in {:?}: {:?}",
+                    backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff);
+                continue;
+            }
+        };
+
+        let (app_client, remoting_client) = match (
+            RESTClient::new(lcu_info.clone(), false),
+            RESTClient::new(lcu_info.clone(), true),
+        ) {
+            (Ok(app_client), Ok(remoting_client)) => (app_client, remoting_client),
+            _ => {
+                warn!(
+                    "Failed to create League Client REST clients, retrying in {:?}",
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff);
+                continue;
+            }
+        };
+
+        let ws = match lcu_events::connect().await {
+            Some(ws) => ws,
+            None => {
+                warn!(
+                    "League Client websocket is not ready, retrying in {:?}",
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff);
+                continue;
+            }
+        };
+
+        // The websocket can be up a moment before the client answers requests.
+        let state = match state::get_gameflow_state(&remoting_client).await {
+            Ok(state) => state,
+            Err(e) => {
+                warn!(
+                    "Failed to read the gameflow phase, retrying in {:?}: {:?}",
+                    backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff);
+                continue;
+            }
+        };
+
+        backoff = INITIAL_BACKOFF;
+
+        let lcu = app_handle.state::<LCU>();
+        let mut lcu = lcu.0.lock().await;
+        lcu.data = Some(lcu_info);
+        lcu.app_client = Some(app_client.clone());
+        lcu.remoting_client = Some(remoting_client.clone());
+        drop(lcu);
+
+        connected = true;
+        set_connected(&app_handle, true).await;
+
+        info!("Connected to League Client!");
+        i18n::load_client_locale(&app_handle, &app_client).await;
+
+        gameflow::update_phase(&app_handle, GameflowPhase::from(state.as_str())).await;
+        state::handle_client_state(state, &app_handle, &remoting_client, &app_client).await;
+        if let Err(e) = friends::refresh(&app_handle, &remoting_client).await {
+            warn!("Failed to load the friend list: {:?}", e);
+        }
+
+        lcu_events::listen(ws, &app_handle, &remoting_client, &app_client).await;
+
+        warn!("Lost connection to League Client, reconnecting...");
+        connected = false;
+        set_connected(&app_handle, false).await;
+    }
+}
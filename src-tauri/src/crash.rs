@@ -0,0 +1,185 @@
+use crate::{config::Config, gameflow::GameflowPhase, AppConfig, ManagedGameflowState, LCU};
+use serde::{Deserialize, Serialize};
+use std::{
+    backtrace::Backtrace,
+    panic::PanicInfo,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+/// Reports that went through are moved here so they aren't sent twice.
+const UPLOADED_DIR: &str = "uploaded";
+
+fn dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    Some(app_handle.path_resolver().app_data_dir()?.join("crashes"))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub app_version: String,
+    pub os: String,
+    /// Unix seconds.
+    pub time: u64,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub state: StateSnapshot,
+}
+
+/// What the app was doing. Each field is `None` when its lock was held at the time of the
+/// panic, since waiting on it could hang the crashing thread.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshot {
+    pub connected: Option<bool>,
+    pub gameflow_phase: Option<GameflowPhase>,
+    /// Without API keys, tokens and headers, see `Config::without_secrets`.
+    pub config: Option<Config>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn snapshot(app_handle: &AppHandle) -> StateSnapshot {
+    StateSnapshot {
+        connected: app_handle
+            .state::<LCU>()
+            .0
+            .try_lock()
+            .ok()
+            .map(|lcu| lcu.connected),
+        gameflow_phase: app_handle
+            .state::<ManagedGameflowState>()
+            .0
+            .try_lock()
+            .ok()
+            .map(|phase| *phase),
+        config: app_handle
+            .try_state::<AppConfig>()
+            .and_then(|config| config.0.try_lock().ok().map(|c| c.without_secrets())),
+    }
+}
+
+fn report(app_handle: &AppHandle, info: &PanicInfo) -> CrashReport {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string());
+
+    CrashReport {
+        app_version: app_handle.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        time: now(),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string(),
+        message,
+        location: info.location().map(|l| l.to_string()),
+        backtrace: Backtrace::force_capture().to_string(),
+        state: snapshot(app_handle),
+    }
+}
+
+fn write(crash_dir: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(crash_dir).map_err(|e| e.to_string())?;
+    let path = crash_dir.join(format!("crash-{}.json", report.time));
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Writes a crash report to the `crashes` data folder for every panic, then runs the default
+/// hook.
+pub fn install(app_handle: AppHandle) {
+    let Some(crash_dir) = dir(&app_handle) else {
+        warn!("No data folder, crash reports are disabled");
+        return;
+    };
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = report(&app_handle, info);
+        match write(&crash_dir, &report) {
+            Ok(path) => error!("Crashed, report written to {}", path.display()),
+            Err(e) => error!("Crashed and failed to write the report: {}", e),
+        }
+        default_hook(info);
+    }));
+}
+
+/// Reports not uploaded yet, oldest first.
+pub fn pending(app_handle: &AppHandle) -> Vec<(PathBuf, CrashReport)> {
+    let Some(entries) = dir(app_handle).and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<(PathBuf, CrashReport)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let json = std::fs::read_to_string(&path).ok()?;
+            match serde_json::from_str(&json) {
+                Ok(report) => Some((path, report)),
+                Err(e) => {
+                    warn!("Skipping unreadable crash report {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    reports.sort_by_key(|(_, report)| report.time);
+    reports
+}
+
+/// Sends the reports left by earlier crashes when the user opted in. Runs at startup and when
+/// uploads get turned on, since a panicking process is no place for network requests.
+pub async fn upload_pending(app_handle: AppHandle) {
+    let crash_reports = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .crash_reports
+        .clone();
+    if !crash_reports.upload || crash_reports.url.is_empty() {
+        return;
+    }
+
+    let reports = pending(&app_handle);
+    if reports.is_empty() {
+        return;
+    }
+
+    let client = reqwest::Client::new();
+    for (path, report) in reports {
+        let result = client
+            .post(&crash_reports.url)
+            .json(&report)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            warn!("Failed to upload crash report {}: {}", path.display(), e);
+            return;
+        }
+
+        info!("Uploaded crash report {}", path.display());
+        let uploaded_dir = path.with_file_name(UPLOADED_DIR);
+        let moved = std::fs::create_dir_all(&uploaded_dir)
+            .and_then(|_| std::fs::rename(&path, uploaded_dir.join(path.file_name().unwrap())));
+        if let Err(e) = moved {
+            warn!("Failed to move uploaded crash report: {}", e);
+        }
+    }
+}
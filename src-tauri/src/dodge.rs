@@ -0,0 +1,572 @@
+use crate::{
+    audio::{self, Sound},
+    autofill,
+    champ_select::ChampSelectSession,
+    error::AppError,
+    gameflow, i18n,
+    lobby::Lobby,
+    notifications::{self, NotificationKind},
+    penalties, ranked,
+    speech::{self, Announcement},
+    storage::BlocklistMatch,
+    streamer, AppConfig, DodgeState, ManagedChampSelectState, ManagedDodgeState, ManagedStorage,
+    ManagedTimerState,
+};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+const COUNTDOWN_TICK: Duration = Duration::from_millis(250);
+/// The countdown sound ticks for the last seconds only, and is announced when they start.
+const COUNTDOWN_ALERT_SECONDS: u64 = 5;
+const STATE_FILE: &str = "dodge_state.json";
+const CLASH_QUEUE_IDS: [i64; 2] = [700, 720];
+
+/// Dodge penalties escalate for every dodge within this window.
+const DODGE_WINDOW_SECS: i64 = 24 * 60 * 60;
+/// LP lost in ranked and minutes locked out of queue for the first, second and any further
+/// dodge inside the window.
+const DODGE_PENALTIES: [(i64, u64); 3] = [(5, 6), (15, 30), (15, 720)];
+
+/// With no queues configured, dodging is allowed everywhere except Clash and custom games,
+/// which report a queue id of -1 or 0.
+pub fn queue_allowed(allowed: &[i64], queue_id: i64) -> bool {
+    if allowed.is_empty() {
+        queue_id > 0 && !CLASH_QUEUE_IDS.contains(&queue_id)
+    } else {
+        allowed.contains(&queue_id)
+    }
+}
+
+/// Errors unless the dodge features may be used in the queue of the current game. `allowed`
+/// is `dodge_queue_ids` from the config, passed in by callers that already hold a copy.
+pub async fn check_queue(remoting_client: &RESTClient, allowed: &[i64]) -> Result<(), AppError> {
+    let queue = gameflow::get_gameflow_session(remoting_client)
+        .await?
+        .game_data
+        .queue;
+
+    if queue_allowed(allowed, queue.id) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Dodging is disabled in queue {} ({})",
+            queue.id, queue.description
+        )))
+    }
+}
+
+/// The part of `DodgeState` that survives a restart.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct PersistedDodgeState {
+    enabled: Option<u64>,
+    last_dodge: Option<u64>,
+}
+
+/// Restores the armed game and last dodge written by `save_state`. A stale armed game is
+/// harmless since dodges only fire for the game id they were armed for.
+pub fn load_state(data_dir: &Path) -> DodgeState {
+    let persisted = std::fs::read_to_string(data_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<PersistedDodgeState>(&json).ok())
+        .unwrap_or_default();
+
+    DodgeState {
+        last_dodge: persisted.last_dodge,
+        enabled: persisted.enabled,
+        auto_armed: None,
+        scheduled: None,
+        pending: None,
+        rules_checked: None,
+    }
+}
+
+pub fn save_state(app_handle: &AppHandle, dodge_state: &DodgeState) {
+    let Some(data_dir) = app_handle.path_resolver().app_data_dir() else {
+        return;
+    };
+
+    let persisted = PersistedDodgeState {
+        enabled: dodge_state.enabled,
+        last_dodge: dodge_state.last_dodge,
+    };
+    let json = serde_json::to_string(&persisted).unwrap();
+    if let Err(e) = std::fs::write(data_dir.join(STATE_FILE), json) {
+        warn!("Failed to save dodge state: {}", e);
+    }
+}
+
+/// Disarms the dodge when its game can no longer be dodged: the game started, or champ select
+/// moved on to a different game. Emits `dodge_disarmed` if anything was armed.
+pub async fn clear_stale(app_handle: &AppHandle, current_game_id: Option<u64>) {
+    let dodge_state = app_handle.state::<ManagedDodgeState>();
+    let mut dodge_state = dodge_state.0.lock().await;
+    let Some(armed) = dodge_state.enabled else {
+        return;
+    };
+    if current_game_id == Some(armed) {
+        return;
+    }
+
+    dodge_state.enabled = None;
+    dodge_state.pending = None;
+    save_state(app_handle, &dodge_state);
+    drop(dodge_state);
+
+    info!("Disarmed stale dodge for game {}", armed);
+    app_handle.emit_all("dodge_disarmed", armed).unwrap();
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeCountdown {
+    pub game_id: u64,
+    pub ms_remaining: u64,
+}
+
+pub async fn quit_champ_select(remoting_client: &RESTClient) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            "/lol-login/v1/session/invoke?destination=lcdsServiceProxy&method=call&args=[\"\",\"teambuilder-draft\",\"quitV2\",\"\"]".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgePending {
+    pub ms_remaining: u64,
+}
+
+async fn current_game_id(app_handle: &AppHandle) -> u64 {
+    let champ_select = app_handle.state::<ManagedChampSelectState>();
+    let game_id = champ_select.0.lock().await.as_ref().map(|s| s.game_id);
+    game_id.unwrap_or_default()
+}
+
+/// Records the dodge for cost estimates and tells the frontend it went through.
+async fn dodge_executed(app_handle: &AppHandle, game_id: u64) {
+    let storage = app_handle.state::<ManagedStorage>();
+    if let Err(e) = storage.0.lock().await.record_dodge(game_id as i64) {
+        error!("Failed to record dodge: {}", e);
+    }
+
+    app_handle.emit_all("dodge_executed", game_id).unwrap();
+    let tr = i18n::translator(app_handle).await;
+    notifications::notify(
+        app_handle,
+        NotificationKind::DodgeExecuted,
+        &tr.t("notification.dodged.title"),
+        &tr.t("notification.dodged.body"),
+    )
+    .await;
+}
+
+/// Quits champ select after `grace`, emitting `dodge_pending` first so the frontend can offer
+/// a cancel button. A zero grace period dodges right away.
+pub async fn dodge_with_grace(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    allowed_queues: &[i64],
+    grace: Duration,
+) -> Result<(), AppError> {
+    check_queue(remoting_client, allowed_queues).await?;
+
+    if grace.is_zero() {
+        info!("Attempting to quit champ select...");
+        quit_champ_select(remoting_client).await?;
+        let game_id = current_game_id(app_handle).await;
+        dodge_executed(app_handle, game_id).await;
+        return Ok(());
+    }
+
+    let deadline = Instant::now() + grace;
+    let dodge_state = app_handle.state::<ManagedDodgeState>();
+    dodge_state.0.lock().await.pending = Some(deadline);
+
+    info!("Dodging in {}ms unless cancelled", grace.as_millis());
+    app_handle.emit_all(
+        "dodge_pending",
+        DodgePending {
+            ms_remaining: grace.as_millis() as u64,
+        },
+    )?;
+
+    let app_handle = app_handle.clone();
+    let remoting_client = remoting_client.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep_until(deadline.into()).await;
+
+        // A cancel, or a newer dodge replacing this one, changes the pending deadline.
+        let dodge_state = app_handle.state::<ManagedDodgeState>();
+        let mut dodge_state = dodge_state.0.lock().await;
+        if dodge_state.pending != Some(deadline) {
+            return;
+        }
+        dodge_state.pending = None;
+        drop(dodge_state);
+
+        info!("Grace period over, calling quit endpoint...");
+        match quit_champ_select(&remoting_client).await {
+            Ok(()) => {
+                let game_id = current_game_id(&app_handle).await;
+                dodge_executed(&app_handle, game_id).await;
+            }
+            Err(e) => error!("Failed to dodge: {}", e),
+        }
+    });
+
+    Ok(())
+}
+
+/// Aborts a dodge waiting out its grace period and disarms any scheduled last-second dodge.
+/// Returns whether there was anything to cancel.
+pub async fn cancel_dodge(app_handle: &AppHandle) -> bool {
+    let dodge_state = app_handle.state::<ManagedDodgeState>();
+    let mut dodge_state = dodge_state.0.lock().await;
+    let pending = dodge_state.pending.take();
+    let armed = dodge_state.enabled.take();
+    let cancelled = pending.is_some() || armed.is_some();
+    save_state(app_handle, &dodge_state);
+    drop(dodge_state);
+
+    if cancelled {
+        info!("Dodge cancelled");
+        app_handle.emit_all("dodge_cancelled", ()).unwrap();
+    }
+    cancelled
+}
+
+/// Starts counting down an armed dodge once finalization begins. Does nothing unless the dodge
+/// is armed for this game and not already counting down or done.
+pub async fn schedule_dodge(app_handle: &AppHandle, remoting_client: &RESTClient, game_id: u64) {
+    let dodge_state = app_handle.state::<ManagedDodgeState>();
+    let mut dodge_state = dodge_state.0.lock().await;
+    if dodge_state.enabled != Some(game_id)
+        || dodge_state.scheduled == Some(game_id)
+        || dodge_state.last_dodge == Some(game_id)
+    {
+        return;
+    }
+
+    dodge_state.scheduled = Some(game_id);
+    drop(dodge_state);
+
+    info!("Scheduled dodge for game {}", game_id);
+    tauri::async_runtime::spawn(run_countdown(
+        app_handle.clone(),
+        remoting_client.clone(),
+        game_id,
+    ));
+}
+
+/// Follows the champ select timer and quits `dodge_lead_seconds` before finalization ends,
+/// emitting `dodge_countdown` every second. Disarming the dodge or leaving finalization
+/// cancels it.
+async fn run_countdown(app_handle: AppHandle, remoting_client: RESTClient, game_id: u64) {
+    let mut last_second = None;
+
+    loop {
+        let cfg = app_handle.state::<AppConfig>();
+        let lead_ms = cfg.0.lock().await.dodge_lead_seconds as u64 * 1000;
+
+        let ms_remaining = {
+            let timer_state = app_handle.state::<ManagedTimerState>();
+            let timer_state = timer_state.0.lock().await;
+            if timer_state.game_id == game_id && timer_state.phase == "FINALIZATION" {
+                timer_state.ms_remaining()
+            } else {
+                None
+            }
+        };
+
+        let dodge_state = app_handle.state::<ManagedDodgeState>();
+        let mut dodge_state = dodge_state.0.lock().await;
+        let Some(ms_remaining) = ms_remaining.filter(|_| dodge_state.enabled == Some(game_id))
+        else {
+            dodge_state.scheduled = None;
+            drop(dodge_state);
+
+            info!("Scheduled dodge for game {} cancelled", game_id);
+            app_handle
+                .emit_all("scheduled_dodge_cancelled", game_id)
+                .unwrap();
+            return;
+        };
+
+        let ms_until_dodge = ms_remaining.saturating_sub(lead_ms);
+        if ms_until_dodge == 0 {
+            dodge_state.scheduled = None;
+            dodge_state.last_dodge = Some(game_id);
+            save_state(&app_handle, &dodge_state);
+            break;
+        }
+        drop(dodge_state);
+
+        let second = ms_until_dodge.div_ceil(1000);
+        if last_second != Some(second) {
+            last_second = Some(second);
+            if second <= COUNTDOWN_ALERT_SECONDS {
+                audio::play(&app_handle, Sound::DodgeCountdown).await;
+            }
+            if second == COUNTDOWN_ALERT_SECONDS {
+                speech::announce(&app_handle, Announcement::DodgeCountdown(second)).await;
+            }
+            app_handle
+                .emit_all(
+                    "dodge_countdown",
+                    DodgeCountdown {
+                        game_id,
+                        ms_remaining: ms_until_dodge,
+                    },
+                )
+                .unwrap();
+        }
+
+        tokio::time::sleep(COUNTDOWN_TICK).await;
+    }
+
+    info!("Last second dodge calling quit endpoint...");
+    match quit_champ_select(&remoting_client).await {
+        Ok(()) => dodge_executed(&app_handle, game_id).await,
+        Err(e) => error!("Failed to dodge game {}: {}", game_id, e),
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoDodgeArmed {
+    pub game_id: u64,
+    pub matches: Vec<BlocklistMatch>,
+}
+
+/// Arms the last-second dodge on behalf of an automatic rule. Each game is only auto armed
+/// once, so a user who disarms it stays disarmed. Returns whether the dodge was newly armed.
+pub async fn auto_arm(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    allowed_queues: &[i64],
+    game_id: u64,
+) -> bool {
+    if let Err(e) = check_queue(remoting_client, allowed_queues).await {
+        info!("Not arming dodge for game {}: {}", game_id, e);
+        return false;
+    }
+
+    let dodge_state = app_handle.state::<ManagedDodgeState>();
+    let mut dodge_state = dodge_state.0.lock().await;
+    if dodge_state.auto_armed == Some(game_id) || dodge_state.last_dodge == Some(game_id) {
+        return false;
+    }
+
+    dodge_state.auto_armed = Some(game_id);
+    if dodge_state.enabled == Some(game_id) {
+        return false;
+    }
+
+    dodge_state.enabled = Some(game_id);
+    save_state(app_handle, &dodge_state);
+    true
+}
+
+/// Arms the last-second dodge for the current game when blocklisted players are present. The
+/// dodge only fires at the end of finalization, so until then `enable_dodge` disarms it again.
+pub async fn arm_for_blocklisted(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    allowed_queues: &[i64],
+    matches: Vec<BlocklistMatch>,
+) {
+    if matches.is_empty() {
+        return;
+    }
+
+    let champ_select = app_handle.state::<ManagedChampSelectState>();
+    let Some(game_id) = champ_select.0.lock().await.as_ref().map(|s| s.game_id) else {
+        return;
+    };
+
+    if !auto_arm(app_handle, remoting_client, allowed_queues, game_id).await {
+        return;
+    }
+
+    info!(
+        "Armed dodge for game {}, {} blocklisted players",
+        game_id,
+        matches.len()
+    );
+    streamer::emit_all(
+        app_handle,
+        "auto_dodge_armed",
+        AutoDodgeArmed { game_id, matches },
+    )
+    .await;
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeAdvice {
+    /// 0 to 100, higher means more reason to dodge.
+    pub score: u8,
+    pub reasons: Vec<String>,
+}
+
+/// Combines the blocklist, autofill flags and teammates' season winrates into one score, with
+/// a reason for every point it adds.
+pub async fn get_dodge_advice(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    session: Option<&ChampSelectSession>,
+) -> DodgeAdvice {
+    let mut score = 0u32;
+    let mut reasons = Vec::new();
+
+    let blocklisted = {
+        let storage = app_handle.state::<ManagedStorage>();
+        let storage = storage.0.lock().await;
+        storage.find_blocklisted(lobby).unwrap_or_else(|e| {
+            warn!("Failed to check the blocklist: {}", e);
+            Vec::new()
+        })
+    };
+    for found in &blocklisted {
+        score += 50;
+        reasons.push(format!(
+            "{}#{} is blocklisted: {}",
+            found.participant.game_name, found.participant.game_tag, found.entry.reason
+        ));
+    }
+
+    let ranks = ranked::get_lobby_ranks(remoting_client, lobby).await;
+    for rank in &ranks {
+        let Some(solo) = rank.solo.as_ref().filter(|q| q.games() >= 20) else {
+            continue;
+        };
+        match solo.winrate() {
+            Some(winrate) if winrate < 0.38 => score += 15,
+            Some(winrate) if winrate < 0.42 => score += 10,
+            _ => continue,
+        }
+        reasons.push(format!(
+            "{}#{} has a {:.0}% winrate over {} games",
+            rank.game_name,
+            rank.game_tag,
+            solo.winrate().unwrap_or_default() * 100.0,
+            solo.games()
+        ));
+    }
+
+    if let Some(session) = session {
+        let autofilled = autofill::get_autofill_flags(remoting_client, session)
+            .await
+            .into_iter()
+            .filter(|f| f.likely_autofilled)
+            .collect::<Vec<_>>();
+        if !autofilled.is_empty() {
+            score += 15 + 20 * (autofilled.len() as u32 - 1);
+            let positions = autofilled
+                .iter()
+                .map(|f| f.assigned_position.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            reasons.push(format!(
+                "{} likely autofilled ({})",
+                autofilled.len(),
+                positions
+            ));
+        }
+    }
+
+    DodgeAdvice {
+        score: score.min(100) as u8,
+        reasons,
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeCost {
+    pub queue_id: i64,
+    pub ranked: bool,
+    /// Dodges made through the app in the last 24 hours.
+    pub recent_dodges: u32,
+    pub expected_lp_loss: i64,
+    pub lockout_minutes: u64,
+    /// Queue lockout already running from an earlier penalty.
+    pub current_lockout_seconds: f64,
+    pub low_priority_seconds: f64,
+    pub notes: Vec<String>,
+}
+
+/// Estimates what dodging the current champ select costs, from the dodges we recorded and
+/// any penalty the client already reports.
+pub async fn get_dodge_cost(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+) -> Result<DodgeCost, AppError> {
+    let queue = gameflow::get_gameflow_session(remoting_client)
+        .await?
+        .game_data
+        .queue;
+
+    let recent_dodges = {
+        let storage = app_handle.state::<ManagedStorage>();
+        let storage = storage.0.lock().await;
+        storage.recent_dodges(DODGE_WINDOW_SECS)?
+    };
+
+    let penalties = penalties::get_penalties(remoting_client).await;
+
+    let (lp_loss, lockout_minutes) =
+        DODGE_PENALTIES[(recent_dodges as usize).min(DODGE_PENALTIES.len() - 1)];
+    let expected_lp_loss = if queue.is_ranked { lp_loss } else { 0 };
+
+    let current_lockout_seconds = penalties.lockout_seconds();
+    let low_priority_seconds = penalties.low_priority_seconds;
+
+    let mut notes = vec![format!(
+        "This would be dodge number {} in the last 24 hours",
+        recent_dodges + 1
+    )];
+    if recent_dodges > 0 {
+        notes.push("Dodges made outside the app are not counted".to_string());
+    }
+    for lockout in &penalties.lockouts {
+        notes.push(format!(
+            "Active {} penalty with {:.0} seconds left",
+            lockout.error_type, lockout.seconds_remaining
+        ));
+    }
+    if low_priority_seconds > 0.0 {
+        notes.push(format!(
+            "Low priority queue ({}) with {:.0} seconds left",
+            penalties.low_priority_reason, low_priority_seconds
+        ));
+    }
+    if penalties.low_priority_games_remaining > 0 {
+        notes.push(format!(
+            "{} more games with low priority queue",
+            penalties.low_priority_games_remaining
+        ));
+    }
+
+    Ok(DodgeCost {
+        queue_id: queue.id,
+        ranked: queue.is_ranked,
+        recent_dodges,
+        expected_lp_loss,
+        lockout_minutes,
+        current_lockout_seconds,
+        low_priority_seconds,
+        notes,
+    })
+}
@@ -0,0 +1,185 @@
+use crate::{error::AppError, streamer, ManagedStorage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shaco::rest::RESTClient;
+use std::{collections::HashMap, time::Duration};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+const EOG_ATTEMPTS: u32 = 30;
+const EOG_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGameStats {
+    pub game_id: i64,
+    /// Game length in seconds.
+    pub game_length: i64,
+    pub queue_id: i64,
+    pub teams: Vec<EndOfGameTeam>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGameTeam {
+    pub team_id: i64,
+    pub is_player_team: bool,
+    pub is_winning_team: bool,
+    pub players: Vec<EndOfGamePlayer>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGamePlayer {
+    pub puuid: String,
+    pub summoner_id: i64,
+    pub riot_id_game_name: String,
+    pub riot_id_tag_line: String,
+    pub champion_id: i64,
+    /// Keyed by the client's stat names, e.g. `CHAMPIONS_KILLED` or `VISION_SCORE`.
+    pub stats: HashMap<String, Value>,
+}
+
+impl EndOfGamePlayer {
+    pub fn stat(&self, name: &str) -> i64 {
+        self.stats
+            .get(name)
+            .and_then(|v| v.as_i64().or_else(|| v.as_f64().map(|f| f as i64)))
+            .unwrap_or_default()
+    }
+}
+
+/// One line of the scoreboard.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerLine {
+    pub puuid: String,
+    pub game_name: String,
+    pub tag_line: String,
+    pub team_id: i64,
+    pub champion_id: i64,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    /// Lane minions plus jungle monsters.
+    pub creep_score: i64,
+    pub gold: i64,
+    pub damage_to_champions: i64,
+    pub vision_score: i64,
+}
+
+impl From<(&EndOfGameTeam, &EndOfGamePlayer)> for PlayerLine {
+    fn from((team, player): (&EndOfGameTeam, &EndOfGamePlayer)) -> Self {
+        PlayerLine {
+            puuid: player.puuid.clone(),
+            game_name: player.riot_id_game_name.clone(),
+            tag_line: player.riot_id_tag_line.clone(),
+            team_id: team.team_id,
+            champion_id: player.champion_id,
+            kills: player.stat("CHAMPIONS_KILLED"),
+            deaths: player.stat("NUM_DEATHS"),
+            assists: player.stat("ASSISTS"),
+            creep_score: player.stat("MINIONS_KILLED") + player.stat("NEUTRAL_MINIONS_KILLED"),
+            gold: player.stat("GOLD_EARNED"),
+            damage_to_champions: player.stat("TOTAL_DAMAGE_DEALT_TO_CHAMPIONS"),
+            vision_score: player.stat("VISION_SCORE"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameFinished {
+    pub game_id: i64,
+    pub queue_id: i64,
+    pub game_length: i64,
+    pub win: Option<bool>,
+    pub players: Vec<PlayerLine>,
+}
+
+impl EndOfGameStats {
+    pub fn player_team(&self) -> Option<&EndOfGameTeam> {
+        self.teams.iter().find(|t| t.is_player_team)
+    }
+
+    /// `win` or `loss`, `None` when the client didn't say which team we were on.
+    pub fn result(&self) -> Option<&'static str> {
+        self.player_team()
+            .map(|t| if t.is_winning_team { "win" } else { "loss" })
+    }
+
+    pub fn scoreboard(&self) -> Vec<PlayerLine> {
+        self.teams
+            .iter()
+            .flat_map(|team| {
+                team.players
+                    .iter()
+                    .map(move |p| PlayerLine::from((team, p)))
+            })
+            .collect()
+    }
+
+    pub fn summary(&self) -> GameFinished {
+        GameFinished {
+            game_id: self.game_id,
+            queue_id: self.queue_id,
+            game_length: self.game_length,
+            win: self.player_team().map(|t| t.is_winning_team),
+            players: self.scoreboard(),
+        }
+    }
+}
+
+/// The end of game screen's stats, both parsed and as the client sent them.
+pub async fn get_eog_stats(
+    remoting_client: &RESTClient,
+) -> Result<(EndOfGameStats, Value), AppError> {
+    let raw = remoting_client
+        .get("/lol-end-of-game/v1/eog-stats-block".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok((serde_json::from_value(raw.clone())?, raw))
+}
+
+/// Polls the end of game stats until they belong to `game_id`. The stats block only shows
+/// up after the game client has closed and the client has collected the results.
+pub async fn wait_for_eog_stats(
+    remoting_client: &RESTClient,
+    game_id: i64,
+) -> Option<(EndOfGameStats, Value)> {
+    for _ in 0..EOG_ATTEMPTS {
+        match get_eog_stats(remoting_client).await {
+            Ok((stats, raw)) if stats.game_id == game_id => return Some((stats, raw)),
+            Ok(_) => debug!("End of game stats are not for game {} yet", game_id),
+            Err(e) => debug!("End of game stats not available yet: {:?}", e),
+        }
+        tokio::time::sleep(EOG_INTERVAL).await;
+    }
+
+    warn!("No end of game stats for game {}", game_id);
+    None
+}
+
+/// Marks the game's encounters with our result, archives the scoreboard and emits
+/// `game_finished` with a summary of it.
+pub async fn record_game(app_handle: &AppHandle, stats: &EndOfGameStats, raw: &Value) {
+    {
+        let storage = app_handle.state::<ManagedStorage>();
+        let mut storage = storage.0.lock().await;
+        if let Some(result) = stats.result() {
+            match storage.record_result(stats.game_id, result) {
+                Ok(updated) => info!(
+                    "Recorded {} for {} encounters in game {}",
+                    result, updated, stats.game_id
+                ),
+                Err(e) => error!("Failed to record game result: {}", e),
+            }
+        }
+        if let Err(e) = storage.record_game(stats, raw) {
+            error!("Failed to archive game {}: {}", stats.game_id, e);
+        }
+    }
+
+    streamer::emit_all(app_handle, "game_finished", stats.summary()).await;
+}
@@ -0,0 +1,207 @@
+use crate::{
+    error::AppError,
+    gameflow::{self, GameflowPlayer},
+    livegame::LivePlayer,
+    lobby::{Lobby, Participant},
+    region, summoner,
+    utils::display_champ_select,
+    AppConfig, ManagedLiveGame,
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+/// How long to wait for the live client data when the gameflow session has no teams.
+const LIVE_DATA_ATTEMPTS: u32 = 30;
+const LIVE_DATA_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnemyPlayer {
+    pub participant: Participant,
+    /// 0 when only the champion name is known.
+    pub champion_id: i64,
+    pub champion_name: Option<String>,
+    pub position: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnemyTeam {
+    pub players: Vec<EnemyPlayer>,
+}
+
+impl EnemyTeam {
+    pub fn lobby(&self) -> Lobby {
+        Lobby {
+            participants: self.players.iter().map(|p| p.participant.clone()).collect(),
+            is_arena: false,
+            subteams: Vec::new(),
+        }
+    }
+}
+
+fn participant(game_name: String, game_tag: String, puuid: String) -> Participant {
+    Participant {
+        cid: String::new(),
+        name: game_name.clone(),
+        game_name,
+        game_tag,
+        muted: false,
+        pid: String::new(),
+        puuid,
+        region: String::new(),
+    }
+}
+
+async fn from_gameflow_player(
+    remoting_client: &RESTClient,
+    player: &GameflowPlayer,
+) -> Option<EnemyPlayer> {
+    let summoner = match summoner::get_summoner_by_puuid(remoting_client, &player.puuid).await {
+        Ok(summoner) => summoner,
+        Err(e) => {
+            warn!("Failed to resolve enemy {}: {:?}", player.puuid, e);
+            return None;
+        }
+    };
+
+    Some(EnemyPlayer {
+        participant: participant(summoner.game_name, summoner.tag_line, player.puuid.clone()),
+        champion_id: player.champion_id,
+        champion_name: None,
+        position: player.selected_position.clone(),
+    })
+}
+
+fn from_live_player(player: &LivePlayer) -> EnemyPlayer {
+    EnemyPlayer {
+        participant: participant(
+            player.riot_id_game_name.clone(),
+            player.riot_id_tag_line.clone(),
+            String::new(),
+        ),
+        champion_id: 0,
+        champion_name: Some(player.champion_name.clone()),
+        position: player.position.clone(),
+    }
+}
+
+/// The other team according to the gameflow session, which lists both teams once the game
+/// has started. Empty when the session doesn't include them.
+async fn from_gameflow(remoting_client: &RESTClient) -> Result<Vec<EnemyPlayer>, AppError> {
+    let session = gameflow::get_gameflow_session(remoting_client).await?;
+    let local_puuid = summoner::get_current_summoner(remoting_client).await?.puuid;
+    let game_data = &session.game_data;
+
+    let enemies = if game_data.team_one.iter().any(|p| p.puuid == local_puuid) {
+        &game_data.team_two
+    } else if game_data.team_two.iter().any(|p| p.puuid == local_puuid) {
+        &game_data.team_one
+    } else {
+        return Ok(Vec::new());
+    };
+
+    Ok(join_all(
+        enemies
+            .iter()
+            .filter(|p| !p.puuid.is_empty())
+            .map(|p| from_gameflow_player(remoting_client, p)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect())
+}
+
+/// The other team according to the live client data, once the loading screen is over.
+async fn from_live_data(app_handle: &AppHandle) -> Vec<EnemyPlayer> {
+    for _ in 0..LIVE_DATA_ATTEMPTS {
+        let data = app_handle
+            .state::<ManagedLiveGame>()
+            .0
+            .lock()
+            .await
+            .data
+            .clone();
+
+        if let Some(data) = data {
+            let local_team = data
+                .all_players
+                .iter()
+                .find(|p| p.riot_id == data.active_player.riot_id)
+                .map(|p| p.team.clone());
+            if let Some(local_team) = local_team {
+                return data
+                    .all_players
+                    .iter()
+                    .filter(|p| p.team != local_team && !p.is_bot)
+                    .map(from_live_player)
+                    .collect();
+            }
+        }
+
+        tokio::time::sleep(LIVE_DATA_INTERVAL).await;
+    }
+
+    Vec::new()
+}
+
+/// The enemy team's Riot IDs and champions. Champ select only ever shows our own team, so
+/// this is the first point they can be looked up.
+pub async fn get_enemy_team(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+) -> Result<EnemyTeam, AppError> {
+    let mut players = from_gameflow(remoting_client).await?;
+    if players.is_empty() {
+        info!("Gameflow session has no enemy team, waiting for live client data");
+        players = from_live_data(app_handle).await;
+    }
+
+    Ok(EnemyTeam { players })
+}
+
+/// Emits `enemy_team_revealed` once the game starts and opens the configured provider for
+/// the enemy team when `auto_open_enemies` is set.
+pub async fn reveal_enemy_team(
+    app_handle: AppHandle,
+    app_client: RESTClient,
+    remoting_client: RESTClient,
+) {
+    let team = match get_enemy_team(&app_handle, &remoting_client).await {
+        Ok(team) if !team.players.is_empty() => team,
+        Ok(_) => {
+            warn!("Could not find the enemy team");
+            return;
+        }
+        Err(e) => {
+            warn!("Failed to look up the enemy team: {:?}", e);
+            return;
+        }
+    };
+
+    info!("Revealed {} enemy players", team.players.len());
+    app_handle.emit_all("enemy_team_revealed", &team).unwrap();
+
+    let config = app_handle.state::<AppConfig>();
+    let config = config.0.lock().await;
+    if !config.auto_open_enemies {
+        return;
+    }
+
+    let region_info = match region::get_region_info(&app_client).await {
+        Ok(region_info) => region_info,
+        Err(e) => {
+            error!("Error fetching region info: {:?}", e);
+            return;
+        }
+    };
+    if let Err(e) = display_champ_select(&team.lobby(), region_info.multi_search_region(), &config)
+    {
+        error!("Failed to open multi-search for the enemy team: {}", e);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::config::ConfigIssue;
+use serde::Serialize;
+use std::fmt::{self, Debug, Display};
+
+/// Errors returned from commands, serialized as `{ "kind": ..., "message": ... }` so the
+/// frontend can tell a disconnected client apart from a bad response.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum AppError {
+    LcuNotConnected,
+    LcuRequest(String),
+    Parse(String),
+    Io(String),
+    Provider(String),
+    InvalidInput(String),
+    InvalidConfig(Vec<ConfigIssue>),
+    Storage(String),
+    Update(String),
+}
+
+impl AppError {
+    pub fn lcu_request(e: impl Debug) -> Self {
+        AppError::LcuRequest(format!("{:?}", e))
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::LcuNotConnected => write!(f, "League Client is not connected"),
+            AppError::LcuRequest(e) => write!(f, "League Client request failed: {}", e),
+            AppError::Parse(e) => write!(f, "Failed to parse response: {}", e),
+            AppError::Io(e) => write!(f, "IO error: {}", e),
+            AppError::Provider(e) => write!(f, "Provider error: {}", e),
+            AppError::InvalidInput(e) => write!(f, "Invalid input: {}", e),
+            AppError::InvalidConfig(issues) => {
+                write!(f, "Invalid config ({} issues)", issues.len())
+            }
+            AppError::Storage(e) => write!(f, "Storage error: {}", e),
+            AppError::Update(e) => write!(f, "Update failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Parse(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    config::ExportFormat,
+    i18n::Translator,
+    lobby::Lobby,
+    ranked::{self, ParticipantRank},
+};
+use shaco::rest::RESTClient;
+
+/// `GOLD II 45 LP`, apex tiers have no division.
+fn rank_label(rank: Option<&ParticipantRank>, tr: &Translator) -> String {
+    let Some(solo) = rank.and_then(|r| r.solo.as_ref()) else {
+        return tr.t("export.unranked");
+    };
+    if solo.division.is_empty() || solo.division == "NA" {
+        format!("{} {} LP", solo.tier, solo.league_points)
+    } else {
+        format!("{} {} {} LP", solo.tier, solo.division, solo.league_points)
+    }
+}
+
+/// Formats the lobby as text. Shared by everything that exports a lobby, so the clipboard
+/// and files look the same.
+pub fn format_lobby(
+    lobby: &Lobby,
+    region: &str,
+    ranks: &[ParticipantRank],
+    format: &ExportFormat,
+    tr: &Translator,
+) -> String {
+    let players: Vec<String> = lobby
+        .participants
+        .iter()
+        .map(|participant| {
+            let riot_id = format!("{}#{}", participant.game_name, participant.game_tag);
+            if !format.include_rank {
+                return riot_id;
+            }
+            let rank = ranks.iter().find(|r| r.puuid == participant.puuid);
+            format!("{} ({})", riot_id, rank_label(rank, tr))
+        })
+        .collect();
+
+    let text = players.join(&format.separator);
+    if format.include_region {
+        format!("[{}] {}", region, text)
+    } else {
+        text
+    }
+}
+
+/// Like `format_lobby`, but looks up ranks first when the format includes them.
+pub async fn export_lobby(
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    region: &str,
+    format: &ExportFormat,
+    tr: &Translator,
+) -> String {
+    let ranks = if format.include_rank {
+        ranked::get_lobby_ranks(remoting_client, lobby).await
+    } else {
+        Vec::new()
+    };
+
+    format_lobby(lobby, region, &ranks, format, tr)
+}
@@ -0,0 +1,171 @@
+use crate::{error::AppError, ManagedFriends};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Friend {
+    pub puuid: String,
+    pub pid: String,
+    pub summoner_id: i64,
+    pub game_name: String,
+    pub game_tag: String,
+    /// `chat`, `away`, `dnd`, `mobile` or `offline`.
+    pub availability: String,
+    pub product: String,
+    pub status_message: String,
+    pub lol: FriendGame,
+}
+
+/// League presence, only filled in while the friend is in the League client.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FriendGame {
+    /// `outOfGame`, `hosting_*`, `inQueue`, `championSelect`, `inGame` or `spectating`.
+    pub game_status: String,
+    pub game_queue_type: String,
+    /// The presence sends numbers as strings.
+    pub champion_id: String,
+    /// When the current status started, in milliseconds.
+    pub time_stamp: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Activity {
+    Offline,
+    Online,
+    InLobby,
+    InQueue,
+    ChampSelect,
+    InGame,
+    Spectating,
+}
+
+impl Friend {
+    pub fn activity(&self) -> Activity {
+        if self.availability == "offline" || self.availability == "mobile" {
+            return Activity::Offline;
+        }
+
+        match self.lol.game_status.as_str() {
+            "inQueue" => Activity::InQueue,
+            "championSelect" => Activity::ChampSelect,
+            "inGame" => Activity::InGame,
+            "spectating" => Activity::Spectating,
+            status if status.starts_with("hosting") => Activity::InLobby,
+            _ => Activity::Online,
+        }
+    }
+
+    pub fn riot_id(&self) -> String {
+        format!("{}#{}", self.game_name, self.game_tag)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendStatus {
+    pub friend: Friend,
+    pub activity: Activity,
+}
+
+impl From<Friend> for FriendStatus {
+    fn from(friend: Friend) -> Self {
+        FriendStatus {
+            activity: friend.activity(),
+            friend,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendStatusChanged {
+    pub friend: Friend,
+    pub activity: Activity,
+    pub previous: Activity,
+}
+
+pub async fn get_friends(remoting_client: &RESTClient) -> Result<Vec<Friend>, AppError> {
+    let friends = remoting_client
+        .get("/lol-chat/v1/friends".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(friends)?)
+}
+
+/// Fetches the friend list, remembers it for change detection and returns it with each
+/// friend's activity.
+pub async fn refresh(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+) -> Result<Vec<FriendStatus>, AppError> {
+    let friends = get_friends(remoting_client).await?;
+
+    let state = app_handle.state::<ManagedFriends>();
+    let mut state = state.0.lock().await;
+    state.clear();
+    for friend in &friends {
+        state.insert(friend.puuid.clone(), friend.clone());
+    }
+
+    let mut friends: Vec<FriendStatus> = friends.into_iter().map(FriendStatus::from).collect();
+    friends.sort_by(|a, b| {
+        (
+            a.activity == Activity::Offline,
+            a.friend.game_name.to_lowercase(),
+        )
+            .cmp(&(
+                b.activity == Activity::Offline,
+                b.friend.game_name.to_lowercase(),
+            ))
+    });
+    Ok(friends)
+}
+
+/// Handles a friend update from the websocket and emits `friend_status_changed` when a
+/// friend we already knew about changed activity.
+pub async fn handle_update(app_handle: &AppHandle, data: Value) {
+    // Removed friends come through with no data.
+    if data.is_null() {
+        return;
+    }
+
+    let friend = match serde_json::from_value::<Friend>(data) {
+        Ok(friend) if !friend.puuid.is_empty() => friend,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Failed to parse friend update: {:?}", e);
+            return;
+        }
+    };
+
+    let state = app_handle.state::<ManagedFriends>();
+    let previous = state
+        .0
+        .lock()
+        .await
+        .insert(friend.puuid.clone(), friend.clone())
+        .map(|f| f.activity());
+
+    let activity = friend.activity();
+    let Some(previous) = previous.filter(|p| *p != activity) else {
+        return;
+    };
+
+    debug!("{} is now {:?}", friend.riot_id(), activity);
+    app_handle
+        .emit_all(
+            "friend_status_changed",
+            FriendStatusChanged {
+                friend,
+                activity,
+                previous,
+            },
+        )
+        .unwrap();
+}
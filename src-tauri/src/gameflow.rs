@@ -0,0 +1,134 @@
+use crate::{dodge, error::AppError, livegame, overlay, ManagedGameflowState};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameflowPhase {
+    None,
+    Lobby,
+    Matchmaking,
+    CheckedIntoTournament,
+    ReadyCheck,
+    ChampSelect,
+    GameStart,
+    FailedToLaunch,
+    InProgress,
+    Reconnect,
+    WaitingForStats,
+    PreEndOfGame,
+    EndOfGame,
+    TerminatedInError,
+    Unknown,
+}
+
+impl From<&str> for GameflowPhase {
+    fn from(phase: &str) -> Self {
+        match phase {
+            "None" => GameflowPhase::None,
+            "Lobby" => GameflowPhase::Lobby,
+            "Matchmaking" => GameflowPhase::Matchmaking,
+            "CheckedIntoTournament" => GameflowPhase::CheckedIntoTournament,
+            "ReadyCheck" => GameflowPhase::ReadyCheck,
+            "ChampSelect" => GameflowPhase::ChampSelect,
+            "GameStart" => GameflowPhase::GameStart,
+            "FailedToLaunch" => GameflowPhase::FailedToLaunch,
+            "InProgress" => GameflowPhase::InProgress,
+            "Reconnect" => GameflowPhase::Reconnect,
+            "WaitingForStats" => GameflowPhase::WaitingForStats,
+            "PreEndOfGame" => GameflowPhase::PreEndOfGame,
+            "EndOfGame" => GameflowPhase::EndOfGame,
+            "TerminatedInError" => GameflowPhase::TerminatedInError,
+            _ => GameflowPhase::Unknown,
+        }
+    }
+}
+
+pub const ARENA_QUEUE_IDS: [i64; 2] = [1700, 1710];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameflowSession {
+    pub game_data: GameData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameData {
+    pub game_id: u64,
+    pub queue: Queue,
+    /// Both teams, filled in once the game has started.
+    pub team_one: Vec<GameflowPlayer>,
+    pub team_two: Vec<GameflowPlayer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameflowPlayer {
+    pub puuid: String,
+    pub champion_id: i64,
+    pub selected_position: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Queue {
+    pub id: i64,
+    pub game_mode: String,
+    pub map_id: i64,
+    pub description: String,
+    pub is_ranked: bool,
+}
+
+impl Queue {
+    pub fn is_arena(&self) -> bool {
+        ARENA_QUEUE_IDS.contains(&self.id) || self.game_mode == "CHERRY"
+    }
+}
+
+pub async fn get_gameflow_session(
+    remoting_client: &RESTClient,
+) -> Result<GameflowSession, AppError> {
+    let session = remoting_client
+        .get("/lol-gameflow/v1/session".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(session)?)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameflowChanged {
+    pub previous: GameflowPhase,
+    pub phase: GameflowPhase,
+}
+
+/// Records the new phase and emits `gameflow_changed` when it differs from the previous one.
+/// Returns the previous phase so callers can react to specific transitions.
+pub async fn update_phase(app_handle: &AppHandle, phase: GameflowPhase) -> GameflowPhase {
+    let gameflow = app_handle.state::<ManagedGameflowState>();
+    let mut gameflow = gameflow.0.lock().await;
+    let previous = *gameflow;
+    *gameflow = phase;
+    drop(gameflow);
+
+    if previous != phase {
+        info!("Gameflow phase changed: {:?} -> {:?}", previous, phase);
+        app_handle
+            .emit_all("gameflow_changed", GameflowChanged { previous, phase })
+            .unwrap();
+    }
+
+    if previous == GameflowPhase::ChampSelect && phase != GameflowPhase::ChampSelect {
+        overlay::hide(app_handle);
+    }
+
+    if phase == GameflowPhase::InProgress {
+        dodge::clear_stale(app_handle, None).await;
+        livegame::start_polling(app_handle).await;
+    }
+
+    previous
+}
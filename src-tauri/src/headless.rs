@@ -0,0 +1,94 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+const HEADLESS_ARG: &str = "--headless";
+
+/// Everything the backend emits with `emit_all`. New events need adding here to show up in
+/// headless mode.
+const EVENTS: [&str; 56] = [
+    "auto_ban_executed",
+    "auto_dodge_armed",
+    "autofill_update",
+    "bench_changed",
+    "blocklisted_player_detected",
+    "champ_select_chat_message",
+    "champ_select_started",
+    "champ_select_timer",
+    "champ_select_timer_warning",
+    "champ_select_trade",
+    "champ_select_update",
+    "chat_update",
+    "client_state_update",
+    "config_updated",
+    "dodge_cancelled",
+    "dodge_countdown",
+    "dodge_disarmed",
+    "dodge_executed",
+    "dodge_pending",
+    "dodge_rule_triggered",
+    "enemy_team_revealed",
+    "friend_status_changed",
+    "game_finished",
+    "gameflow_changed",
+    "gameflow_state_update",
+    "honor_action",
+    "invite_received",
+    "item_sets_applied",
+    "lane_intent_sent",
+    "lcu_state_update",
+    "live_game_event",
+    "live_game_update",
+    "pick_mastery",
+    "played_with_before",
+    "player_annotations",
+    "players_muted",
+    "post_game_ready",
+    "preset_applied",
+    "profile_switched",
+    "queue_stopped",
+    "queue_update",
+    "ready_check_accepted",
+    "replay_download_progress",
+    "replay_downloaded",
+    "requeue_cancelled",
+    "requeue_done",
+    "requeue_pending",
+    "runes_applied",
+    "scheduled_dodge_cancelled",
+    "second_instance",
+    "session_stats_updated",
+    "skin_selected",
+    "spells_applied",
+    "update_available",
+    "update_progress",
+    "vote_alert",
+];
+
+/// Started with `--headless`: no window, automations only and events on stdout.
+pub fn enabled() -> bool {
+    std::env::args().any(|arg| arg == HEADLESS_ARG)
+}
+
+/// Closes the main window and prints every event to stdout as one JSON object per line, like
+/// `{"event":"queue_update","payload":{...}}`. Logs go to stderr so stdout can be piped.
+/// Windows release builds have no console, so there stdout only shows up when redirected.
+pub fn start(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_window("main") {
+        let _ = window.close();
+    }
+
+    for event in EVENTS {
+        app_handle.listen_global(event, move |e| {
+            let payload = e
+                .payload()
+                .and_then(|payload| serde_json::from_str(payload).ok())
+                .unwrap_or(Value::Null);
+            println!(
+                "{}",
+                serde_json::json!({ "event": event, "payload": payload })
+            );
+        });
+    }
+    info!("Running headless, printing events to stdout");
+}
@@ -0,0 +1,188 @@
+use crate::{
+    end_of_game::{self, PlayerLine},
+    error::AppError,
+    AppConfig, ManagedHonor,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shaco::rest::RESTClient;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Give the ballot a moment on screen, and the end of game stats time to show up.
+const BALLOT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Ballot {
+    pub game_id: i64,
+    pub eligible_allies: Vec<EligiblePlayer>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EligiblePlayer {
+    pub puuid: String,
+    pub summoner_id: i64,
+    pub champion_name: String,
+    pub bot_player: bool,
+}
+
+/// Emitted as `honor_action` for everything the honor automation does or declines to do.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HonorAction {
+    pub game_id: i64,
+    /// `honor`, `skip` or `none`.
+    pub action: String,
+    pub puuid: Option<String>,
+    pub game_name: Option<String>,
+    pub reason: String,
+}
+
+impl HonorAction {
+    fn new(game_id: i64, action: &str, reason: impl Into<String>) -> Self {
+        HonorAction {
+            game_id,
+            action: action.to_string(),
+            puuid: None,
+            game_name: None,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Picks the eligible teammate to honor for the given mode.
+fn pick<'a>(mode: &str, candidates: &[&'a PlayerLine]) -> Option<(&'a PlayerLine, String)> {
+    match mode {
+        "highestVision" => candidates
+            .iter()
+            .max_by_key(|p| p.vision_score)
+            .map(|p| (*p, format!("Highest vision score ({})", p.vision_score))),
+        "highestKda" => candidates
+            .iter()
+            .max_by(|a, b| kda(a).total_cmp(&kda(b)))
+            .map(|p| {
+                (
+                    *p,
+                    format!("Highest KDA ({}/{}/{})", p.kills, p.deaths, p.assists),
+                )
+            }),
+        _ => None,
+    }
+}
+
+fn kda(player: &PlayerLine) -> f64 {
+    (player.kills + player.assists) as f64 / player.deaths.max(1) as f64
+}
+
+async fn submit(remoting_client: &RESTClient, body: Value) -> Result<(), AppError> {
+    remoting_client
+        .post("/lol-honor-v2/v1/honor-player".to_string(), body)
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+async fn vote(remoting_client: &RESTClient, ballot: &Ballot, mode: &str) -> HonorAction {
+    if mode == "skip" {
+        let body = json!({
+            "gameId": ballot.game_id,
+            "honorCategory": "OPT_OUT",
+            "summonerId": 0,
+        });
+        return match submit(remoting_client, body).await {
+            Ok(()) => HonorAction::new(ballot.game_id, "skip", "Skipping honor is configured"),
+            Err(e) => HonorAction::new(ballot.game_id, "none", format!("Failed to skip: {}", e)),
+        };
+    }
+
+    let stats = match end_of_game::get_eog_stats(remoting_client).await {
+        Ok((stats, _)) => stats,
+        Err(e) => {
+            return HonorAction::new(
+                ballot.game_id,
+                "none",
+                format!("No end of game stats to choose from: {}", e),
+            )
+        }
+    };
+    let scoreboard = stats.scoreboard();
+    let candidates: Vec<&PlayerLine> = scoreboard
+        .iter()
+        .filter(|line| {
+            ballot
+                .eligible_allies
+                .iter()
+                .any(|ally| !ally.bot_player && ally.puuid == line.puuid)
+        })
+        .collect();
+
+    let Some((player, reason)) = pick(mode, &candidates) else {
+        return HonorAction::new(ballot.game_id, "none", "No eligible teammate to honor");
+    };
+    let summoner_id = ballot
+        .eligible_allies
+        .iter()
+        .find(|ally| ally.puuid == player.puuid)
+        .map(|ally| ally.summoner_id)
+        .unwrap_or_default();
+    let body = json!({
+        "gameId": ballot.game_id,
+        "honorCategory": "HEART",
+        "summonerId": summoner_id,
+        "puuid": player.puuid,
+    });
+
+    match submit(remoting_client, body).await {
+        Ok(()) => HonorAction {
+            game_id: ballot.game_id,
+            action: "honor".to_string(),
+            puuid: Some(player.puuid.clone()),
+            game_name: Some(format!("{}#{}", player.game_name, player.tag_line)),
+            reason,
+        },
+        Err(e) => HonorAction::new(ballot.game_id, "none", format!("Failed to honor: {}", e)),
+    }
+}
+
+/// Votes on a new honor ballot according to `honor.mode`, once per game.
+pub async fn handle_ballot(app_handle: AppHandle, remoting_client: RESTClient, ballot: Value) {
+    let mode = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.honor.mode.clone()
+    };
+    if mode == "off" {
+        return;
+    }
+
+    let ballot: Ballot = match serde_json::from_value(ballot) {
+        Ok(ballot) => ballot,
+        Err(e) => {
+            warn!("Failed to parse honor ballot: {:?}", e);
+            return;
+        }
+    };
+    if ballot.game_id == 0 || ballot.eligible_allies.is_empty() {
+        return;
+    }
+
+    {
+        let honored = app_handle.state::<ManagedHonor>();
+        let mut honored = honored.0.lock().await;
+        if *honored == Some(ballot.game_id) {
+            return;
+        }
+        *honored = Some(ballot.game_id);
+    }
+
+    tokio::time::sleep(BALLOT_DELAY).await;
+    let action = vote(&remoting_client, &ballot, &mode).await;
+    info!(
+        "Honor automation in game {}: {} ({})",
+        action.game_id, action.action, action.reason
+    );
+    app_handle.emit_all("honor_action", action).unwrap();
+}
@@ -0,0 +1,141 @@
+use crate::{
+    commands,
+    config::{Config, HotkeyConfig},
+    error::AppError,
+    AppConfig,
+};
+use tauri::{AppHandle, GlobalShortcutManager, Manager};
+use tracing::{error, info, warn};
+
+#[derive(Clone, Copy, Debug)]
+pub enum HotkeyAction {
+    Dodge,
+    EnableDodge,
+    OpenOpggLink,
+    CancelDodge,
+    CopyLobby,
+    RerollChampion,
+    RandomizeSkin,
+    MuteAll,
+    UnmuteAll,
+    ToggleAutoAccept,
+    ToggleAutoPick,
+    ToggleAutoBan,
+    ToggleAutoRunes,
+    ToggleAutoSpells,
+    ToggleAutoItemSets,
+    ToggleRandomSkin,
+    ToggleRequeue,
+}
+
+impl HotkeyAction {
+    /// Parses the action names used by custom bindings in the config.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let action = match name {
+            "dodge" => HotkeyAction::Dodge,
+            "enableDodge" => HotkeyAction::EnableDodge,
+            "openOpggLink" => HotkeyAction::OpenOpggLink,
+            "cancelDodge" => HotkeyAction::CancelDodge,
+            "copyLobby" => HotkeyAction::CopyLobby,
+            "rerollChampion" => HotkeyAction::RerollChampion,
+            "randomizeSkin" => HotkeyAction::RandomizeSkin,
+            "muteAll" => HotkeyAction::MuteAll,
+            "unmuteAll" => HotkeyAction::UnmuteAll,
+            "toggleAutoAccept" => HotkeyAction::ToggleAutoAccept,
+            "toggleAutoPick" => HotkeyAction::ToggleAutoPick,
+            "toggleAutoBan" => HotkeyAction::ToggleAutoBan,
+            "toggleAutoRunes" => HotkeyAction::ToggleAutoRunes,
+            "toggleAutoSpells" => HotkeyAction::ToggleAutoSpells,
+            "toggleAutoItemSets" => HotkeyAction::ToggleAutoItemSets,
+            "toggleRandomSkin" => HotkeyAction::ToggleRandomSkin,
+            "toggleRequeue" => HotkeyAction::ToggleRequeue,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// Flips an automation in the config, saving it like the settings page would and telling
+/// the frontend through `config_updated`.
+async fn toggle(app_handle: AppHandle, flag: fn(&mut Config) -> &mut bool) -> Result<(), AppError> {
+    let cfg = app_handle.state::<AppConfig>();
+    let mut new_cfg = cfg.0.lock().await.clone();
+    let enabled = flag(&mut new_cfg);
+    *enabled = !*enabled;
+    info!("Toggled to {}", enabled);
+
+    commands::set_config(cfg, new_cfg.clone(), app_handle.clone()).await?;
+    app_handle.emit_all("config_updated", new_cfg)?;
+    Ok(())
+}
+
+async fn run(app_handle: AppHandle, action: HotkeyAction) {
+    info!("Hotkey pressed: {:?}", action);
+    let result = match action {
+        HotkeyAction::Dodge => commands::dodge(app_handle, None).await,
+        HotkeyAction::EnableDodge => commands::enable_dodge(app_handle).await,
+        HotkeyAction::OpenOpggLink => commands::open_opgg_link(app_handle).await,
+        HotkeyAction::CancelDodge => commands::cancel_dodge(app_handle).await.map(|_| ()),
+        HotkeyAction::CopyLobby => commands::copy_lobby_to_clipboard(app_handle, None)
+            .await
+            .map(|_| ()),
+        HotkeyAction::RerollChampion => commands::reroll_champion(app_handle).await,
+        HotkeyAction::RandomizeSkin => {
+            let cfg_handle = app_handle.clone();
+            commands::randomize_skin(app_handle, cfg_handle.state())
+                .await
+                .map(|_| ())
+        }
+        HotkeyAction::MuteAll => commands::set_all_muted(true, app_handle).await.map(|_| ()),
+        HotkeyAction::UnmuteAll => commands::set_all_muted(false, app_handle).await.map(|_| ()),
+        HotkeyAction::ToggleAutoAccept => toggle(app_handle, |c| &mut c.auto_accept).await,
+        HotkeyAction::ToggleAutoPick => toggle(app_handle, |c| &mut c.auto_pick.enabled).await,
+        HotkeyAction::ToggleAutoBan => toggle(app_handle, |c| &mut c.auto_ban.enabled).await,
+        HotkeyAction::ToggleAutoRunes => toggle(app_handle, |c| &mut c.runes.enabled).await,
+        HotkeyAction::ToggleAutoSpells => toggle(app_handle, |c| &mut c.spells.enabled).await,
+        HotkeyAction::ToggleAutoItemSets => toggle(app_handle, |c| &mut c.item_sets.enabled).await,
+        HotkeyAction::ToggleRandomSkin => toggle(app_handle, |c| &mut c.random_skin.enabled).await,
+        HotkeyAction::ToggleRequeue => toggle(app_handle, |c| &mut c.requeue.enabled).await,
+    };
+
+    if let Err(e) = result {
+        warn!("Hotkey {:?} failed: {}", action, e);
+    }
+}
+
+/// Replaces all global shortcuts with the configured ones, the fixed bindings first and then
+/// the custom ones. Shortcuts are system wide, so they work while the League client has
+/// focus. Empty bindings and unknown actions are skipped.
+pub fn register(app_handle: &AppHandle, hotkeys: &HotkeyConfig) {
+    let mut manager = app_handle.global_shortcut_manager();
+    if let Err(e) = manager.unregister_all() {
+        error!("Failed to unregister hotkeys: {:?}", e);
+    }
+
+    let mut bindings = vec![
+        (&hotkeys.dodge, HotkeyAction::Dodge),
+        (&hotkeys.enable_dodge, HotkeyAction::EnableDodge),
+        (&hotkeys.open_opgg_link, HotkeyAction::OpenOpggLink),
+        (&hotkeys.cancel_dodge, HotkeyAction::CancelDodge),
+    ];
+    for binding in &hotkeys.custom {
+        match HotkeyAction::from_name(&binding.action) {
+            Some(action) => bindings.push((&binding.accelerator, action)),
+            None => warn!("Unknown hotkey action '{}'", binding.action),
+        }
+    }
+
+    for (accelerator, action) in bindings {
+        if accelerator.is_empty() {
+            continue;
+        }
+
+        let app_handle = app_handle.clone();
+        match manager.register(accelerator, move || {
+            tauri::async_runtime::spawn(run(app_handle.clone(), action));
+        }) {
+            Ok(()) => info!("Registered hotkey {} for {:?}", accelerator, action),
+            Err(e) => error!("Failed to register hotkey {}: {:?}", accelerator, e),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{config::AUTO_LANGUAGE, region, ManagedI18n};
+use shaco::rest::RESTClient;
+use std::{collections::HashMap, sync::Arc};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+/// Strings for everything the backend writes itself, one file per language.
+const LOCALES: [(&str, &str); 3] = [
+    ("en", include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("fr", include_str!("../locales/fr.json")),
+];
+
+/// Used for languages we have no file for and keys missing from a file.
+const FALLBACK: &str = "en";
+
+type Catalog = HashMap<String, String>;
+
+pub struct I18nState {
+    catalogs: HashMap<&'static str, Arc<Catalog>>,
+    /// `language` from the config, `auto` to follow the client.
+    configured: String,
+    /// The client's locale like `es_ES`, once we're connected.
+    client_locale: Option<String>,
+}
+
+impl I18nState {
+    pub fn new(configured: &str) -> Self {
+        let catalogs = LOCALES
+            .iter()
+            .filter_map(|(language, json)| match serde_json::from_str(json) {
+                Ok(catalog) => Some((*language, Arc::new(catalog))),
+                Err(e) => {
+                    error!("Invalid {} locale file: {}", language, e);
+                    None
+                }
+            })
+            .collect();
+
+        I18nState {
+            catalogs,
+            configured: configured.to_string(),
+            client_locale: None,
+        }
+    }
+
+    fn language(&self) -> &str {
+        if self.configured != AUTO_LANGUAGE && self.catalogs.contains_key(self.configured.as_str())
+        {
+            return &self.configured;
+        }
+        self.client_locale
+            .as_deref()
+            .and_then(|locale| locale.split('_').next())
+            .map(|language| language.to_lowercase())
+            .and_then(|language| self.catalogs.keys().find(|l| **l == language).copied())
+            .unwrap_or(FALLBACK)
+    }
+
+    fn translator(&self) -> Translator {
+        let fallback = self.catalogs.get(FALLBACK).cloned().unwrap_or_default();
+        Translator {
+            strings: self
+                .catalogs
+                .get(self.language())
+                .cloned()
+                .unwrap_or_else(|| fallback.clone()),
+            fallback,
+        }
+    }
+}
+
+/// The strings of the current language, for code that formats several of them without
+/// going back to the managed state each time.
+#[derive(Clone)]
+pub struct Translator {
+    strings: Arc<Catalog>,
+    fallback: Arc<Catalog>,
+}
+
+impl Translator {
+    /// The string for `key`, or the key itself when no locale file has it.
+    pub fn t(&self, key: &str) -> String {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| {
+                warn!("Missing translation for {}", key);
+                key.to_string()
+            })
+    }
+
+    /// Like `t`, replacing each `{name}` with its value.
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        args.iter().fold(self.t(key), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+pub async fn translator(app_handle: &AppHandle) -> Translator {
+    app_handle
+        .state::<ManagedI18n>()
+        .0
+        .lock()
+        .await
+        .translator()
+}
+
+pub async fn t(app_handle: &AppHandle, key: &str) -> String {
+    translator(app_handle).await.t(key)
+}
+
+pub async fn set_language(app_handle: &AppHandle, language: &str) {
+    let i18n = app_handle.state::<ManagedI18n>();
+    let mut i18n = i18n.0.lock().await;
+    i18n.configured = language.to_string();
+    info!("Generated text is now in {}", i18n.language());
+}
+
+/// Picks up the client's locale for the `auto` language. Called on every connection since
+/// the client can be restarted in another language.
+pub async fn load_client_locale(app_handle: &AppHandle, app_client: &RESTClient) {
+    let locale = match region::get_region_info(app_client).await {
+        Ok(region_info) => region_info.locale,
+        Err(e) => {
+            warn!("No client locale, keeping the current language: {}", e);
+            return;
+        }
+    };
+
+    let i18n = app_handle.state::<ManagedI18n>();
+    let mut i18n = i18n.0.lock().await;
+    i18n.client_locale = Some(locale);
+    info!("Generated text is now in {}", i18n.language());
+}
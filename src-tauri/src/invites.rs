@@ -0,0 +1,190 @@
+use crate::{
+    config::InviteConfig, error::AppError, AppConfig, ManagedFriends, ManagedInvites,
+    ManagedStorage,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Invitation {
+    pub invitation_id: String,
+    pub from_summoner_id: i64,
+    pub from_summoner_name: String,
+    pub can_accept_invitation: bool,
+    /// `Pending`, `Accepted`, `Declined` or `Error`.
+    pub state: String,
+    pub timestamp: String,
+    pub game_config: InvitationGameConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InvitationGameConfig {
+    pub queue_id: i64,
+    pub game_mode: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteReceived {
+    pub invitation: Invitation,
+    /// Empty when the sender isn't on our friend list.
+    pub from_puuid: String,
+    pub from_riot_id: String,
+    /// Our tags for the sender.
+    pub tags: Vec<String>,
+    /// `accept` or `decline` when a rule handled the invite, `None` when it waits for us.
+    pub action: Option<String>,
+}
+
+pub async fn get_invitations(remoting_client: &RESTClient) -> Result<Vec<Invitation>, AppError> {
+    let invitations = remoting_client
+        .get("/lol-lobby/v2/received-invitations".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(invitations)?)
+}
+
+pub async fn respond(
+    remoting_client: &RESTClient,
+    invitation_id: &str,
+    accept: bool,
+) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            format!(
+                "/lol-lobby/v2/received-invitations/{}/{}",
+                urlencoding::encode(invitation_id),
+                if accept { "accept" } else { "decline" }
+            ),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+fn has_any_tag(tags: &[String], wanted: &[String]) -> bool {
+    tags.iter()
+        .any(|t| wanted.iter().any(|w| w.eq_ignore_ascii_case(t)))
+}
+
+/// What the rules say to do with an invite from a friend carrying `tags`. Invites from
+/// people who aren't friends are only ever declined.
+fn rule_action(config: &InviteConfig, is_friend: bool, tags: &[String]) -> Option<bool> {
+    if has_any_tag(tags, &config.auto_decline_tags) {
+        Some(false)
+    } else if is_friend && has_any_tag(tags, &config.auto_accept_tags) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+async fn handle_invitation(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    config: &InviteConfig,
+    invitation: Invitation,
+) {
+    let friend = app_handle
+        .state::<ManagedFriends>()
+        .0
+        .lock()
+        .await
+        .values()
+        .find(|f| f.summoner_id == invitation.from_summoner_id)
+        .cloned();
+    let (from_puuid, from_riot_id) = match &friend {
+        Some(friend) => (friend.puuid.clone(), friend.riot_id()),
+        None => (String::new(), invitation.from_summoner_name.clone()),
+    };
+
+    let tags = if from_puuid.is_empty() {
+        Vec::new()
+    } else {
+        let storage = app_handle.state::<ManagedStorage>();
+        let storage = storage.0.lock().await;
+        match storage.get_player_annotations(&from_puuid) {
+            Ok(annotations) => annotations.tags,
+            Err(e) => {
+                error!("Failed to load tags for {}: {}", from_puuid, e);
+                Vec::new()
+            }
+        }
+    };
+
+    let mut action = None;
+    if let Some(accept) = rule_action(config, friend.is_some(), &tags) {
+        if accept && !invitation.can_accept_invitation {
+            info!("Cannot accept the invite from {} right now", from_riot_id);
+        } else {
+            match respond(remoting_client, &invitation.invitation_id, accept).await {
+                Ok(()) => {
+                    info!(
+                        "{} the invite from {} by rule",
+                        if accept { "Accepted" } else { "Declined" },
+                        from_riot_id
+                    );
+                    action = Some(if accept { "accept" } else { "decline" }.to_string());
+                }
+                Err(e) => warn!("Failed to answer the invite from {}: {}", from_riot_id, e),
+            }
+        }
+    }
+
+    app_handle
+        .emit_all(
+            "invite_received",
+            InviteReceived {
+                invitation,
+                from_puuid,
+                from_riot_id,
+                tags,
+                action,
+            },
+        )
+        .unwrap();
+}
+
+/// Handles the received invitations list from the websocket: every pending invite we
+/// haven't seen yet goes through the rules and is emitted as `invite_received`.
+pub async fn handle_update(app_handle: &AppHandle, remoting_client: &RESTClient, data: Value) {
+    let invitations = match serde_json::from_value::<Option<Vec<Invitation>>>(data) {
+        // The list is deleted outright once the last invite is gone.
+        Ok(invitations) => invitations.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to parse received invitations: {:?}", e);
+            return;
+        }
+    };
+
+    let new: Vec<Invitation> = {
+        let seen = app_handle.state::<ManagedInvites>();
+        let mut seen = seen.0.lock().await;
+        // Forget invites that are gone, so the list doesn't grow for the whole session.
+        seen.retain(|id| invitations.iter().any(|i| &i.invitation_id == id));
+        invitations
+            .into_iter()
+            .filter(|i| i.state == "Pending" && seen.insert(i.invitation_id.clone()))
+            .collect()
+    };
+    if new.is_empty() {
+        return;
+    }
+
+    let config = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .invites
+        .clone();
+    for invitation in new {
+        handle_invitation(app_handle, remoting_client, &config, invitation).await;
+    }
+}
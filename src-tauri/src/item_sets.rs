@@ -0,0 +1,300 @@
+use crate::{
+    assets::{self, ChampionData},
+    champ_select::ChampSelectSession,
+    config::ItemSetConfig,
+    error::AppError,
+    runes, summoner, AppConfig,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Every set we write has a uid with this prefix, so they can be told apart from the
+/// player's own sets and cleaned up later.
+const UID_PREFIX: &str = "iseeuall-";
+
+/// OP.GG's build sections, in the order they are shown in the shop.
+const OPGG_BLOCKS: [(&str, &str); 7] = [
+    ("starter_items", "Starting items"),
+    ("boots", "Boots"),
+    ("core_items", "Core build"),
+    ("fourth_items", "Fourth item"),
+    ("fifth_items", "Fifth item"),
+    ("sixth_items", "Sixth item"),
+    ("last_items", "Situational"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSetBlock {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub items: Vec<ItemSetItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSetItem {
+    /// Item ids are strings in item sets.
+    pub id: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSetsApplied {
+    pub champion_id: i64,
+    pub position: String,
+    pub title: String,
+    pub blocks: Vec<ItemSetBlock>,
+}
+
+/// The item sets of the current summoner. Sets are kept as raw JSON so fields we don't
+/// know about survive the round trip.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemSets {
+    account_id: i64,
+    item_sets: Vec<Value>,
+    timestamp: i64,
+}
+
+fn is_generated(set: &Value) -> bool {
+    set.get("uid")
+        .and_then(Value::as_str)
+        .is_some_and(|uid| uid.starts_with(UID_PREFIX))
+}
+
+fn block(block_type: &str, ids: &[i64]) -> ItemSetBlock {
+    ItemSetBlock {
+        block_type: block_type.to_string(),
+        items: ids
+            .iter()
+            .map(|id| ItemSetItem {
+                id: id.to_string(),
+                count: 1,
+            })
+            .collect(),
+    }
+}
+
+/// Item ids of an OP.GG section, which is either a single build or a list of alternatives.
+fn section_ids(value: &Value) -> Vec<i64> {
+    let mut ids: Vec<i64> = Vec::new();
+    let builds = match value {
+        Value::Array(builds) => builds.iter().collect(),
+        build => vec![build],
+    };
+    for build in builds {
+        let build_ids = build
+            .get("ids")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_i64);
+        for id in build_ids {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Looks for item set blocks in a provider response, either already in the LCU's shape or
+/// as OP.GG's build sections. MCP tools wrap their JSON in text content, which is parsed
+/// along the way.
+fn find_blocks(value: &Value) -> Option<Vec<ItemSetBlock>> {
+    match value {
+        Value::Object(map) => {
+            if let Some(blocks) = map.get("blocks") {
+                if let Ok(blocks) = serde_json::from_value::<Vec<ItemSetBlock>>(blocks.clone()) {
+                    if !blocks.is_empty() {
+                        return Some(blocks);
+                    }
+                }
+            }
+            if map.contains_key("core_items") {
+                let blocks: Vec<ItemSetBlock> = OPGG_BLOCKS
+                    .iter()
+                    .filter_map(|(key, name)| {
+                        let ids = section_ids(map.get(*key)?);
+                        (!ids.is_empty()).then(|| block(name, &ids))
+                    })
+                    .collect();
+                if !blocks.is_empty() {
+                    return Some(blocks);
+                }
+            }
+            map.values().find_map(find_blocks)
+        }
+        Value::Array(values) => values.iter().find_map(find_blocks),
+        Value::String(text) => serde_json::from_str::<Value>(text)
+            .ok()
+            .filter(|v| v.is_object() || v.is_array())
+            .and_then(|v| find_blocks(&v)),
+        _ => None,
+    }
+}
+
+async fn fetch_blocks(
+    app_handle: &AppHandle,
+    config: &ItemSetConfig,
+    champion: &ChampionData,
+    position: &str,
+) -> Result<Vec<ItemSetBlock>, AppError> {
+    let response = runes::fetch_build_data(
+        app_handle,
+        &config.source,
+        &config.url_template,
+        champion,
+        position,
+    )
+    .await?;
+
+    find_blocks(&response).ok_or_else(|| {
+        AppError::Provider(format!("No item build for {} {}", champion.name, position))
+    })
+}
+
+fn sets_path(summoner_id: i64) -> String {
+    format!("/lol-item-sets/v1/item-sets/{}/sets", summoner_id)
+}
+
+async fn get_item_sets(
+    remoting_client: &RESTClient,
+    summoner_id: i64,
+) -> Result<ItemSets, AppError> {
+    let sets = remoting_client
+        .get(sets_path(summoner_id))
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(sets)?)
+}
+
+async fn put_item_sets(
+    remoting_client: &RESTClient,
+    summoner_id: i64,
+    sets: &ItemSets,
+) -> Result<(), AppError> {
+    remoting_client
+        .put(sets_path(summoner_id), serde_json::to_value(sets)?)
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+/// Replaces our generated set for the champion with a new one, leaving the player's own
+/// sets alone.
+pub async fn write_item_set(
+    remoting_client: &RESTClient,
+    champion_id: i64,
+    title: &str,
+    blocks: &[ItemSetBlock],
+) -> Result<(), AppError> {
+    let summoner = summoner::get_current_summoner(remoting_client).await?;
+    let mut sets = get_item_sets(remoting_client, summoner.summoner_id).await?;
+
+    let uid = format!("{}{}", UID_PREFIX, champion_id);
+    sets.item_sets
+        .retain(|set| set.get("uid").and_then(Value::as_str) != Some(uid.as_str()));
+    sets.item_sets.push(json!({
+        "uid": uid,
+        "title": title,
+        "type": "custom",
+        "map": "any",
+        "mode": "any",
+        "startedFrom": "blank",
+        "sortrank": 0,
+        "associatedChampions": [champion_id],
+        "associatedMaps": [],
+        "preferredItemSlots": [],
+        "blocks": blocks,
+    }));
+
+    put_item_sets(remoting_client, summoner.summoner_id, &sets).await
+}
+
+/// Removes every item set we generated. Returns how many were removed.
+pub async fn clear_generated(remoting_client: &RESTClient) -> Result<usize, AppError> {
+    let summoner = summoner::get_current_summoner(remoting_client).await?;
+    let mut sets = get_item_sets(remoting_client, summoner.summoner_id).await?;
+
+    let before = sets.item_sets.len();
+    sets.item_sets.retain(|set| !is_generated(set));
+    let removed = before - sets.item_sets.len();
+    if removed > 0 {
+        put_item_sets(remoting_client, summoner.summoner_id, &sets).await?;
+        info!("Removed {} generated item sets", removed);
+    }
+
+    Ok(removed)
+}
+
+/// Writes the recommended item set for the champion we just locked and emits
+/// `item_sets_applied`.
+pub async fn apply_for_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    champion_id: i64,
+) {
+    let config = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.item_sets.clone()
+    };
+    if !config.enabled {
+        return;
+    }
+
+    let champion = match assets::get_assets(app_handle).await {
+        Ok(assets) => match assets.champion(champion_id) {
+            Some(champion) => champion.clone(),
+            None => {
+                warn!("Unknown champion {}, not importing items", champion_id);
+                return;
+            }
+        },
+        Err(e) => {
+            warn!("Champion data unavailable, not importing items: {:?}", e);
+            return;
+        }
+    };
+    let position = session.local_position();
+
+    let blocks = match fetch_blocks(app_handle, &config, &champion, position).await {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            warn!("Failed to fetch items for {}: {}", champion.name, e);
+            return;
+        }
+    };
+
+    if config.clear_previous {
+        if let Err(e) = clear_generated(remoting_client).await {
+            warn!("Failed to clear generated item sets: {}", e);
+        }
+    }
+
+    let title = format!("{} {} {}", config.title_prefix, champion.name, position);
+    if let Err(e) = write_item_set(remoting_client, champion_id, &title, &blocks).await {
+        warn!("Failed to write item set: {}", e);
+        return;
+    }
+
+    info!("Imported items for {} {}", champion.name, position);
+    app_handle
+        .emit_all(
+            "item_sets_applied",
+            ItemSetsApplied {
+                champion_id,
+                position: position.to_string(),
+                title,
+                blocks,
+            },
+        )
+        .unwrap();
+}
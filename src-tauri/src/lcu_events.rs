@@ -0,0 +1,345 @@
+use crate::{
+    assets, autofill,
+    champ_select::{self, ChampSelectChange, ChampSelectSession, ChampSelectUpdate},
+    chat, dodge, friends,
+    gameflow::{self, GameflowPhase},
+    honor, invites, item_sets, mastery, post_game, presets, runes, skins, spells, state, streamer,
+    timer, trades, AppConfig, ManagedChampSelectState, ManagedGameflowState,
+};
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use shaco::model::ws::LcuEvent;
+use shaco::model::ws::LcuSubscriptionType::JsonApiEvent;
+use shaco::rest::RESTClient;
+use shaco::ws::LcuWebsocketClient;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error};
+
+/// Every LCU endpoint we want pushed to us instead of polling.
+const SUBSCRIPTIONS: [&str; 6] = [
+    "/lol-gameflow/v1/gameflow-phase",
+    "/lol-champ-select/v1/session",
+    "/lol-chat/v1/conversations",
+    "/lol-chat/v1/friends",
+    "/lol-lobby/v2/received-invitations",
+    "/lol-honor-v2/v1/ballot",
+];
+
+const CONNECT_ATTEMPTS: u32 = 6;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatEvent {
+    pub data: Value,
+}
+
+/// Opens the LCU websocket and subscribes to every endpoint in `SUBSCRIPTIONS`.
+/// Returns `None` when the websocket could not be opened, so the caller can back off and retry.
+pub async fn connect() -> Option<LcuWebsocketClient> {
+    // The websocket event API will not be opened until a few seconds after the client is opened.
+    let mut attempts = 0;
+    let mut ws = loop {
+        match LcuWebsocketClient::connect().await {
+            Ok(ws) => break ws,
+            Err(_) => {
+                attempts += 1;
+                if attempts >= CONNECT_ATTEMPTS {
+                    return None;
+                }
+
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        }
+    };
+
+    for endpoint in SUBSCRIPTIONS {
+        if let Err(e) = ws.subscribe(JsonApiEvent(endpoint.to_string())).await {
+            error!("Failed to subscribe to {}: {:?}", endpoint, e);
+            return None;
+        }
+    }
+
+    Some(ws)
+}
+
+pub async fn listen(
+    mut ws: LcuWebsocketClient,
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    app_client: &RESTClient,
+) {
+    while let Some(msg) = ws.next().await {
+        handle_ws_message(msg, app_handle, remoting_client, app_client).await;
+    }
+}
+
+async fn handle_ws_message(
+    msg: LcuEvent,
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    app_client: &RESTClient,
+) {
+    let msg_type = msg.subscription_type.to_string();
+
+    match msg_type.as_str() {
+        "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase" => {
+            let client_state = msg.data.to_string().replace('\"', "");
+
+            // Emit the gameflow state to frontend
+            app_handle
+                .emit_all("gameflow_state_update", &client_state)
+                .unwrap();
+            let phase = GameflowPhase::from(client_state.as_str());
+            let previous = gameflow::update_phase(app_handle, phase).await;
+            // A crashed game client goes through `Reconnect` and back, which isn't the end.
+            let game_ended = matches!(
+                previous,
+                GameflowPhase::InProgress | GameflowPhase::Reconnect
+            ) && matches!(
+                phase,
+                GameflowPhase::WaitingForStats
+                    | GameflowPhase::PreEndOfGame
+                    | GameflowPhase::EndOfGame
+            );
+            if game_ended {
+                tauri::async_runtime::spawn(post_game::run(
+                    app_handle.clone(),
+                    remoting_client.clone(),
+                ));
+            }
+
+            state::handle_client_state(client_state, app_handle, remoting_client, app_client).await;
+        }
+        "OnJsonApiEvent_lol-champ-select_v1_session" => {
+            let champ_select = serde_json::from_value::<ChampSelectSession>(msg.data.clone());
+            if champ_select.is_err() {
+                error!(
+                    "Failed to parse champ select session!, {:?}",
+                    champ_select.err()
+                );
+                return;
+            }
+
+            let champ_select = champ_select.unwrap();
+            debug!(
+                "Champion select session detected - Phase: {}",
+                champ_select.timer.phase
+            );
+
+            let champ_select_state = app_handle.state::<ManagedChampSelectState>();
+            let mut champ_select_state = champ_select_state.0.lock().await;
+            let changes = champ_select::diff_sessions(champ_select_state.as_ref(), &champ_select);
+            let new_game =
+                champ_select_state.as_ref().map(|s| s.game_id) != Some(champ_select.game_id);
+            *champ_select_state = Some(champ_select.clone());
+            drop(champ_select_state);
+
+            if new_game {
+                dodge::clear_stale(app_handle, Some(champ_select.game_id)).await;
+
+                let app_handle = app_handle.clone();
+                let remoting_client = remoting_client.clone();
+                let session = champ_select.clone();
+                tauri::async_runtime::spawn(async move {
+                    autofill::emit_autofill_flags(&app_handle, &remoting_client, &session).await;
+                });
+            }
+
+            if let Some(bench) = changes
+                .iter()
+                .find(|c| matches!(c, ChampSelectChange::Bench { .. }))
+            {
+                app_handle.emit_all("bench_changed", bench).unwrap();
+            }
+
+            for change in &changes {
+                match change {
+                    ChampSelectChange::Trade { .. } => {
+                        app_handle.emit_all("champ_select_trade", change).unwrap();
+                    }
+                    ChampSelectChange::PickOrderSwap { id, cell_id, state } => {
+                        app_handle.emit_all("champ_select_trade", change).unwrap();
+                        if state != "RECEIVED" {
+                            continue;
+                        }
+
+                        let (id, cell_id) = (*id, *cell_id);
+                        let app_handle = app_handle.clone();
+                        let remoting_client = remoting_client.clone();
+                        let session = champ_select.clone();
+                        tauri::async_runtime::spawn(async move {
+                            trades::handle_pick_order_swap(
+                                &app_handle,
+                                &remoting_client,
+                                &session,
+                                id,
+                                cell_id,
+                            )
+                            .await;
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            for change in &changes {
+                if let ChampSelectChange::Lock {
+                    cell_id,
+                    champion_id,
+                } = *change
+                {
+                    let app_handle = app_handle.clone();
+                    let remoting_client = remoting_client.clone();
+                    let session = champ_select.clone();
+                    tauri::async_runtime::spawn(async move {
+                        mastery::emit_pick_mastery(
+                            &app_handle,
+                            &remoting_client,
+                            &session,
+                            cell_id,
+                            champion_id,
+                        )
+                        .await;
+                    });
+
+                    if cell_id == champ_select.local_player_cell_id {
+                        let app_handle = app_handle.clone();
+                        let remoting_client = remoting_client.clone();
+                        let session = champ_select.clone();
+                        tauri::async_runtime::spawn(async move {
+                            spells::apply_for_lock(
+                                &app_handle,
+                                &remoting_client,
+                                &session,
+                                champion_id,
+                            )
+                            .await;
+                            runes::apply_for_lock(
+                                &app_handle,
+                                &remoting_client,
+                                &session,
+                                champion_id,
+                            )
+                            .await;
+                            item_sets::apply_for_lock(
+                                &app_handle,
+                                &remoting_client,
+                                &session,
+                                champion_id,
+                            )
+                            .await;
+                            skins::apply_for_lock(&app_handle, &remoting_client, champion_id).await;
+                            // Last, so a preset wins over the automatic spells, runes and skin.
+                            presets::apply_for_lock(&app_handle, &remoting_client, champion_id)
+                                .await;
+                        });
+                    } else if champ_select.their_team.iter().any(|p| p.cell_id == cell_id) {
+                        let app_handle = app_handle.clone();
+                        let remoting_client = remoting_client.clone();
+                        let session = champ_select.clone();
+                        tauri::async_runtime::spawn(async move {
+                            spells::apply_for_enemy_lock(
+                                &app_handle,
+                                &remoting_client,
+                                &session,
+                                champion_id,
+                            )
+                            .await;
+                        });
+                    }
+                }
+            }
+
+            let champion_ids: Vec<i64> = changes.iter().flat_map(|c| c.champion_ids()).collect();
+            let champion_names = if champion_ids.is_empty() {
+                BTreeMap::new()
+            } else {
+                assets::champion_names(app_handle, &champion_ids).await
+            };
+
+            streamer::emit_all(
+                app_handle,
+                "champ_select_update",
+                ChampSelectUpdate {
+                    session: champ_select.clone(),
+                    changes,
+                    champion_names,
+                },
+            )
+            .await;
+
+            timer::update_timer(app_handle, &champ_select).await;
+
+            let cfg = app_handle.state::<AppConfig>();
+            let (auto_pick, auto_ban) = {
+                let cfg = cfg.0.lock().await;
+                (cfg.auto_pick.clone(), cfg.auto_ban.clone())
+            };
+            champ_select::handle_auto_ban(&champ_select, remoting_client, &auto_ban, app_handle)
+                .await;
+            champ_select::handle_auto_pick(&champ_select, remoting_client, &auto_pick).await;
+
+            // Handle champion select start for all phases except FINALIZATION
+            if champ_select.timer.phase != "FINALIZATION" && champ_select.timer.phase != "" {
+                // Instead of waiting for gameflow state, handle it directly from WebSocket
+                let cloned_app_handle = app_handle.clone();
+                let cloned_app_client = app_client.clone();
+                let cloned_remoting = remoting_client.clone();
+
+                tauri::async_runtime::spawn(async move {
+                    // Small delay to ensure champion select is fully loaded
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+                    // A copy, since the lookups below take a while and must not hold the lock.
+                    let cfg = cloned_app_handle.state::<AppConfig>();
+                    let cfg = cfg.0.lock().await.clone();
+
+                    champ_select::handle_champ_select_start(
+                        &cloned_app_client,
+                        &cloned_remoting,
+                        &cfg,
+                        &cloned_app_handle,
+                    )
+                    .await;
+                });
+            }
+
+            if champ_select.timer.phase == "FINALIZATION" {
+                dodge::schedule_dodge(app_handle, remoting_client, champ_select.game_id).await;
+            }
+        }
+        "OnJsonApiEvent_lol-chat_v1_conversations" => {
+            // Only messages carry a body, conversation and participant updates don't.
+            let is_message = msg.data.get("body").is_some();
+            streamer::emit_all(app_handle, "chat_update", ChatEvent { data: msg.data }).await;
+
+            let phase = *app_handle.state::<ManagedGameflowState>().0.lock().await;
+            if is_message && phase == GameflowPhase::ChampSelect {
+                tauri::async_runtime::spawn(chat::capture_champ_select_chat(
+                    app_handle.clone(),
+                    app_client.clone(),
+                    remoting_client.clone(),
+                ));
+            }
+        }
+        "OnJsonApiEvent_lol-chat_v1_friends" => {
+            friends::handle_update(app_handle, msg.data).await;
+        }
+        "OnJsonApiEvent_lol-lobby_v2_received-invitations" => {
+            invites::handle_update(app_handle, remoting_client, msg.data).await;
+        }
+        "OnJsonApiEvent_lol-honor-v2_v1_ballot" => {
+            tauri::async_runtime::spawn(honor::handle_ballot(
+                app_handle.clone(),
+                remoting_client.clone(),
+                msg.data,
+            ));
+        }
+        _ => {
+            debug!("Unhandled Message: {}", msg_type);
+        }
+    }
+}
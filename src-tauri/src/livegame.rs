@@ -0,0 +1,249 @@
+use crate::{
+    audio::{self, Sound},
+    gameflow::GameflowPhase,
+    i18n,
+    notifications::{self, NotificationKind},
+    streamer,
+    votes::{VoteAlert, VoteKind, VoteTracker},
+    AppConfig, ManagedGameflowState, ManagedLiveGame,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, time::Duration};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info};
+
+/// The game client serves this with a self-signed certificate on localhost.
+const ALL_GAME_DATA_URL: &str = "https://127.0.0.1:2999/liveclientdata/allgamedata";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AllGameData {
+    pub active_player: ActivePlayer,
+    pub all_players: Vec<LivePlayer>,
+    pub events: LiveEvents,
+    pub game_data: LiveGameData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivePlayer {
+    pub riot_id: String,
+    pub level: i64,
+    pub current_gold: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LivePlayer {
+    pub riot_id: String,
+    pub riot_id_game_name: String,
+    pub riot_id_tag_line: String,
+    pub champion_name: String,
+    /// `ORDER` for blue side, `CHAOS` for red side.
+    pub team: String,
+    pub position: String,
+    pub level: i64,
+    pub is_bot: bool,
+    pub is_dead: bool,
+    pub respawn_timer: f64,
+    pub scores: Scores,
+    pub items: Vec<LiveItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiveItem {
+    #[serde(rename = "itemID")]
+    pub item_id: i64,
+    pub display_name: String,
+    pub slot: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Scores {
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    pub creep_score: i64,
+    pub ward_score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LiveEvents {
+    #[serde(rename = "Events")]
+    pub events: Vec<LiveEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LiveEvent {
+    #[serde(rename = "EventID")]
+    pub event_id: i64,
+    #[serde(rename = "EventName")]
+    pub event_name: String,
+    /// Seconds since the game started.
+    #[serde(rename = "EventTime")]
+    pub event_time: f64,
+    /// Event specific fields like `KillerName` or `VictimName`.
+    #[serde(flatten)]
+    pub details: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiveGameData {
+    pub game_mode: String,
+    /// Seconds since the game started.
+    pub game_time: f64,
+    pub map_number: i64,
+}
+
+/// Team totals and the scoreboard, emitted as `live_game_update` on every poll.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveGameUpdate {
+    pub game_time: f64,
+    pub players: Vec<LivePlayer>,
+    /// Kills per team, keyed by `ORDER` and `CHAOS`.
+    pub team_kills: BTreeMap<String, i64>,
+}
+
+impl AllGameData {
+    pub fn update(&self) -> LiveGameUpdate {
+        let mut team_kills = BTreeMap::new();
+        for player in &self.all_players {
+            *team_kills.entry(player.team.clone()).or_insert(0) += player.scores.kills;
+        }
+
+        LiveGameUpdate {
+            game_time: self.game_data.game_time,
+            players: self.all_players.clone(),
+            team_kills,
+        }
+    }
+}
+
+pub struct LiveGameState {
+    pub data: Option<AllGameData>,
+    polling: bool,
+    client: reqwest::Client,
+}
+
+impl Default for LiveGameState {
+    fn default() -> Self {
+        LiveGameState {
+            data: None,
+            polling: false,
+            client: reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+async fn fetch(client: &reqwest::Client) -> Result<AllGameData, reqwest::Error> {
+    client
+        .get(ALL_GAME_DATA_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<AllGameData>()
+        .await
+}
+
+/// Starts polling the game client while the game is in progress, unless that is already
+/// happening. Emits `live_game_update` on every poll, `live_game_event` for each new event and
+/// `vote_alert` when vote alerts are on and a surrender or remake vote can be called.
+pub async fn start_polling(app_handle: &AppHandle) {
+    let live = app_handle.state::<ManagedLiveGame>();
+    let mut live = live.0.lock().await;
+    if live.polling {
+        return;
+    }
+    live.polling = true;
+    live.data = None;
+    tauri::async_runtime::spawn(poll(app_handle.clone(), live.client.clone()));
+}
+
+/// Notifies about `alert` with its sound too, for players whose client is on another screen.
+async fn notify_vote(app_handle: &AppHandle, alert: &VoteAlert) {
+    let tr = i18n::translator(app_handle).await;
+    let (title, body) = match alert.kind {
+        VoteKind::Remake => (
+            "notification.vote.remake.title",
+            tr.format(
+                "notification.vote.remake.body",
+                &[("players", alert.afk_players.join(", "))],
+            ),
+        ),
+        VoteKind::EarlySurrender => (
+            "notification.vote.earlySurrender.title",
+            tr.t("notification.vote.surrender.body"),
+        ),
+        VoteKind::Surrender => (
+            "notification.vote.surrender.title",
+            tr.t("notification.vote.surrender.body"),
+        ),
+    };
+    let body = streamer::scrub_text(app_handle, &body).await;
+    notifications::notify(app_handle, NotificationKind::Vote, &tr.t(title), &body).await;
+    audio::play(app_handle, Sound::Vote).await;
+}
+
+async fn poll(app_handle: AppHandle, client: reqwest::Client) {
+    info!("Polling live client data");
+    let mut last_event_id = -1;
+    let mut votes = VoteTracker::default();
+
+    loop {
+        let phase = *app_handle.state::<ManagedGameflowState>().0.lock().await;
+        if phase != GameflowPhase::InProgress {
+            break;
+        }
+
+        match fetch(&client).await {
+            Ok(data) => {
+                for event in data
+                    .events
+                    .events
+                    .iter()
+                    .filter(|e| e.event_id > last_event_id)
+                {
+                    streamer::emit_all(&app_handle, "live_game_event", event).await;
+                }
+                if let Some(last) = data.events.events.last() {
+                    last_event_id = last_event_id.max(last.event_id);
+                }
+
+                streamer::emit_all(&app_handle, "live_game_update", data.update()).await;
+                let alerts = votes.check(&data);
+                if app_handle.state::<AppConfig>().0.lock().await.vote_alerts {
+                    for alert in alerts {
+                        info!("{:?} vote available", alert.kind);
+                        notify_vote(&app_handle, &alert).await;
+                        streamer::emit_all(&app_handle, "vote_alert", alert).await;
+                    }
+                }
+                let live = app_handle.state::<ManagedLiveGame>();
+                live.0.lock().await.data = Some(data);
+            }
+            // The API only comes up once the loading screen is over.
+            Err(e) => debug!("Live client data not available: {:?}", e),
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    let live = app_handle.state::<ManagedLiveGame>();
+    let mut live = live.0.lock().await;
+    live.polling = false;
+    live.data = None;
+    info!("Stopped polling live client data");
+}
@@ -1,7 +1,9 @@
+use crate::{champ_select, gameflow};
 use serde::{Deserialize, Serialize};
 use shaco::rest::RESTClient;
+use tracing::{debug, error, info, trace, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Participant {
     pub cid: String,
     pub game_name: String,
@@ -13,39 +15,55 @@ pub struct Participant {
     pub region: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Subteam {
+    pub id: i64,
+    pub participants: Vec<Participant>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Lobby {
     pub participants: Vec<Participant>,
+    #[serde(default)]
+    pub is_arena: bool,
+    #[serde(default)]
+    pub subteams: Vec<Subteam>,
 }
 
-pub async fn get_lobby_info(app_client: &RESTClient) -> Lobby {
-    println!("Attempting to fetch lobby info from LCU...");
+impl Lobby {
+    fn empty() -> Self {
+        Lobby {
+            participants: Vec::new(),
+            is_arena: false,
+            subteams: Vec::new(),
+        }
+    }
+}
+
+pub async fn get_lobby_info(app_client: &RESTClient, remoting_client: &RESTClient) -> Lobby {
+    debug!("Attempting to fetch lobby info from LCU...");
     
     let response = app_client
         .get("/chat/v5/participants".to_string())
         .await;
         
     if let Err(e) = response {
-        println!("Error fetching lobby info: {:?}", e);
-        return Lobby {
-            participants: Vec::new(),
-        };
+        error!("Error fetching lobby info: {:?}", e);
+        return Lobby::empty();
     }
     
     let response_value = response.unwrap();
-    println!("Raw lobby response: {}", response_value);
+    trace!("Raw lobby response: {}", response_value);
     
     let team: Result<Lobby, _> = serde_json::from_value(response_value);
     
     if let Err(e) = team {
-        println!("Error parsing lobby response: {:?}", e);
-        return Lobby {
-            participants: Vec::new(),
-        };
+        error!("Error parsing lobby response: {:?}", e);
+        return Lobby::empty();
     }
     
     let team = team.unwrap();
-    println!("Parsed {} participants", team.participants.len());
+    debug!("Parsed {} participants", team.participants.len());
 
     // filter out all cids that contain champ-select
     let team_participants = team
@@ -54,15 +72,75 @@ pub async fn get_lobby_info(app_client: &RESTClient) -> Lobby {
         .filter(|p| p.cid.contains("champ-select"))
         .collect::<Vec<Participant>>();
 
-    println!("Found {} champion select participants", team_participants.len());
+    info!("Found {} champion select participants", team_participants.len());
     
     for participant in &team_participants {
-        println!("Participant: {} ({}#{})", participant.name, participant.game_name, participant.game_tag);
+        debug!("Participant: {} ({}#{})", participant.name, participant.game_name, participant.game_tag);
     }
 
-    let team = Lobby {
+    let mut team = Lobby {
         participants: team_participants,
+        is_arena: false,
+        subteams: Vec::new(),
     };
 
+    match gameflow::get_gameflow_session(remoting_client).await {
+        Ok(session) if session.game_data.queue.is_arena() => {
+            group_arena_subteams(&mut team, remoting_client).await;
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Error fetching gameflow session: {:?}", e),
+    }
+
     team
 }
+
+/// Arena lobbies hold up to 16 players in teams of two, so group them by the subteam the
+/// champ select session assigns to each player.
+async fn group_arena_subteams(team: &mut Lobby, remoting_client: &RESTClient) {
+    team.is_arena = true;
+
+    let session = match champ_select::get_champ_select_session(remoting_client).await {
+        Ok(session) => session,
+        Err(e) => {
+            warn!(
+                "Error fetching champ select session for arena grouping: {:?}",
+                e
+            );
+            return;
+        }
+    };
+
+    let mut subteams: Vec<Subteam> = Vec::new();
+    let mut ungrouped = Vec::new();
+    for participant in &team.participants {
+        let subteam_id = session
+            .my_team
+            .iter()
+            .chain(session.their_team.iter())
+            .find(|p| !p.puuid.is_empty() && p.puuid == participant.puuid)
+            .map(|p| p.team);
+
+        match subteam_id {
+            Some(id) => match subteams.iter_mut().find(|s| s.id == id) {
+                Some(subteam) => subteam.participants.push(participant.clone()),
+                None => subteams.push(Subteam {
+                    id,
+                    participants: vec![participant.clone()],
+                }),
+            },
+            None => ungrouped.push(participant.clone()),
+        }
+    }
+
+    if !ungrouped.is_empty() {
+        subteams.push(Subteam {
+            id: 0,
+            participants: ungrouped,
+        });
+    }
+
+    subteams.sort_by_key(|s| s.id);
+    info!("Grouped arena lobby into {} subteams", subteams.len());
+    team.subteams = subteams;
+}
@@ -0,0 +1,58 @@
+use std::path::Path;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+    fmt, fmt::writer::BoxMakeWriter, prelude::*, reload, EnvFilter, Registry,
+};
+
+const MAX_LOG_FILES: usize = 14;
+
+pub struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    // Dropping the guard stops the background writer, so it lives as long as the app.
+    _guard: WorkerGuard,
+}
+
+impl Logging {
+    /// Replaces the active filter. Accepts anything `EnvFilter` does, so per-module levels
+    /// like `info,reveal::lcu_events=debug` work as well as a plain `debug`.
+    pub fn set_level(&self, level: &str) -> Result<(), String> {
+        let filter = EnvFilter::try_new(level).map_err(|e| e.to_string())?;
+        self.filter
+            .modify(|f| *f = filter)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Logs to stdout, or stderr when stdout carries headless events, and to a daily rotating
+/// `reveal.log` inside `log_dir`, keeping the last `MAX_LOG_FILES` days.
+pub fn init(log_dir: &Path, level: &str, to_stderr: bool) -> Logging {
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("reveal.log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .expect("Failed to create the log file");
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+    let console = if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(console))
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .init();
+
+    Logging {
+        filter: handle,
+        _guard: guard,
+    }
+}
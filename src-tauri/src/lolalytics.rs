@@ -0,0 +1,41 @@
+use crate::{
+    assets, champ_select::ChampSelectSession, error::AppError,
+    utils::create_lolalytics_champion_link,
+};
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionStatLink {
+    pub cell_id: i64,
+    pub champion_id: i64,
+    pub champion_name: String,
+    pub url: String,
+}
+
+/// Lolalytics build pages for every champion my team has locked or hovered so far.
+pub async fn get_champion_stat_links(
+    app_handle: &AppHandle,
+    session: &ChampSelectSession,
+) -> Result<Vec<ChampionStatLink>, AppError> {
+    let assets = assets::get_assets(app_handle).await?;
+
+    Ok(session
+        .my_team
+        .iter()
+        .filter_map(|player| {
+            let champion_id = match player.champion_id {
+                0 => player.champion_pick_intent,
+                id => id,
+            };
+            let champion = assets.champion(champion_id)?;
+            Some(ChampionStatLink {
+                cell_id: player.cell_id,
+                champion_id,
+                champion_name: champion.name.clone(),
+                url: create_lolalytics_champion_link(&champion.key, &player.assigned_position),
+            })
+        })
+        .collect())
+}
@@ -2,28 +2,119 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod analytics;
+mod api;
+mod assets;
+mod audio;
+mod autofill;
+mod autostart;
 mod champ_select;
+mod chat;
+mod clash;
 mod commands;
+mod config;
+mod connection;
+mod crash;
+mod dodge;
+mod end_of_game;
+mod enemy_team;
+mod error;
+mod export;
+mod friends;
+mod gameflow;
+mod headless;
+mod honor;
+mod hotkeys;
+mod i18n;
+mod invites;
+mod item_sets;
+mod lcu_events;
+mod livegame;
 mod lobby;
+mod logging;
+mod lolalytics;
+mod mastery;
+mod match_history;
+mod mute;
+mod notifications;
+mod obs;
+mod opgg;
+mod overlay;
+mod penalties;
+mod played_with;
+mod post_game;
+mod premades;
+mod presets;
+mod profiles;
+mod queue;
+mod ranked;
 mod region;
+mod replays;
+mod report;
+mod requeue;
+mod riot_api;
+mod rules;
+mod runes;
+mod session_stats;
+mod single_instance;
+mod skins;
+mod smurf;
+mod spectate;
+mod speech;
+mod spells;
 mod state;
+mod storage;
+mod streamer;
 mod summoner;
+mod timer;
+mod trades;
+mod tray;
+mod updater;
 mod utils;
+mod votes;
+mod win_probability;
 
-use crate::champ_select::ChampSelectSession;
+use assets::AssetState;
+use champ_select::{ChampSelectSession, HandledGames};
 use commands::{
-    app_ready, call_opgg_api, dodge, enable_dodge, get_config, get_lcu_info, get_lcu_state, open_opgg_link,
-    set_config,
+    add_player_note, add_to_blocklist, app_ready, apply_preset, call_opgg_api, call_opgg_api_batch,
+    cancel_dodge, cancel_requeue, check_for_updates, clear_item_sets, copy_lobby_to_clipboard,
+    delete_preset, detect_premades, dodge, download_replay, duplicate_profile, enable_dodge,
+    export_config, export_lobby, get_autofill_flags, get_blocklist, get_champ_select_session,
+    get_champion_data, get_champion_skins, get_champion_stat_links, get_config, get_crash_reports,
+    get_dodge_advice, get_dodge_cost, get_encounters, get_enemy_team, get_friends,
+    get_gameflow_phase, get_invitations, get_lcu_info, get_lcu_state, get_live_events,
+    get_live_game, get_live_players, get_lobby_mastery, get_lobby_ranks, get_lobby_report,
+    get_participant_matches, get_penalties, get_player_annotations, get_player_chat,
+    get_queue_status, get_queues, get_rank_emblems, get_session_stats, get_smurf_scores,
+    get_win_probability, import_config, install_update, list_opgg_tools, list_presets,
+    list_profiles, lookup_champion, open_opgg_link, randomize_skin, remove_from_blocklist,
+    reroll_champion, reset_config, respond_to_invite, respond_to_trade, save_preset, scout_clash,
+    search_champion, set_all_muted, set_auto_accept, set_auto_ban, set_auto_item_sets,
+    set_auto_runes, set_config, set_log_level, set_player_tags, set_players_muted, spectate,
+    swap_bench_champion, switch_profile,
 };
-use futures_util::StreamExt;
-use serde::{Deserialize, Serialize};
-use shaco::model::ws::LcuEvent;
-use shaco::rest::RESTClient;
-use shaco::utils::process_info;
-use shaco::ws::LcuWebsocketClient;
-use shaco::{model::ws::LcuSubscriptionType::JsonApiEvent, rest::LCUClientInfo};
-use std::time::Duration;
-use tauri::{AppHandle, Manager};
+use config::Config;
+use friends::Friend;
+use gameflow::GameflowPhase;
+use i18n::I18nState;
+use livegame::LiveGameState;
+use obs::ObsState;
+use opgg::OpggState;
+use presets::Preset;
+use profiles::ProfileState;
+use queue::QueueState;
+use riot_api::RiotApiState;
+use session_stats::SessionState;
+use timer::TimerState;
+use shaco::rest::{LCUClientInfo, RESTClient};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{atomic::AtomicU64, mpsc::Sender},
+    time::Instant,
+};
+use storage::Storage;
+use streamer::StreamerState;
+use tauri::{async_runtime::JoinHandle, Manager};
 use tokio::sync::Mutex;
 
 struct LCU(Mutex<LCUState>);
@@ -31,41 +122,125 @@ struct LCU(Mutex<LCUState>);
 pub struct LCUState {
     pub connected: bool,
     pub data: Option<LCUClientInfo>,
+    pub app_client: Option<RESTClient>,
+    pub remoting_client: Option<RESTClient>,
 }
 
 struct ManagedDodgeState(Mutex<DodgeState>);
 
+struct ManagedOpgg(Mutex<OpggState>);
+
+struct ManagedAssets(Mutex<AssetState>);
+
+struct ManagedRiotApi(Mutex<RiotApiState>);
+
+struct ManagedLiveGame(Mutex<LiveGameState>);
+
+struct ManagedSession(Mutex<SessionState>);
+
+struct ManagedQueue(Mutex<QueueState>);
+
 pub struct DodgeState {
     pub last_dodge: Option<u64>,
     pub enabled: Option<u64>,
+    /// Game the blocklist rule already armed a dodge for, so cancelling it sticks.
+    pub auto_armed: Option<u64>,
+    /// Game whose armed dodge is counting down to the end of finalization.
+    pub scheduled: Option<u64>,
+    /// When a manual dodge waiting out its grace period goes through.
+    pub pending: Option<Instant>,
+    /// Game the dodge rules were already evaluated for.
+    pub rules_checked: Option<u64>,
 }
 
-struct AppConfig(Mutex<Config>);
+struct ManagedGameflowState(Mutex<GameflowPhase>);
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-struct Config {
-    pub auto_open: bool,
-    pub auto_accept: bool,
-    pub accept_delay: u32,
-    #[serde(default = "default_provider")]
-    pub multi_provider: String,
-}
+struct ManagedChampSelectState(Mutex<Option<ChampSelectSession>>);
 
-fn default_provider() -> String {
-    "opgg".to_string()
-}
+struct ManagedHandledGames(Mutex<HandledGames>);
+
+/// Game whose honor ballot was already handled.
+struct ManagedHonor(Mutex<Option<i64>>);
+
+struct ManagedTimerState(Mutex<TimerState>);
+
+struct ManagedLogging(logging::Logging);
+
+struct ManagedProfiles(Mutex<ProfileState>);
+
+/// The friend list keyed by puuid, to tell which presence updates change a friend's activity.
+struct ManagedFriends(Mutex<BTreeMap<String, Friend>>);
+
+/// When a requeue waiting out its delay goes through.
+struct ManagedRequeue(Mutex<Option<Instant>>);
+
+/// Ids of the pending lobby invites already handled.
+struct ManagedInvites(Mutex<BTreeSet<String>>);
+
+/// Loadout presets keyed by champion id.
+struct ManagedPresets(Mutex<BTreeMap<i64, Preset>>);
+
+struct ManagedStorage(Mutex<Storage>);
+
+/// Bytes of the update being installed downloaded so far.
+struct ManagedUpdateProgress(AtomicU64);
+
+/// Queues text for the thread that owns the text to speech voice.
+struct ManagedSpeech(Mutex<Sender<String>>);
+
+/// Whether streamer mode is on and whose names it hides.
+struct ManagedStreamer(Mutex<StreamerState>);
+
+/// Locale files and the language generated text is written in.
+struct ManagedI18n(Mutex<I18nState>);
+
+/// The local API server while it runs.
+struct ManagedApi(Mutex<Option<JoinHandle<()>>>);
+
+/// The OBS server and what it last broadcast.
+struct ManagedObs(Mutex<ObsState>);
+
+struct AppConfig(Mutex<Config>);
 
 fn main() {
     tauri::Builder::default()
+        .plugin(single_instance::init())
         .manage(LCU(Mutex::new(LCUState {
             connected: false,
             data: None,
+            app_client: None,
+            remoting_client: None,
         })))
-        .manage(ManagedDodgeState(Mutex::new(DodgeState {
-            last_dodge: None,
-            enabled: None,
-        })))
+        .manage(ManagedGameflowState(Mutex::new(GameflowPhase::None)))
+        .manage(ManagedFriends(Mutex::new(BTreeMap::new())))
+        .manage(ManagedInvites(Mutex::new(BTreeSet::new())))
+        .manage(ManagedRequeue(Mutex::new(None)))
+        .manage(ManagedChampSelectState(Mutex::new(None)))
+        .manage(ManagedHandledGames(Mutex::new(HandledGames::default())))
+        .manage(ManagedHonor(Mutex::new(None)))
+        .manage(ManagedTimerState(Mutex::new(TimerState::default())))
+        .manage(ManagedOpgg(Mutex::new(OpggState::default())))
+        .manage(ManagedAssets(Mutex::new(AssetState::default())))
+        .manage(ManagedRiotApi(Mutex::new(RiotApiState::default())))
+        .manage(ManagedLiveGame(Mutex::new(LiveGameState::default())))
+        .manage(ManagedSession(Mutex::new(SessionState::default())))
+        .manage(ManagedQueue(Mutex::new(QueueState::default())))
+        .manage(ManagedSpeech(Mutex::new(speech::start())))
+        .manage(ManagedUpdateProgress(AtomicU64::new(0)))
+        .manage(ManagedApi(Mutex::new(None)))
+        .manage(ManagedObs(Mutex::new(ObsState::default())))
+        .system_tray(tray::build())
+        .on_system_tray_event(|app_handle, event| tray::handle_event(app_handle, event))
+        .on_window_event(|event| {
+            // The hidden overlay window would otherwise keep the app running. Headless mode
+            // closes the main window itself and keeps going.
+            if event.window().label() == "main"
+                && matches!(event.event(), tauri::WindowEvent::Destroyed)
+                && !headless::enabled()
+            {
+                event.window().app_handle().exit(0);
+            }
+        })
         .setup(|app| {
             let app_handle = app.handle();
             let cfg_folder = app.path_resolver().app_config_dir().unwrap();
@@ -73,96 +248,55 @@ fn main() {
                 std::fs::create_dir(&cfg_folder).unwrap();
             }
 
+            // Logging starts before the config is loaded so its warnings aren't lost, and
+            // switches to the configured level right after.
+            let data_dir = app.path_resolver().app_data_dir().unwrap();
+            let log_dir = data_dir.join("logs");
+            std::fs::create_dir_all(&log_dir).unwrap();
+            let logging = logging::init(&log_dir, "info", headless::enabled());
+
             let cfg_path = cfg_folder.join("config.json");
-            if !cfg_path.exists() {
-                let cfg = Config {
-                    auto_open: true,
-                    auto_accept: false,
-                    accept_delay: 2000,
-                    multi_provider: "opgg".to_string(),
-                };
-
-                let cfg_json = serde_json::to_string(&cfg).unwrap();
-                std::fs::write(&cfg_path, cfg_json).unwrap();
+            let cfg = config::load_or_default(&cfg_path);
+            if let Err(e) = logging.set_level(&cfg.log_level) {
+                tracing::warn!("Failed to apply log level from config.json: {}", e);
             }
-
-            let cfg_json = std::fs::read_to_string(&cfg_path).unwrap();
-            let cfg: Config = serde_json::from_str(&cfg_json).unwrap();
+            app.manage(ManagedLogging(logging));
+            crash::install(app_handle.clone());
+            app.manage(ManagedDodgeState(Mutex::new(dodge::load_state(&data_dir))));
+            app.manage(ManagedStorage(Mutex::new(Storage::open_or_in_memory(
+                &data_dir.join("reveal.db"),
+            ))));
+
+            hotkeys::register(&app_handle, &cfg.hotkeys);
+            tray::set_auto_accept(&app_handle, cfg.auto_accept);
+            autostart::apply(&app_handle, cfg.launch_on_startup);
+            autostart::hide_if_minimized(&app_handle);
+            if headless::enabled() {
+                headless::start(&app_handle);
+            }
+            app.manage(ManagedI18n(Mutex::new(I18nState::new(&cfg.language))));
+            app.manage(ManagedStreamer(Mutex::new(StreamerState::new(
+                cfg.streamer_mode,
+            ))));
+            tauri::async_runtime::spawn(api::apply(app_handle.clone(), cfg.local_api.clone()));
+            tauri::async_runtime::spawn(obs::apply(app_handle.clone(), cfg.obs.clone()));
+            obs::forward_events(&app_handle);
             app.manage(AppConfig(Mutex::new(cfg)));
+            app.manage(ManagedProfiles(Mutex::new(ProfileState {
+                active: profiles::load_active(&cfg_folder),
+            })));
+            app.manage(ManagedPresets(Mutex::new(presets::load(&cfg_folder))));
+            config::watch_config(app_handle.clone(), cfg_path);
 
+            let assets_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                let mut connected = true;
-
-                loop {
-                    let args = process_info::get_league_process_args();
-                    if args.is_none() {
-                        if connected {
-                            println!("Waiting for League Client to open...");
-                            connected = false;
-                            app_handle.emit_all("lcu_state_update", false).unwrap();
-                        }
-
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                        continue;
-                    }
-
-                    let args = args.unwrap();
-
-                    let lcu_info = process_info::get_auth_info(args).unwrap();
-                    let app_client = RESTClient::new(lcu_info.clone(), false).unwrap();
-                    let remoting_client = RESTClient::new(lcu_info.clone(), true).unwrap();
-
-                    let cloned_app_handle = app_handle.clone();
-                    let lcu = cloned_app_handle.state::<LCU>();
-
-                    connected = true;
-                    app_handle.emit_all("lcu_state_update", true).unwrap();
-
-                    let mut lcu = lcu.0.lock().await;
-                    lcu.connected = true;
-                    lcu.data = Some(lcu_info);
-
-                    drop(lcu);
-
-                    // The websocket event API will not be opened until a few seconds after the client is opened.
-                    let mut ws = match LcuWebsocketClient::connect().await {
-                        Ok(ws) => ws,
-                        Err(_) => {
-                            let mut attempts = 0;
-                            loop {
-                                tokio::time::sleep(Duration::from_secs(3)).await;
-                                if attempts > 5 {
-                                    panic!("Failed to connect to League Client!");
-                                }
-
-                                attempts += 1;
-                                match LcuWebsocketClient::connect().await {
-                                    Ok(ws) => break ws,
-                                    Err(_) => continue,
-                                }
-                            }
-                        }
-                    };
-
-                    ws.subscribe(JsonApiEvent("/lol-gameflow/v1/gameflow-phase".to_string()))
-                        .await
-                        .unwrap();
-
-                    ws.subscribe(JsonApiEvent("/lol-champ-select/v1/session".to_string()))
-                        .await
-                        .unwrap();
-
-                    println!("Connected to League Client!");
-
-                    let state = state::get_gameflow_state(&remoting_client).await;
-                    state::handle_client_state(state, &app_handle, &remoting_client, &app_client)
-                        .await;
-
-                    while let Some(msg) = ws.next().await {
-                        handle_ws_message(msg, &app_handle, &remoting_client, &app_client).await;
-                    }
+                if let Err(e) = assets::get_assets(&assets_handle).await {
+                    tracing::warn!("Failed to load champion data: {}", e);
                 }
             });
+            tauri::async_runtime::spawn(updater::check_on_startup(app_handle.clone()));
+            tauri::async_runtime::spawn(crash::upload_pending(app_handle.clone()));
+            tauri::async_runtime::spawn(connection::watch_lcu(app_handle));
 
             Ok(())
         })
@@ -175,102 +309,85 @@ fn main() {
             open_opgg_link,
             dodge,
             enable_dodge,
-            call_opgg_api
+            call_opgg_api,
+            get_gameflow_phase,
+            set_log_level,
+            list_profiles,
+            switch_profile,
+            duplicate_profile,
+            export_config,
+            import_config,
+            reset_config,
+            set_auto_accept,
+            set_auto_ban,
+            get_champ_select_session,
+            reroll_champion,
+            swap_bench_champion,
+            scout_clash,
+            get_lobby_ranks,
+            get_lobby_mastery,
+            get_encounters,
+            add_player_note,
+            set_player_tags,
+            get_player_annotations,
+            add_to_blocklist,
+            remove_from_blocklist,
+            get_blocklist,
+            detect_premades,
+            get_smurf_scores,
+            get_autofill_flags,
+            get_win_probability,
+            get_dodge_advice,
+            cancel_dodge,
+            get_dodge_cost,
+            get_champion_stat_links,
+            copy_lobby_to_clipboard,
+            export_lobby,
+            get_lobby_report,
+            list_opgg_tools,
+            call_opgg_api_batch,
+            get_champion_data,
+            lookup_champion,
+            search_champion,
+            get_champion_skins,
+            get_queues,
+            get_rank_emblems,
+            get_participant_matches,
+            get_session_stats,
+            get_live_game,
+            get_live_players,
+            get_live_events,
+            get_enemy_team,
+            set_auto_runes,
+            set_auto_item_sets,
+            clear_item_sets,
+            list_presets,
+            save_preset,
+            delete_preset,
+            apply_preset,
+            randomize_skin,
+            respond_to_trade,
+            set_all_muted,
+            set_players_muted,
+            get_player_chat,
+            get_friends,
+            get_invitations,
+            respond_to_invite,
+            cancel_requeue,
+            get_queue_status,
+            get_penalties,
+            download_replay,
+            spectate,
+            check_for_updates,
+            install_update,
+            get_crash_reports
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
-
-async fn handle_ws_message(
-    msg: LcuEvent,
-    app_handle: &AppHandle,
-    remoting_client: &RESTClient,
-    app_client: &RESTClient,
-) {
-    let msg_type = msg.subscription_type.to_string();
-
-    match msg_type.as_str() {
-        "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase" => {
-            let client_state = msg.data.to_string().replace('\"', "");
-            
-            // Emit the gameflow state to frontend
-            app_handle.emit_all("gameflow_state_update", &client_state).unwrap();
-            
-            state::handle_client_state(client_state, app_handle, remoting_client, app_client).await;
-        }
-        "OnJsonApiEvent_lol-champ-select_v1_session" => {
-            let champ_select = serde_json::from_value::<ChampSelectSession>(msg.data.clone());
-            if champ_select.is_err() {
-                println!("Failed to parse champ select session!, {:?}", champ_select.err());
-                return;
-            }
-
-            let champ_select = champ_select.unwrap();
-            println!("Champion select session detected - Phase: {}", champ_select.timer.phase);
-            
-            // Handle champion select start for all phases except FINALIZATION
-            if champ_select.timer.phase != "FINALIZATION" && champ_select.timer.phase != "" {
-                // Instead of waiting for gameflow state, handle it directly from WebSocket
-                let cloned_app_handle = app_handle.clone();
-                let cloned_app_client = app_client.clone();
-                let cloned_remoting = remoting_client.clone();
-                
-                tauri::async_runtime::spawn(async move {
-                    // Small delay to ensure champion select is fully loaded
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                    
-                    // Get config inside the async block to avoid lifetime issues
-                    let cfg = cloned_app_handle.state::<AppConfig>();
-                    let cfg = cfg.0.lock().await;
-                    
-                    crate::champ_select::handle_champ_select_start(
-                        &cloned_app_client,
-                        &cloned_remoting,
-                        &cfg,
-                        &cloned_app_handle,
-                    ).await;
-                });
-            }
-            
-            if champ_select.timer.phase == "FINALIZATION" {
-                let time = champ_select.timer.adjusted_time_left_in_phase;
-                let cloned_remoting = remoting_client.clone();
-                let game_id = champ_select.game_id;
-                let dodge_state = app_handle.state::<ManagedDodgeState>();
-                let mut dodge_state = dodge_state.0.lock().await;
-
-                if let Some(last_dodge) = dodge_state.last_dodge {
-                    if last_dodge == game_id {
-                        return;
-                    }
-                }
-
-                if (dodge_state.enabled.is_some() && dodge_state.enabled.unwrap() != game_id)
-                    || dodge_state.enabled.is_none()
-                {
-                    return;
-                }
-
-                dodge_state.last_dodge = Some(game_id);
-                drop(dodge_state);
-
-                println!("Spawned task to dodge in finalization timer: {}ms", time);
-
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(Duration::from_millis(time)).await;
-                    println!("Last second dodge calling quit endpoint...");
-                    let _resp = cloned_remoting
-                        .post(
-                            "/lol-login/v1/session/invoke?destination=lcdsServiceProxy&method=call&args=[\"\",\"teambuilder-draft\",\"quitV2\",\"\"]".to_string(),
-                            serde_json::json!({}),
-                        )
-                        .await
-                        .unwrap();
-                });
-            }
-        }
-        _ => {
-            println!("Unhandled Message: {}", msg_type);
-        }
-    }
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| match event {
+            tauri::RunEvent::Updater(event) => updater::handle_event(app_handle, event),
+            // Without any window left, headless mode would exit right after starting.
+            tauri::RunEvent::ExitRequested { api, .. } if headless::enabled() => api.prevent_exit(),
+            _ => {}
+        });
 }
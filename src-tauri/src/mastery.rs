@@ -0,0 +1,133 @@
+use crate::{champ_select::ChampSelectSession, error::AppError, lobby::Lobby, summoner};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const TOP_CHAMPIONS: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChampionMastery {
+    pub champion_id: i64,
+    pub champion_level: i64,
+    pub champion_points: i64,
+    pub last_play_time: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantMastery {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    pub top: Vec<ChampionMastery>,
+    pub picked: Option<ChampionMastery>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PickMastery {
+    pub puuid: String,
+    pub cell_id: i64,
+    pub champion_id: i64,
+    pub mastery: Option<ChampionMastery>,
+}
+
+pub async fn get_champion_mastery(
+    remoting_client: &RESTClient,
+    puuid: &str,
+) -> Result<Vec<ChampionMastery>, AppError> {
+    let summoner = summoner::get_summoner_by_puuid(remoting_client, puuid).await?;
+    let mastery = remoting_client
+        .get(format!(
+            "/lol-collections/v1/inventories/{}/champion-mastery",
+            summoner.summoner_id
+        ))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    let mut mastery: Vec<ChampionMastery> = serde_json::from_value(mastery)?;
+    mastery.sort_by(|a, b| b.champion_points.cmp(&a.champion_points));
+    Ok(mastery)
+}
+
+/// The champion a teammate has picked so far. Players with hidden names have no puuid in the
+/// session, so they never match.
+fn picked_champion(session: Option<&ChampSelectSession>, puuid: &str) -> Option<i64> {
+    session?
+        .my_team
+        .iter()
+        .find(|p| !p.puuid.is_empty() && p.puuid == puuid && p.champion_id != 0)
+        .map(|p| p.champion_id)
+}
+
+pub async fn get_lobby_mastery(
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    session: Option<&ChampSelectSession>,
+) -> Vec<ParticipantMastery> {
+    let lookups = lobby.participants.iter().map(|participant| async move {
+        let mastery = match get_champion_mastery(remoting_client, &participant.puuid).await {
+            Ok(mastery) => mastery,
+            Err(e) => {
+                warn!(
+                    "Failed to fetch mastery for {}#{}: {:?}",
+                    participant.game_name, participant.game_tag, e
+                );
+                Vec::new()
+            }
+        };
+
+        let picked = picked_champion(session, &participant.puuid)
+            .and_then(|id| mastery.iter().find(|m| m.champion_id == id).cloned());
+
+        ParticipantMastery {
+            puuid: participant.puuid.clone(),
+            game_name: participant.game_name.clone(),
+            game_tag: participant.game_tag.clone(),
+            top: mastery.into_iter().take(TOP_CHAMPIONS).collect(),
+            picked,
+        }
+    });
+
+    join_all(lookups).await
+}
+
+/// Emits `pick_mastery` with the player's mastery on the champion they just locked in.
+pub async fn emit_pick_mastery(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    cell_id: i64,
+    champion_id: i64,
+) {
+    let Some(player) = session
+        .my_team
+        .iter()
+        .find(|p| p.cell_id == cell_id && !p.puuid.is_empty())
+    else {
+        return;
+    };
+
+    let mastery = match get_champion_mastery(remoting_client, &player.puuid).await {
+        Ok(mastery) => mastery.into_iter().find(|m| m.champion_id == champion_id),
+        Err(e) => {
+            warn!("Failed to fetch mastery for cell {}: {:?}", cell_id, e);
+            return;
+        }
+    };
+
+    app_handle
+        .emit_all(
+            "pick_mastery",
+            PickMastery {
+                puuid: player.puuid.clone(),
+                cell_id,
+                champion_id,
+                mastery,
+            },
+        )
+        .unwrap();
+}
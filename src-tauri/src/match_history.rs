@@ -0,0 +1,163 @@
+use crate::{assets, error::AppError, riot_api};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tauri::AppHandle;
+use tracing::warn;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchHistory {
+    pub games: MatchHistoryGames,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchHistoryGames {
+    pub games: Vec<Game>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Game {
+    pub game_id: i64,
+    pub game_creation: i64,
+    pub game_duration: i64,
+    pub queue_id: i64,
+    pub participants: Vec<GameParticipant>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameParticipant {
+    pub participant_id: i64,
+    pub champion_id: i64,
+    pub team_id: i64,
+    pub stats: ParticipantStats,
+    pub timeline: Timeline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParticipantStats {
+    pub win: bool,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Timeline {
+    pub lane: String,
+    pub role: String,
+}
+
+/// The player's most recent games, newest first. The LCU only includes the requested player
+/// in each game's participant list.
+pub async fn get_match_history(
+    remoting_client: &RESTClient,
+    puuid: &str,
+    count: usize,
+) -> Result<Vec<Game>, AppError> {
+    let history = remoting_client
+        .get(format!(
+            "/lol-match-history/v1/products/lol/{}/matches?begIndex=0&endIndex={}",
+            puuid,
+            count.saturating_sub(1)
+        ))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    let history: MatchHistory = serde_json::from_value(history)?;
+    Ok(history.games.games)
+}
+
+/// The LCU match history, or the Riot API's when the LCU has nothing for this player and an
+/// API key is configured.
+pub async fn get_match_history_with_fallback(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    region: &str,
+    puuid: &str,
+    count: usize,
+) -> Result<Vec<Game>, AppError> {
+    let lcu = get_match_history(remoting_client, puuid, count).await;
+    if matches!(&lcu, Ok(games) if !games.is_empty()) || !riot_api::is_configured(app_handle).await
+    {
+        return lcu;
+    }
+
+    if let Err(e) = &lcu {
+        warn!("LCU match history failed, trying the Riot API: {:?}", e);
+    }
+    riot_api::get_match_history(app_handle, region, puuid, count).await
+}
+
+/// One of a player's recent games from their point of view.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantMatch {
+    pub game_id: i64,
+    /// Unix timestamp in milliseconds.
+    pub game_creation: i64,
+    /// Game length in seconds.
+    pub game_duration: i64,
+    pub queue_id: i64,
+    pub queue_name: Option<String>,
+    pub champion_id: i64,
+    pub champion_name: Option<String>,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    /// (kills + assists) / deaths, deaths counting as at least one.
+    pub kda: f64,
+    pub win: bool,
+}
+
+/// A player's recent games flattened into what the lobby panel shows per game, with champion
+/// and queue names filled in when the asset cache is available.
+pub async fn get_participant_matches(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    region: &str,
+    puuid: &str,
+    count: usize,
+) -> Result<Vec<ParticipantMatch>, AppError> {
+    let games =
+        get_match_history_with_fallback(app_handle, remoting_client, region, puuid, count).await?;
+    let assets = match assets::get_assets(app_handle).await {
+        Ok(assets) => Some(assets),
+        Err(e) => {
+            warn!("Match history without champion and queue names: {:?}", e);
+            None
+        }
+    };
+
+    Ok(games
+        .iter()
+        .filter_map(|game| {
+            let player = game.participants.first()?;
+            let stats = &player.stats;
+            Some(ParticipantMatch {
+                game_id: game.game_id,
+                game_creation: game.game_creation,
+                game_duration: game.game_duration,
+                queue_id: game.queue_id,
+                queue_name: assets
+                    .as_ref()
+                    .and_then(|a| a.queue(game.queue_id))
+                    .map(|q| q.name.clone()),
+                champion_id: player.champion_id,
+                champion_name: assets
+                    .as_ref()
+                    .and_then(|a| a.champion(player.champion_id))
+                    .map(|c| c.name.clone()),
+                kills: stats.kills,
+                deaths: stats.deaths,
+                assists: stats.assists,
+                kda: (stats.kills + stats.assists) as f64 / stats.deaths.max(1) as f64,
+                win: stats.win,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,145 @@
+use crate::{
+    champ_select::{self, Team},
+    error::AppError,
+    lobby::Lobby,
+    streamer, ManagedStorage,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MutedPlayer {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    pub muted: bool,
+}
+
+/// The champ select endpoint only toggles, so it is called just for players whose state
+/// differs from what we want.
+async fn toggle_muted(remoting_client: &RESTClient, player: &Team) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            "/lol-champ-select/v1/toggle-player-muted".to_string(),
+            serde_json::json!({
+                "summonerId": player.summoner_id,
+                "puuid": player.puuid,
+                "obfuscatedSummonerId": player.obfuscated_summoner_id,
+                "obfuscatedPuuid": player.obfuscated_puuid,
+            }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+/// Whether `puuid` is muted in the champ select chat, `None` when they aren't in it. Read
+/// right before each toggle so a player another call already muted isn't flipped back. The
+/// chat participants know who is muted, the session doesn't.
+async fn is_muted(app_client: &RESTClient, puuid: &str) -> Result<Option<bool>, AppError> {
+    let participants = app_client
+        .get("/chat/v5/participants".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    let participants: Lobby = serde_json::from_value(participants)?;
+    Ok(participants
+        .participants
+        .iter()
+        .find(|p| p.cid.contains("champ-select") && p.puuid == puuid)
+        .map(|p| p.muted))
+}
+
+/// Mutes or unmutes our teammates in champ select, all of them when `puuids` is `None`.
+/// Returns the players whose state was changed.
+pub async fn set_muted(
+    app_client: &RESTClient,
+    remoting_client: &RESTClient,
+    puuids: Option<&[String]>,
+    muted: bool,
+) -> Result<Vec<MutedPlayer>, AppError> {
+    let session = champ_select::get_champ_select_session(remoting_client).await?;
+
+    let mut changed = Vec::new();
+    for player in session
+        .my_team
+        .iter()
+        .filter(|p| p.cell_id != session.local_player_cell_id && !p.puuid.is_empty())
+        .filter(|p| puuids.map_or(true, |puuids| puuids.contains(&p.puuid)))
+    {
+        match is_muted(app_client, &player.puuid).await {
+            Ok(Some(current)) if current == muted => continue,
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to read the mute state of {}: {}", player.puuid, e);
+                continue;
+            }
+        }
+
+        if let Err(e) = toggle_muted(remoting_client, player).await {
+            warn!("Failed to toggle mute for {}: {}", player.puuid, e);
+            continue;
+        }
+        changed.push(MutedPlayer {
+            puuid: player.puuid.clone(),
+            game_name: player.game_name.clone(),
+            game_tag: player.tag_line.clone(),
+            muted,
+        });
+    }
+
+    info!(
+        "{} {} players",
+        if muted { "Muted" } else { "Unmuted" },
+        changed.len()
+    );
+    Ok(changed)
+}
+
+/// Mutes lobby players carrying any of `tags` in our notes and emits `players_muted`, once
+/// per game so players unmuted by hand stay unmuted.
+pub async fn auto_mute_tagged(
+    app_handle: AppHandle,
+    app_client: RESTClient,
+    remoting_client: RESTClient,
+    lobby: Lobby,
+    tags: Vec<String>,
+    game_id: u64,
+) {
+    let puuids: Vec<String> = {
+        let storage = app_handle.state::<ManagedStorage>();
+        let storage = storage.0.lock().await;
+        lobby
+            .participants
+            .iter()
+            .filter(
+                |participant| match storage.get_player_annotations(&participant.puuid) {
+                    Ok(annotations) => annotations
+                        .tags
+                        .iter()
+                        .any(|t| tags.iter().any(|tag| tag.eq_ignore_ascii_case(t))),
+                    Err(e) => {
+                        error!("Failed to load tags for {}: {}", participant.puuid, e);
+                        false
+                    }
+                },
+            )
+            .map(|participant| participant.puuid.clone())
+            .collect()
+    };
+    if puuids.is_empty()
+        || !champ_select::first_time_for_game(&app_handle, game_id, |h| &mut h.auto_mute).await
+    {
+        return;
+    }
+
+    match set_muted(&app_client, &remoting_client, Some(&puuids), true).await {
+        Ok(muted) if !muted.is_empty() => {
+            streamer::emit_all(&app_handle, "players_muted", &muted).await;
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to mute tagged players: {}", e),
+    }
+}
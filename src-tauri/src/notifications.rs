@@ -0,0 +1,37 @@
+use crate::AppConfig;
+use tauri::{api::notification::Notification, AppHandle, Manager};
+use tracing::{debug, warn};
+
+#[derive(Clone, Copy, Debug)]
+pub enum NotificationKind {
+    Blocklisted,
+    QueuePop,
+    DodgeExecuted,
+    LobbyReport,
+    Vote,
+}
+
+/// Shows a native notification if notifications for `kind` are turned on.
+pub async fn notify(app_handle: &AppHandle, kind: NotificationKind, title: &str, body: &str) {
+    let enabled = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        let notifications = &config.notifications;
+        match kind {
+            NotificationKind::Blocklisted => notifications.blocklisted,
+            NotificationKind::QueuePop => notifications.queue_pop,
+            NotificationKind::DodgeExecuted => notifications.dodge_executed,
+            NotificationKind::LobbyReport => notifications.lobby_report,
+            NotificationKind::Vote => notifications.vote,
+        }
+    };
+    if !enabled {
+        return;
+    }
+
+    debug!("Showing {:?} notification", kind);
+    let identifier = &app_handle.config().tauri.bundle.identifier;
+    if let Err(e) = Notification::new(identifier).title(title).body(body).show() {
+        warn!("Failed to show {:?} notification: {:?}", kind, e);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{config::ObsConfig, streamer, ManagedObs};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header::ORIGIN, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, SocketAddr},
+};
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio::sync::broadcast::{self, error::RecvError, Sender};
+use tracing::{error, info, warn};
+
+/// A browser source for OBS that renders what the WebSocket sends.
+const PAGE: &str = include_str!("../obs/index.html");
+
+/// Messages a slow client can fall behind by before it starts missing some.
+const CHANNEL_CAPACITY: usize = 32;
+
+/// Events passed on to OBS as they are emitted. The lobby report is broadcast by
+/// `report::get_lobby_report` itself since it isn't an event.
+const FORWARDED_EVENTS: [&str; 4] = [
+    "dodge_countdown",
+    "dodge_cancelled",
+    "dodge_executed",
+    "session_stats_updated",
+];
+
+pub struct ObsState {
+    sender: Sender<String>,
+    server: Option<JoinHandle<()>>,
+    /// Latest message per event, sent to clients when they connect.
+    last: BTreeMap<String, String>,
+}
+
+impl Default for ObsState {
+    fn default() -> Self {
+        ObsState {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            server: None,
+            last: BTreeMap::new(),
+        }
+    }
+}
+
+pub async fn is_running(app_handle: &AppHandle) -> bool {
+    app_handle
+        .state::<ManagedObs>()
+        .0
+        .lock()
+        .await
+        .server
+        .is_some()
+}
+
+/// Sends `payload` to every connected client as `{"event":...,"payload":...}`, scrubbed when
+/// streamer mode is on. Does nothing while the server is off.
+pub async fn broadcast<T: Serialize>(app_handle: &AppHandle, event: &str, payload: T) {
+    let obs = app_handle.state::<ManagedObs>();
+    let mut obs = obs.0.lock().await;
+    if obs.server.is_none() {
+        return;
+    }
+
+    let payload = streamer::scrub(app_handle, payload).await;
+    let message = serde_json::json!({ "event": event, "payload": payload }).to_string();
+    obs.last.insert(event.to_string(), message.clone());
+    // Failing only means nobody is connected.
+    let _ = obs.sender.send(message);
+}
+
+/// Passes `FORWARDED_EVENTS` on to `broadcast`.
+pub fn forward_events(app_handle: &AppHandle) {
+    for event in FORWARDED_EVENTS {
+        let forward_handle = app_handle.clone();
+        app_handle.listen_global(event, move |e| {
+            let payload: Value = e
+                .payload()
+                .and_then(|payload| serde_json::from_str(payload).ok())
+                .unwrap_or(Value::Null);
+            let app_handle = forward_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                broadcast(&app_handle, event, payload).await;
+            });
+        });
+    }
+}
+
+async fn page() -> Html<&'static str> {
+    Html(PAGE)
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app_handle: AppHandle,
+    port: u16,
+}
+
+/// Browsers let any page open a WebSocket to localhost, so only our own page may connect, and
+/// clients like OBS that send no `Origin` at all.
+async fn ws(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if let Some(origin) = headers.get(ORIGIN) {
+        let origin = origin.to_str().unwrap_or_default();
+        let own = [
+            format!("http://127.0.0.1:{}", state.port),
+            format!("http://localhost:{}", state.port),
+        ];
+        if !own.iter().any(|own| own == origin) {
+            warn!("Refused an OBS connection from {}", origin);
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+    upgrade.on_upgrade(move |socket| client(state.app_handle, socket))
+}
+
+async fn client(app_handle: AppHandle, mut socket: WebSocket) {
+    let (last, mut receiver) = {
+        let obs = app_handle.state::<ManagedObs>();
+        let obs = obs.0.lock().await;
+        let last: Vec<String> = obs.last.values().cloned().collect();
+        (last, obs.sender.subscribe())
+    };
+    info!("OBS client connected");
+
+    for message in last {
+        if socket.send(Message::Text(message)).await.is_err() {
+            return;
+        }
+    }
+    loop {
+        match receiver.recv().await {
+            Ok(message) => {
+                if socket.send(Message::Text(message)).await.is_err() {
+                    info!("OBS client disconnected");
+                    return;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => warn!("OBS client missed {} messages", skipped),
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Stops the running server, if any, and starts one on localhost when `config` enables it.
+/// `/` serves the browser source and `/ws` the WebSocket. Called at startup and whenever the
+/// `obs` config changes.
+pub async fn apply(app_handle: AppHandle, config: ObsConfig) {
+    let obs = app_handle.state::<ManagedObs>();
+    let mut obs = obs.0.lock().await;
+    if let Some(handle) = obs.server.take() {
+        handle.abort();
+        // Wait for the listener to close so the port can be bound again.
+        let _ = handle.await;
+        obs.last.clear();
+        info!("Stopped the OBS server");
+    }
+    if !config.enabled {
+        return;
+    }
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to start the OBS server on {}: {}", addr, e);
+            return;
+        }
+    };
+
+    let router = Router::new()
+        .route("/", get(page))
+        .route("/ws", get(ws))
+        .with_state(ServerState {
+            app_handle: app_handle.clone(),
+            port: config.port,
+        });
+    info!("OBS browser source at http://{}/", addr);
+    obs.server = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            error!("OBS server stopped: {}", e);
+        }
+    }));
+}
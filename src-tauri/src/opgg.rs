@@ -0,0 +1,311 @@
+use crate::{config::McpConfig, error::AppError, AppConfig, ManagedOpgg};
+use futures_util::{stream, StreamExt};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Semaphore;
+use tracing::{debug, error, warn};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// A lobby report fires a lookup per player, these keep it from hammering the server.
+const MAX_CONCURRENT: usize = 3;
+const MIN_INTERVAL: Duration = Duration::from_millis(200);
+const BATCH_CONCURRENCY: usize = 5;
+
+#[derive(Serialize, Deserialize)]
+struct MCPRequest<P> {
+    jsonrpc: String,
+    id: u64,
+    method: String,
+    params: P,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MCPParams {
+    name: String,
+    arguments: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MCPResponse {
+    jsonrpc: String,
+    id: u64,
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpTool {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the tool's arguments.
+    pub input_schema: Value,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ToolList {
+    tools: Vec<McpTool>,
+}
+
+pub struct OpggState {
+    /// Tools offered by the MCP server, fetched on first use.
+    pub tools: Option<Vec<McpTool>>,
+    /// Tool results and when they expire, keyed by tool name and arguments.
+    cache: HashMap<String, (Instant, Value)>,
+    client: reqwest::Client,
+    /// Bounds how many requests are in flight at once.
+    limiter: Arc<Semaphore>,
+    /// Earliest time the next request may start.
+    next_slot: Instant,
+}
+
+impl Default for OpggState {
+    fn default() -> Self {
+        OpggState {
+            tools: None,
+            cache: HashMap::new(),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            limiter: Arc::new(Semaphore::new(MAX_CONCURRENT)),
+            next_slot: Instant::now(),
+        }
+    }
+}
+
+/// Forgets the tools and results fetched from the previous endpoint. Called whenever the
+/// `opggMcp` config changes.
+pub async fn reset(app_handle: &AppHandle) {
+    let opgg = app_handle.state::<ManagedOpgg>();
+    let mut opgg = opgg.0.lock().await;
+    opgg.tools = None;
+    opgg.cache.clear();
+}
+
+/// Network failures and overloaded servers are worth another try, bad requests are not.
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.status()
+            .is_some_and(|s| s.is_server_error() || s == StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Waits until requests are spaced at least `MIN_INTERVAL` apart.
+async fn wait_for_slot(app_handle: &AppHandle) {
+    let start = {
+        let opgg = app_handle.state::<ManagedOpgg>();
+        let mut opgg = opgg.0.lock().await;
+        let start = opgg.next_slot.max(Instant::now());
+        opgg.next_slot = start + MIN_INTERVAL;
+        start
+    };
+    tokio::time::sleep_until(start.into()).await;
+}
+
+async fn post<P: Serialize>(
+    client: &reqwest::Client,
+    endpoint: &McpConfig,
+    request: &MCPRequest<P>,
+) -> Result<MCPResponse, reqwest::Error> {
+    let mut builder = client
+        .post(&endpoint.url)
+        .json(request)
+        .header("Content-Type", "application/json");
+    if !endpoint.api_key.is_empty() {
+        builder = builder.bearer_auth(&endpoint.api_key);
+    }
+    for (name, value) in &endpoint.headers {
+        builder = builder.header(name, value);
+    }
+
+    builder
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<MCPResponse>()
+        .await
+}
+
+async fn send<P: Serialize>(
+    app_handle: &AppHandle,
+    method: &str,
+    params: P,
+) -> Result<Value, AppError> {
+    let (client, limiter) = {
+        let opgg = app_handle.state::<ManagedOpgg>();
+        let opgg = opgg.0.lock().await;
+        (opgg.client.clone(), opgg.limiter.clone())
+    };
+    let endpoint = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.opgg_mcp.clone()
+    };
+    let _permit = limiter
+        .acquire_owned()
+        .await
+        .map_err(|e| AppError::Provider(e.to_string()))?;
+
+    let request_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+
+    let request = MCPRequest {
+        jsonrpc: "2.0".to_string(),
+        id: request_id,
+        method: method.to_string(),
+        params,
+    };
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    let mcp_response = loop {
+        wait_for_slot(app_handle).await;
+        match post(&client, &endpoint, &request).await {
+            Ok(mcp_response) => break mcp_response,
+            Err(e) if is_transient(&e) && attempt < MAX_ATTEMPTS => {
+                warn!(
+                    "OP.GG API request failed, retrying in {:?}: {:?}",
+                    backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) if e.is_decode() => {
+                error!("Failed to parse OP.GG API response: {:?}", e);
+                return Err(AppError::Parse(format!("{:?}", e)));
+            }
+            Err(e) => {
+                error!("Failed to call OP.GG API: {:?}", e);
+                return Err(AppError::Provider(format!("Network error: {:?}", e)));
+            }
+        }
+    };
+
+    if let Some(error) = mcp_response.error {
+        warn!("OP.GG API error: {:?}", error);
+        Err(AppError::Provider(format!("OP.GG API error: {:?}", error)))
+    } else if let Some(result) = mcp_response.result {
+        debug!("OP.GG API success: {:?}", result);
+        Ok(result)
+    } else {
+        Err(AppError::Provider(
+            "No result or error from OP.GG API".to_string(),
+        ))
+    }
+}
+
+/// Calls a tool on OP.GG's MCP server and returns its raw result.
+pub async fn call_tool(
+    app_handle: &AppHandle,
+    function_name: &str,
+    params: Value,
+) -> Result<Value, AppError> {
+    debug!(
+        "Calling OP.GG API function: {} with params: {:?}",
+        function_name, params
+    );
+
+    send(
+        app_handle,
+        "tools/call",
+        MCPParams {
+            name: function_name.to_string(),
+            arguments: params,
+        },
+    )
+    .await
+}
+
+/// The tools OP.GG's MCP server currently offers, with the schema of their arguments.
+pub async fn list_tools(app_handle: &AppHandle) -> Result<Vec<McpTool>, AppError> {
+    let result = send(app_handle, "tools/list", serde_json::json!({})).await?;
+    let list: ToolList = serde_json::from_value(result)?;
+    Ok(list.tools)
+}
+
+/// `call_tool` behind a cache, so reopening the lobby panel doesn't repeat the same lookups.
+/// How long results stay fresh is configured per tool.
+pub async fn call_tool_cached(
+    app_handle: &AppHandle,
+    function_name: &str,
+    params: Value,
+) -> Result<Value, AppError> {
+    let ttl = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.opgg_cache.ttl(function_name)
+    };
+    if ttl.is_zero() {
+        return call_tool(app_handle, function_name, params).await;
+    }
+
+    let key = format!("{}:{}", function_name, params);
+    {
+        let opgg = app_handle.state::<ManagedOpgg>();
+        let mut opgg = opgg.0.lock().await;
+        opgg.cache
+            .retain(|_, (expires, _)| *expires > Instant::now());
+        if let Some((_, result)) = opgg.cache.get(&key) {
+            debug!("Using cached OP.GG result for {}", function_name);
+            return Ok(result.clone());
+        }
+    }
+
+    let result = call_tool(app_handle, function_name, params).await?;
+    let opgg = app_handle.state::<ManagedOpgg>();
+    opgg.0
+        .lock()
+        .await
+        .cache
+        .insert(key, (Instant::now() + ttl, result.clone()));
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub arguments: Value,
+    pub result: Option<Value>,
+    pub error: Option<AppError>,
+}
+
+/// Runs the same tool once per argument set, a few at a time. Results come back in the
+/// order of `arguments`, and one failing lookup doesn't fail the rest.
+pub async fn call_tool_batch(
+    app_handle: &AppHandle,
+    function_name: &str,
+    arguments: Vec<Value>,
+) -> Vec<BatchResult> {
+    stream::iter(arguments)
+        .map(|arguments| async move {
+            match call_tool_cached(app_handle, function_name, arguments.clone()).await {
+                Ok(result) => BatchResult {
+                    arguments,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => BatchResult {
+                    arguments,
+                    result: None,
+                    error: Some(e),
+                },
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect()
+        .await
+}
@@ -0,0 +1,105 @@
+use crate::{
+    headless, lobby::get_lobby_info, obs, region, report, streamer, AppConfig,
+    ManagedChampSelectState,
+};
+use shaco::rest::RESTClient;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowUrl};
+use tracing::{info, warn};
+
+const LABEL: &str = "overlay";
+/// The frontend renders the overlay instead of the main view for this URL.
+const URL: &str = "index.html?overlay";
+/// Champ select needs a moment before every player shows up in the lobby.
+const REPORT_DELAY: Duration = Duration::from_secs(5);
+
+fn window(app_handle: &AppHandle) -> Result<Window, tauri::Error> {
+    if let Some(window) = app_handle.get_window(LABEL) {
+        return Ok(window);
+    }
+
+    WindowBuilder::new(app_handle, LABEL, WindowUrl::App(URL.into()))
+        .title("Reveal overlay")
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .inner_size(360.0, 520.0)
+        .visible(false)
+        .build()
+}
+
+fn show(app_handle: &AppHandle) -> Option<Window> {
+    let window = match window(app_handle) {
+        Ok(window) => window,
+        Err(e) => {
+            warn!("Failed to open the overlay: {:?}", e);
+            return None;
+        }
+    };
+    if let Err(e) = window.show() {
+        warn!("Failed to show the overlay: {:?}", e);
+        return None;
+    }
+    info!("Showing the champ select overlay");
+    Some(window)
+}
+
+/// Shows the overlay for champ select and fills it with the lobby report once it is ready.
+/// The report is built without the overlay too while the OBS server runs, which broadcasts
+/// it. The dodge countdown reaches the overlay through the `dodge_countdown` event every
+/// window gets.
+pub async fn show_for_champ_select(
+    app_handle: AppHandle,
+    app_client: RESTClient,
+    remoting_client: RESTClient,
+) {
+    let enabled = app_handle.state::<AppConfig>().0.lock().await.overlay;
+    let window = if enabled && !headless::enabled() {
+        show(&app_handle)
+    } else {
+        None
+    };
+    if window.is_none() && !obs::is_running(&app_handle).await {
+        return;
+    }
+
+    tokio::time::sleep(REPORT_DELAY).await;
+    let region_info = match region::get_region_info(&app_client).await {
+        Ok(region_info) => region_info,
+        Err(e) => {
+            warn!("No region for the overlay report: {}", e);
+            return;
+        }
+    };
+    let lobby = get_lobby_info(&app_client, &remoting_client).await;
+    streamer::remember_lobby(&app_handle, &lobby).await;
+    let session = app_handle
+        .state::<ManagedChampSelectState>()
+        .0
+        .lock()
+        .await
+        .clone();
+    let report = report::get_lobby_report(
+        &app_handle,
+        &remoting_client,
+        &lobby,
+        session.as_ref(),
+        region_info.multi_search_region(),
+    )
+    .await;
+
+    // Champ select may be over by the time the report is done.
+    if let Some(window) = window.filter(|w| w.is_visible().unwrap_or(false)) {
+        let report = streamer::scrub(&app_handle, report).await;
+        window.emit("overlay_report", report).unwrap();
+    }
+}
+
+pub fn hide(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_window(LABEL) {
+        if let Err(e) = window.hide() {
+            warn!("Failed to hide the overlay: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tracing::warn;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SearchState {
+    errors: Vec<SearchError>,
+    low_priority_data: LowPriorityData,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SearchError {
+    error_type: String,
+    penalty_time_remaining: f64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LowPriorityData {
+    penalty_time_remaining: f64,
+    reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeaverBusterNotification {
+    pub id: i64,
+    /// e.g. `TaintedWarning`, `PreQueueLowPriority`, `PreQueueTimeBan` or
+    /// `RankedRestrictedGames`.
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    pub punished_games_remaining: i64,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(rename_all = "camelCase")]
+pub enum LeaverBusterLevel {
+    None,
+    Warning,
+    LowPriority,
+    RankedRestricted,
+    QueueBan,
+}
+
+impl LeaverBusterNotification {
+    fn level(&self) -> LeaverBusterLevel {
+        match self.notification_type.as_str() {
+            t if t.contains("TimeBan") || t.contains("QueueBan") => LeaverBusterLevel::QueueBan,
+            t if t.contains("RankedRestricted") => LeaverBusterLevel::RankedRestricted,
+            t if t.contains("LowPriority") => LeaverBusterLevel::LowPriority,
+            t if t.contains("Warning") => LeaverBusterLevel::Warning,
+            _ => LeaverBusterLevel::None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueLockout {
+    /// e.g. `QUEUE_DODGER` or `LEAVER_BUSTED`.
+    pub error_type: String,
+    pub seconds_remaining: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Penalties {
+    /// The most severe LeaverBuster penalty the client currently reports.
+    pub leaver_buster_level: LeaverBusterLevel,
+    pub leaver_buster_notifications: Vec<LeaverBusterNotification>,
+    /// Games that still start with a low priority wait.
+    pub low_priority_games_remaining: i64,
+    /// Low priority wait the next queue would start with.
+    pub low_priority_seconds: f64,
+    pub low_priority_reason: String,
+    /// Queue lockouts already running, e.g. from dodging.
+    pub lockouts: Vec<QueueLockout>,
+}
+
+impl Penalties {
+    /// The longest lockout still running, in seconds.
+    pub fn lockout_seconds(&self) -> f64 {
+        self.lockouts
+            .iter()
+            .map(|l| l.seconds_remaining)
+            .fold(0.0, f64::max)
+    }
+}
+
+async fn get_leaver_buster_notifications(
+    remoting_client: &RESTClient,
+) -> Result<Vec<LeaverBusterNotification>, AppError> {
+    let notifications = remoting_client
+        .get("/lol-leaver-buster/v1/notifications".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(notifications)?)
+}
+
+async fn get_search_state(remoting_client: &RESTClient) -> Result<SearchState, AppError> {
+    let state = remoting_client
+        .get("/lol-lobby/v2/lobby/matchmaking/search-state".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(state)?)
+}
+
+/// Collects the LeaverBuster and queue penalties the client reports. Either source failing,
+/// e.g. the search state outside a lobby, only leaves its part empty.
+pub async fn get_penalties(remoting_client: &RESTClient) -> Penalties {
+    let notifications = get_leaver_buster_notifications(remoting_client)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to fetch LeaverBuster notifications: {:?}", e);
+            Vec::new()
+        });
+    let search_state = get_search_state(remoting_client).await.unwrap_or_else(|e| {
+        warn!("Failed to fetch matchmaking search state: {:?}", e);
+        SearchState::default()
+    });
+
+    let leaver_buster_level = notifications
+        .iter()
+        .map(LeaverBusterNotification::level)
+        .fold(LeaverBusterLevel::None, |a, b| if b > a { b } else { a });
+    let low_priority_games_remaining = notifications
+        .iter()
+        .filter(|n| n.level() == LeaverBusterLevel::LowPriority)
+        .map(|n| n.punished_games_remaining)
+        .max()
+        .unwrap_or(0);
+    let lockouts = search_state
+        .errors
+        .into_iter()
+        .filter(|e| !e.error_type.is_empty())
+        .map(|e| QueueLockout {
+            error_type: e.error_type,
+            seconds_remaining: e.penalty_time_remaining,
+        })
+        .collect();
+
+    Penalties {
+        leaver_buster_level,
+        leaver_buster_notifications: notifications,
+        low_priority_games_remaining,
+        low_priority_seconds: search_state.low_priority_data.penalty_time_remaining,
+        low_priority_reason: search_state.low_priority_data.reason,
+        lockouts,
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    gameflow,
+    lobby::Lobby,
+    match_history::{self, Game},
+    streamer, ManagedStorage,
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+const HISTORY_GAMES: usize = 20;
+
+/// How a player did in a game we shared with them.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Performance {
+    pub champion_id: i64,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedGame {
+    pub game_id: i64,
+    pub queue_id: i64,
+    /// Unix timestamp in seconds.
+    pub played_at: i64,
+    /// Whether we won, `None` when the result was never recorded.
+    pub win: Option<bool>,
+    pub same_team: bool,
+    /// Unknown for games that left the match history before the app archived them.
+    pub performance: Option<Performance>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayedWith {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    /// Newest first.
+    pub games: Vec<SharedGame>,
+}
+
+/// Games found in both our match history and theirs. Each history only lists its own
+/// player, so the shared games are the ones with the same id.
+fn shared_games(ours: &HashMap<i64, &Game>, theirs: &[Game]) -> Vec<SharedGame> {
+    theirs
+        .iter()
+        .filter_map(|game| {
+            let us = ours.get(&game.game_id)?.participants.first()?;
+            let them = game.participants.first()?;
+            Some(SharedGame {
+                game_id: game.game_id,
+                queue_id: game.queue_id,
+                played_at: game.game_creation / 1000,
+                win: Some(us.stats.win),
+                same_team: us.team_id == them.team_id,
+                performance: Some(Performance {
+                    champion_id: them.champion_id,
+                    kills: them.stats.kills,
+                    deaths: them.stats.deaths,
+                    assists: them.stats.assists,
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Lobby players we have played with before, from our recent match history and the
+/// encounter log. Encounters cover games that have since dropped out of the history, the
+/// game being set up right now is left out.
+pub async fn find_played_with(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    local_puuid: &str,
+) -> Vec<PlayedWith> {
+    let others: Vec<_> = lobby
+        .participants
+        .iter()
+        .filter(|p| !p.puuid.is_empty() && p.puuid != local_puuid)
+        .collect();
+
+    let own_history =
+        match match_history::get_match_history(remoting_client, local_puuid, HISTORY_GAMES).await {
+            Ok(games) => games,
+            Err(e) => {
+                warn!("Failed to fetch our own match history: {:?}", e);
+                Vec::new()
+            }
+        };
+    let current_game_id = gameflow::get_gameflow_session(remoting_client)
+        .await
+        .map(|session| session.game_data.game_id as i64)
+        .ok();
+    let ours: HashMap<i64, &Game> = own_history.iter().map(|g| (g.game_id, g)).collect();
+
+    let histories = join_all(others.iter().map(|participant| {
+        match_history::get_match_history(remoting_client, &participant.puuid, HISTORY_GAMES)
+    }))
+    .await;
+
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+
+    let mut played_with = Vec::new();
+    for (participant, history) in others.into_iter().zip(histories) {
+        let mut games = match history {
+            Ok(history) => shared_games(&ours, &history),
+            Err(e) => {
+                warn!(
+                    "Failed to fetch match history for {}#{}: {:?}",
+                    participant.game_name, participant.game_tag, e
+                );
+                Vec::new()
+            }
+        };
+
+        match storage.get_encounters(&participant.puuid) {
+            Ok(encounters) => {
+                for encounter in encounters {
+                    if Some(encounter.game_id) == current_game_id
+                        || games.iter().any(|g| g.game_id == encounter.game_id)
+                    {
+                        continue;
+                    }
+                    games.push(SharedGame {
+                        game_id: encounter.game_id,
+                        queue_id: encounter.queue_id,
+                        played_at: encounter.seen_at,
+                        win: encounter.result.as_deref().map(|r| r == "win"),
+                        same_team: true,
+                        performance: encounter.stats.map(|stats| Performance {
+                            champion_id: stats.champion_id,
+                            kills: stats.kills,
+                            deaths: stats.deaths,
+                            assists: stats.assists,
+                        }),
+                    });
+                }
+            }
+            Err(e) => error!("Failed to load encounters for {}: {}", participant.puuid, e),
+        }
+
+        if games.is_empty() {
+            continue;
+        }
+        games.sort_by(|a, b| b.played_at.cmp(&a.played_at));
+        played_with.push(PlayedWith {
+            puuid: participant.puuid.clone(),
+            game_name: participant.game_name.clone(),
+            game_tag: participant.game_tag.clone(),
+            games,
+        });
+    }
+
+    played_with
+}
+
+/// Emits `played_with_before` when anyone in the lobby has shared a game with us before.
+pub async fn emit_played_with(
+    app_handle: AppHandle,
+    remoting_client: RESTClient,
+    lobby: Lobby,
+    local_puuid: String,
+) {
+    let played_with = find_played_with(&app_handle, &remoting_client, &lobby, &local_puuid).await;
+    if played_with.is_empty() {
+        return;
+    }
+
+    info!("Played with {} lobby players before", played_with.len());
+    streamer::emit_all(&app_handle, "played_with_before", played_with).await;
+}
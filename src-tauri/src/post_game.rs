@@ -0,0 +1,64 @@
+use crate::{
+    dodge,
+    end_of_game::{self, GameFinished},
+    gameflow,
+    session_stats::{self, SessionStats},
+    streamer,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+/// Emitted as `post_game_ready` once everything that follows a game has been done.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PostGame {
+    pub game_id: i64,
+    /// `None` when the client never produced end of game stats, e.g. after a crash.
+    pub game: Option<GameFinished>,
+    pub session: SessionStats,
+}
+
+/// Runs when the gameflow moves from the game on to the end of game screens: records the
+/// result against the encounters, archives the scoreboard, clears dodge state left over from
+/// the game, updates the session stats and emits `post_game_ready`.
+pub async fn run(app_handle: AppHandle, remoting_client: RESTClient) {
+    let session = match gameflow::get_gameflow_session(&remoting_client).await {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("No gameflow session after the game: {:?}", e);
+            return;
+        }
+    };
+    let game_id = session.game_data.game_id as i64;
+    info!("Game {} ended, running the post-game pipeline", game_id);
+
+    let game = match end_of_game::wait_for_eog_stats(&remoting_client, game_id).await {
+        Some((stats, raw)) => {
+            end_of_game::record_game(&app_handle, &stats, &raw).await;
+            Some(stats.summary())
+        }
+        None => None,
+    };
+
+    dodge::clear_stale(&app_handle, None).await;
+
+    let session = session_stats::record_finished_game(
+        &app_handle,
+        &remoting_client,
+        session.game_data.queue.is_ranked,
+    )
+    .await;
+
+    streamer::emit_all(
+        &app_handle,
+        "post_game_ready",
+        PostGame {
+            game_id,
+            game,
+            session,
+        },
+    )
+    .await;
+}
@@ -0,0 +1,201 @@
+use crate::{
+    lobby::{Lobby, Participant},
+    match_history,
+};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::collections::HashSet;
+use tracing::{debug, warn};
+
+const HISTORY_GAMES: usize = 20;
+
+/// Tags handed out by default when Riot IDs were introduced. Sharing one of these says nothing.
+const DEFAULT_TAGS: [&str; 21] = [
+    "NA1", "EUW", "EUW1", "EUNE", "EUN1", "KR", "KR1", "BR1", "LAN", "LAS", "LA1", "LA2", "OCE",
+    "OC1", "TR1", "RU", "JP1", "PH2", "SG2", "TW2", "VN2",
+];
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ChatMessage {
+    body: String,
+    from_pid: String,
+    timestamp: String,
+    #[serde(rename = "type")]
+    message_type: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PremadeGroup {
+    pub members: Vec<Participant>,
+    pub reasons: Vec<String>,
+}
+
+/// Evidence that two participants queued together.
+struct PairEvidence {
+    shared_games: usize,
+    same_join_time: bool,
+    same_tag: bool,
+}
+
+impl PairEvidence {
+    fn is_premade(&self) -> bool {
+        self.shared_games >= 2
+            || (self.shared_games == 1 && (self.same_tag || self.same_join_time))
+            || (self.same_tag && self.same_join_time)
+    }
+
+    fn reasons(&self, a: &Participant, b: &Participant) -> Vec<String> {
+        let pair = format!(
+            "{}#{} and {}#{}",
+            a.game_name, a.game_tag, b.game_name, b.game_tag
+        );
+        let mut reasons = Vec::new();
+        if self.shared_games > 0 {
+            reasons.push(format!(
+                "{} shared {} of their last {} games",
+                pair, self.shared_games, HISTORY_GAMES
+            ));
+        }
+        if self.same_join_time {
+            reasons.push(format!("{} joined champ select at the same time", pair));
+        }
+        if self.same_tag {
+            reasons.push(format!("{} share the tag #{}", pair, a.game_tag));
+        }
+        reasons
+    }
+}
+
+fn shares_custom_tag(a: &Participant, b: &Participant) -> bool {
+    let tag = a.game_tag.trim().to_uppercase();
+    !tag.is_empty()
+        && !DEFAULT_TAGS.contains(&tag.as_str())
+        && tag == b.game_tag.trim().to_uppercase()
+}
+
+fn find_root(group_of: &mut [usize], mut i: usize) -> usize {
+    while group_of[i] != i {
+        group_of[i] = group_of[group_of[i]];
+        i = group_of[i];
+    }
+    i
+}
+
+/// When each participant joined the champ select chat, matched by the chat pid.
+async fn get_join_times(remoting_client: &RESTClient, lobby: &Lobby) -> Vec<Option<String>> {
+    let Some(conversation) = lobby.participants.first().map(|p| p.cid.clone()) else {
+        return Vec::new();
+    };
+
+    let messages = match remoting_client
+        .get(format!(
+            "/lol-chat/v1/conversations/{}/messages",
+            urlencoding::encode(&conversation)
+        ))
+        .await
+    {
+        Ok(messages) => serde_json::from_value::<Vec<ChatMessage>>(messages).unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to fetch champ select chat messages: {:?}", e);
+            Vec::new()
+        }
+    };
+
+    lobby
+        .participants
+        .iter()
+        .map(|participant| {
+            messages
+                .iter()
+                .find(|m| {
+                    m.message_type == "system"
+                        && m.body == "joined_room"
+                        && (m.from_pid == participant.pid
+                            || (!participant.puuid.is_empty()
+                                && m.from_pid.starts_with(&participant.puuid)))
+                })
+                .map(|m| m.timestamp.clone())
+        })
+        .collect()
+}
+
+/// Groups teammates that likely queued together, based on games they recently played
+/// together, joining champ select at the same moment and sharing a custom tag.
+pub async fn detect_premades(remoting_client: &RESTClient, lobby: &Lobby) -> Vec<PremadeGroup> {
+    let participants = &lobby.participants;
+
+    let histories = join_all(participants.iter().map(|participant| async move {
+        match match_history::get_match_history(remoting_client, &participant.puuid, HISTORY_GAMES)
+            .await
+        {
+            Ok(games) => games.iter().map(|g| g.game_id).collect::<HashSet<_>>(),
+            Err(e) => {
+                warn!(
+                    "Failed to fetch match history for {}#{}: {:?}",
+                    participant.game_name, participant.game_tag, e
+                );
+                HashSet::new()
+            }
+        }
+    }))
+    .await;
+    let join_times = get_join_times(remoting_client, lobby).await;
+
+    // Union-find over the participants, linking every pair that looks premade.
+    let mut group_of: Vec<usize> = (0..participants.len()).collect();
+    let mut reasons: Vec<Vec<String>> = vec![Vec::new(); participants.len()];
+
+    for (a, first) in participants.iter().enumerate() {
+        for (b, second) in participants.iter().enumerate().skip(a + 1) {
+            let evidence = PairEvidence {
+                shared_games: histories[a].intersection(&histories[b]).count(),
+                same_join_time: matches!(
+                    (join_times.get(a), join_times.get(b)),
+                    (Some(Some(x)), Some(Some(y))) if x == y
+                ),
+                same_tag: shares_custom_tag(first, second),
+            };
+
+            if !evidence.is_premade() {
+                continue;
+            }
+
+            debug!(
+                "Linked {} and {} as premade",
+                first.game_name, second.game_name
+            );
+            let (root_a, root_b) = (find_root(&mut group_of, a), find_root(&mut group_of, b));
+            group_of[root_b] = root_a;
+            reasons[a].extend(evidence.reasons(first, second));
+        }
+    }
+
+    let mut groups: Vec<(usize, PremadeGroup)> = Vec::new();
+    for (i, participant) in participants.iter().enumerate() {
+        let r = find_root(&mut group_of, i);
+        let index = match groups.iter().position(|(id, _)| *id == r) {
+            Some(index) => index,
+            None => {
+                groups.push((
+                    r,
+                    PremadeGroup {
+                        members: Vec::new(),
+                        reasons: Vec::new(),
+                    },
+                ));
+                groups.len() - 1
+            }
+        };
+        groups[index].1.members.push(participant.clone());
+        groups[index].1.reasons.append(&mut reasons[i]);
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.members.len() > 1)
+        .collect()
+}
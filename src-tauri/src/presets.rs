@@ -0,0 +1,155 @@
+use crate::{
+    error::AppError,
+    runes::{self, RunePage},
+    skins, spells, AppConfig, ManagedPresets,
+};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const PRESETS_FILE: &str = "presets.json";
+
+/// A champion's loadout. Anything left out is not touched when the preset is applied.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Preset {
+    pub champion_id: i64,
+    pub runes: Option<RunePage>,
+    pub spells: Option<Vec<i64>>,
+    pub skin_id: Option<i64>,
+    /// Apply as soon as the champion is locked in, instead of only on request.
+    pub auto_apply: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetApplied {
+    pub champion_id: i64,
+    pub runes: bool,
+    pub spells: bool,
+    pub skin: bool,
+}
+
+impl Preset {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.champion_id <= 0 {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid champion id {}",
+                self.champion_id
+            )));
+        }
+        if let Some(spells) = &self.spells {
+            if spells.len() != 2 || spells[0] == spells[1] {
+                return Err(AppError::InvalidInput(
+                    "A preset needs two different spells".to_string(),
+                ));
+            }
+        }
+        if let Some(runes) = &self.runes {
+            if runes.selected_perk_ids.is_empty() {
+                return Err(AppError::InvalidInput(
+                    "A preset rune page needs runes".to_string(),
+                ));
+            }
+        }
+        // Skin ids are the champion id followed by a three digit skin number.
+        if let Some(skin_id) = self.skin_id {
+            if skin_id / 1000 != self.champion_id {
+                return Err(AppError::InvalidInput(format!(
+                    "Skin {} does not belong to champion {}",
+                    skin_id, self.champion_id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn presets_path(cfg_folder: &Path) -> PathBuf {
+    cfg_folder.join(PRESETS_FILE)
+}
+
+/// Reads the presets file, keyed by champion id. A missing or broken file means no presets.
+pub fn load(cfg_folder: &Path) -> BTreeMap<i64, Preset> {
+    std::fs::read_to_string(presets_path(cfg_folder))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Vec<Preset>>(&json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|preset| (preset.champion_id, preset))
+        .collect()
+}
+
+pub async fn save(cfg_folder: &Path, presets: &BTreeMap<i64, Preset>) -> Result<(), AppError> {
+    let presets: Vec<&Preset> = presets.values().collect();
+    let json = serde_json::to_string_pretty(&presets)?;
+    tokio::fs::write(presets_path(cfg_folder), json).await?;
+    Ok(())
+}
+
+/// Applies every part of the preset and emits `preset_applied`. Rune pages are written to
+/// the page rune imports manage.
+pub async fn apply(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    preset: &Preset,
+) -> Result<PresetApplied, AppError> {
+    let mut applied = PresetApplied {
+        champion_id: preset.champion_id,
+        runes: false,
+        spells: false,
+        skin: false,
+    };
+
+    if let Some([first, second]) = preset.spells.as_deref() {
+        spells::set_spells(remoting_client, [*first, *second]).await?;
+        applied.spells = true;
+    }
+
+    if let Some(page) = &preset.runes {
+        let page_name = {
+            let config = app_handle.state::<AppConfig>();
+            let config = config.0.lock().await;
+            config.runes.page_name.clone()
+        };
+        runes::write_page(remoting_client, &page_name, page).await?;
+        applied.runes = true;
+    }
+
+    if let Some(skin_id) = preset.skin_id {
+        skins::set_skin(remoting_client, skin_id).await?;
+        applied.skin = true;
+    }
+
+    info!("Applied the preset for champion {}", preset.champion_id);
+    app_handle.emit_all("preset_applied", &applied).unwrap();
+    Ok(applied)
+}
+
+/// Applies the preset for the champion we just locked, if it is set to apply automatically.
+pub async fn apply_for_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    champion_id: i64,
+) {
+    let preset = {
+        let presets = app_handle.state::<ManagedPresets>();
+        let presets = presets.0.lock().await;
+        presets.get(&champion_id).cloned()
+    };
+    let Some(preset) = preset.filter(|p| p.auto_apply) else {
+        return;
+    };
+
+    if let Err(e) = apply(app_handle, remoting_client, &preset).await {
+        warn!(
+            "Failed to apply the preset for champion {}: {}",
+            champion_id, e
+        );
+    }
+}
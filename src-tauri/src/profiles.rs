@@ -0,0 +1,116 @@
+use crate::{config::Config, error::AppError};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILE: &str = "Default";
+
+pub struct ProfileState {
+    pub active: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitched {
+    pub name: String,
+    pub config: Config,
+}
+
+fn profiles_dir(cfg_folder: &Path) -> PathBuf {
+    cfg_folder.join("profiles")
+}
+
+fn profile_path(cfg_folder: &Path, name: &str) -> PathBuf {
+    profiles_dir(cfg_folder).join(format!("{}.json", name))
+}
+
+/// Profile names end up as file names, so only allow characters that are safe on every OS.
+pub fn validate_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.trim().is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Invalid profile name '{}'",
+            name
+        )))
+    }
+}
+
+/// Reads the name of the profile that was active when the app last closed.
+pub fn load_active(cfg_folder: &Path) -> String {
+    std::fs::read_to_string(profiles_dir(cfg_folder).join("active"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| validate_name(name).is_ok())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub async fn set_active(cfg_folder: &Path, name: &str) -> Result<(), AppError> {
+    tokio::fs::create_dir_all(profiles_dir(cfg_folder)).await?;
+    tokio::fs::write(profiles_dir(cfg_folder).join("active"), name).await?;
+    Ok(())
+}
+
+pub async fn list(cfg_folder: &Path, active: &str) -> Result<Vec<String>, AppError> {
+    let mut profiles = vec![active.to_string()];
+
+    let dir = profiles_dir(cfg_folder);
+    if !dir.exists() {
+        return Ok(profiles);
+    }
+
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            if !profiles.iter().any(|p| p == name) {
+                profiles.push(name.to_string());
+            }
+        }
+    }
+
+    profiles.sort();
+    Ok(profiles)
+}
+
+pub fn exists(cfg_folder: &Path, name: &str) -> bool {
+    profile_path(cfg_folder, name).exists()
+}
+
+pub async fn save(cfg_folder: &Path, name: &str, cfg: &Config) -> Result<(), AppError> {
+    tokio::fs::create_dir_all(profiles_dir(cfg_folder)).await?;
+    let cfg_json = serde_json::to_string(cfg)?;
+    tokio::fs::write(profile_path(cfg_folder, name), cfg_json).await?;
+    Ok(())
+}
+
+pub async fn load(cfg_folder: &Path, name: &str) -> Result<Config, AppError> {
+    let path = profile_path(cfg_folder, name);
+    if !path.exists() {
+        return Err(AppError::InvalidInput(format!(
+            "Profile '{}' does not exist",
+            name
+        )));
+    }
+
+    let cfg_json = tokio::fs::read_to_string(path).await?;
+    let cfg: Config = serde_json::from_str(&cfg_json)?;
+    cfg.validate().map_err(AppError::InvalidConfig)?;
+    Ok(cfg)
+}
@@ -0,0 +1,130 @@
+use crate::{error::AppError, gameflow::GameflowPhase, ManagedGameflowState, ManagedQueue};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Search {
+    search_state: String,
+    time_in_queue: f64,
+    estimated_queue_time: f64,
+    is_currently_in_queue: bool,
+    low_priority_data: LowPriorityData,
+    errors: Vec<SearchError>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LowPriorityData {
+    pub penalized_summoner_ids: Vec<i64>,
+    pub penalty_time: f64,
+    pub penalty_time_remaining: f64,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SearchError {
+    error_type: String,
+    penalty_time_remaining: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueStatus {
+    /// `Searching`, `Found` or `Error` among others.
+    pub search_state: String,
+    pub elapsed_seconds: f64,
+    pub estimated_seconds: f64,
+    /// Low priority queue wait, `None` when nobody in the party has one.
+    pub low_priority: Option<LowPriorityData>,
+    /// Seconds until a queue lockout from e.g. leaving or dodging ends, 0 without one.
+    pub lockout_seconds_remaining: f64,
+}
+
+#[derive(Default)]
+pub struct QueueState {
+    pub polling: bool,
+    pub status: Option<QueueStatus>,
+}
+
+impl From<Search> for QueueStatus {
+    fn from(search: Search) -> Self {
+        let low_priority = Some(search.low_priority_data)
+            .filter(|l| !l.penalized_summoner_ids.is_empty() || l.penalty_time_remaining > 0.0);
+        let lockout_seconds_remaining = search
+            .errors
+            .iter()
+            .filter(|e| !e.error_type.is_empty())
+            .map(|e| e.penalty_time_remaining)
+            .fold(0.0, f64::max);
+
+        QueueStatus {
+            search_state: search.search_state,
+            elapsed_seconds: search.time_in_queue,
+            estimated_seconds: search.estimated_queue_time,
+            low_priority,
+            lockout_seconds_remaining,
+        }
+    }
+}
+
+pub async fn get_queue_status(remoting_client: &RESTClient) -> Result<QueueStatus, AppError> {
+    let search = remoting_client
+        .get("/lol-matchmaking/v1/search".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    let search: Search = serde_json::from_value(search)?;
+    if !search.is_currently_in_queue && search.errors.is_empty() {
+        return Err(AppError::InvalidInput("Not in queue".to_string()));
+    }
+    Ok(search.into())
+}
+
+/// Starts polling the matchmaking search while we are in queue, unless that is already
+/// happening. Emits `queue_update` on every poll and `queue_stopped` once we leave the queue.
+pub async fn start_polling(app_handle: &AppHandle, remoting_client: &RESTClient) {
+    let queue = app_handle.state::<ManagedQueue>();
+    let mut queue = queue.0.lock().await;
+    if queue.polling {
+        return;
+    }
+    queue.polling = true;
+    queue.status = None;
+    tauri::async_runtime::spawn(poll(app_handle.clone(), remoting_client.clone()));
+}
+
+async fn poll(app_handle: AppHandle, remoting_client: RESTClient) {
+    info!("Polling the matchmaking search");
+
+    loop {
+        let phase = *app_handle.state::<ManagedGameflowState>().0.lock().await;
+        if phase != GameflowPhase::Matchmaking {
+            break;
+        }
+
+        match get_queue_status(&remoting_client).await {
+            Ok(status) => {
+                app_handle.emit_all("queue_update", &status).unwrap();
+                let queue = app_handle.state::<ManagedQueue>();
+                queue.0.lock().await.status = Some(status);
+            }
+            Err(e) => debug!("Matchmaking search not available: {:?}", e),
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    let queue = app_handle.state::<ManagedQueue>();
+    let mut queue = queue.0.lock().await;
+    queue.polling = false;
+    let status = queue.status.take();
+    drop(queue);
+
+    app_handle.emit_all("queue_stopped", status).unwrap();
+}
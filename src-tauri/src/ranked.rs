@@ -0,0 +1,169 @@
+use crate::{error::AppError, lobby::Lobby};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use tracing::warn;
+
+pub const SOLO_QUEUE: &str = "RANKED_SOLO_5x5";
+pub const FLEX_QUEUE: &str = "RANKED_FLEX_SR";
+
+/// Tiers from lowest to highest, as reported by the ranked endpoints.
+pub const TIERS: [&str; 10] = [
+    "IRON",
+    "BRONZE",
+    "SILVER",
+    "GOLD",
+    "PLATINUM",
+    "EMERALD",
+    "DIAMOND",
+    "MASTER",
+    "GRANDMASTER",
+    "CHALLENGER",
+];
+
+/// Position of a tier in [`TIERS`], or `None` for unranked.
+pub fn tier_index(tier: &str) -> Option<usize> {
+    TIERS.iter().position(|t| t.eq_ignore_ascii_case(tier))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RankedStats {
+    pub queues: Vec<QueueStats>,
+    pub highest_previous_season_end_tier: String,
+    pub highest_previous_season_end_division: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueueStats {
+    pub queue_type: String,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub is_provisional: bool,
+    pub previous_season_end_tier: String,
+    pub previous_season_end_division: String,
+    pub previous_season_highest_tier: String,
+    pub previous_season_highest_division: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousSeason {
+    pub tier: String,
+    pub division: String,
+}
+
+impl PreviousSeason {
+    /// The client reports an empty string or `NONE` for seasons the player did not finish ranked.
+    fn from_parts(tier: &str, division: &str) -> Option<Self> {
+        if tier.is_empty() || tier == "NONE" || tier == "UNRANKED" {
+            return None;
+        }
+
+        Some(PreviousSeason {
+            tier: tier.to_string(),
+            division: division.to_string(),
+        })
+    }
+}
+
+impl QueueStats {
+    pub fn games(&self) -> i64 {
+        self.wins + self.losses
+    }
+
+    pub fn winrate(&self) -> Option<f64> {
+        if self.games() == 0 {
+            None
+        } else {
+            Some(self.wins as f64 / self.games() as f64)
+        }
+    }
+}
+
+impl RankedStats {
+    pub fn queue(&self, queue_type: &str) -> Option<&QueueStats> {
+        self.queues.iter().find(|q| q.queue_type == queue_type)
+    }
+
+    /// Best end-of-season rank across all queues last season.
+    pub fn previous_season(&self) -> Option<PreviousSeason> {
+        PreviousSeason::from_parts(
+            &self.highest_previous_season_end_tier,
+            &self.highest_previous_season_end_division,
+        )
+    }
+
+    pub fn previous_season_solo(&self) -> Option<PreviousSeason> {
+        self.queue(SOLO_QUEUE).and_then(|q| {
+            PreviousSeason::from_parts(&q.previous_season_end_tier, &q.previous_season_end_division)
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantRank {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    pub solo: Option<QueueStats>,
+    pub flex: Option<QueueStats>,
+    pub previous_season: Option<PreviousSeason>,
+    pub previous_season_solo: Option<PreviousSeason>,
+    /// Finished last season ranked but has no games in solo queue this season.
+    pub unranked_now: bool,
+}
+
+pub async fn get_ranked_stats(
+    remoting_client: &RESTClient,
+    puuid: &str,
+) -> Result<RankedStats, AppError> {
+    let stats = remoting_client
+        .get(format!("/lol-ranked/v1/ranked-stats/{}", puuid))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(stats)?)
+}
+
+/// Looks up every participant's ranked stats concurrently. Players whose stats could not be
+/// fetched are still listed, just without any queue data.
+pub async fn get_lobby_ranks(remoting_client: &RESTClient, lobby: &Lobby) -> Vec<ParticipantRank> {
+    let lookups = lobby.participants.iter().map(|participant| async move {
+        let stats = match get_ranked_stats(remoting_client, &participant.puuid).await {
+            Ok(stats) => stats,
+            Err(e) => {
+                warn!(
+                    "Failed to fetch ranked stats for {}#{}: {:?}",
+                    participant.game_name, participant.game_tag, e
+                );
+                RankedStats::default()
+            }
+        };
+
+        let solo = stats.queue(SOLO_QUEUE).cloned();
+        let previous_season = stats.previous_season();
+        let unranked_now = previous_season.is_some()
+            && solo
+                .as_ref()
+                .map_or(true, |q| q.tier.is_empty() || q.tier == "NONE");
+
+        ParticipantRank {
+            puuid: participant.puuid.clone(),
+            game_name: participant.game_name.clone(),
+            game_tag: participant.game_tag.clone(),
+            solo,
+            flex: stats.queue(FLEX_QUEUE).cloned(),
+            previous_season,
+            previous_season_solo: stats.previous_season_solo(),
+            unranked_now,
+        }
+    });
+
+    join_all(lookups).await
+}
@@ -1,4 +1,6 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -8,3 +10,47 @@ pub struct RegionInfo {
     pub web_language: String,
     pub web_region: String,
 }
+
+impl RegionInfo {
+    /// The region slug used by multi-search sites.
+    pub fn multi_search_region(&self) -> &str {
+        match self.web_region.as_str() {
+            "SG2" => "SG",
+            _ => &self.web_region,
+        }
+    }
+}
+
+/// Platform id for a multi-search region slug, which isn't always the web region with a `1`
+/// appended.
+pub fn platform_id(region: &str) -> String {
+    match region {
+        "EUNE" => "eun1".to_string(),
+        "LAN" => "la1".to_string(),
+        "LAS" => "la2".to_string(),
+        "OCE" => "oc1".to_string(),
+        "KR" => "kr".to_string(),
+        "RU" => "ru".to_string(),
+        "PH" | "SG" | "TH" | "TW" | "VN" => format!("{}2", region.to_lowercase()),
+        _ => format!("{}1", region.to_lowercase()),
+    }
+}
+
+/// The regional cluster serving match data for a platform.
+pub fn regional_route(platform_id: &str) -> &'static str {
+    match platform_id {
+        "na1" | "br1" | "la1" | "la2" => "americas",
+        "kr" | "jp1" => "asia",
+        "oc1" | "ph2" | "sg2" | "th2" | "tw2" | "vn2" => "sea",
+        _ => "europe",
+    }
+}
+
+pub async fn get_region_info(app_client: &RESTClient) -> Result<RegionInfo, AppError> {
+    let region_info = app_client
+        .get("/riotclient/region-locale".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(region_info)?)
+}
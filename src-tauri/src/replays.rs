@@ -0,0 +1,157 @@
+use crate::{error::AppError, AppConfig};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ReplayMetadata {
+    /// `checking`, `download`, `downloading`, `watch` once it is on disk, or one of the
+    /// failure states like `incompatible` and `missingOrExpired`.
+    state: String,
+    download_progress: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayProgress {
+    pub game_id: i64,
+    pub state: String,
+    /// Percent downloaded.
+    pub progress: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayDownloaded {
+    pub game_id: i64,
+    pub path: PathBuf,
+}
+
+async fn get_metadata(
+    remoting_client: &RESTClient,
+    game_id: i64,
+) -> Result<ReplayMetadata, AppError> {
+    let metadata = remoting_client
+        .get(format!("/lol-replays/v1/metadata/{}", game_id))
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(metadata)?)
+}
+
+async fn get_replay_folder(remoting_client: &RESTClient) -> Result<PathBuf, AppError> {
+    let path = remoting_client
+        .get("/lol-replays/v1/rofls/path".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(PathBuf::from(serde_json::from_value::<String>(path)?))
+}
+
+/// Replays are saved as `<platform>-<game id>.rofl`.
+fn find_replay(folder: &Path, game_id: i64) -> Result<PathBuf, AppError> {
+    let suffix = format!("-{}.rofl", game_id);
+    std::fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(&suffix))
+        })
+        .ok_or_else(|| AppError::Io(format!("Replay for game {} not found", game_id)))
+}
+
+async fn wait_for_download(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    game_id: i64,
+) -> Result<(), AppError> {
+    let started = Instant::now();
+    loop {
+        let metadata = get_metadata(remoting_client, game_id).await?;
+        app_handle
+            .emit_all(
+                "replay_download_progress",
+                ReplayProgress {
+                    game_id,
+                    state: metadata.state.clone(),
+                    progress: metadata.download_progress,
+                },
+            )
+            .unwrap();
+
+        match metadata.state.as_str() {
+            "watch" => return Ok(()),
+            "checking" | "download" | "downloading" | "found" => {}
+            state => {
+                return Err(AppError::LcuRequest(format!(
+                    "Replay for game {} can't be downloaded ({})",
+                    game_id, state
+                )))
+            }
+        }
+
+        if started.elapsed() > DOWNLOAD_TIMEOUT {
+            return Err(AppError::LcuRequest(format!(
+                "Replay download for game {} timed out",
+                game_id
+            )));
+        }
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+    }
+}
+
+/// Has the client download the replay of `game_id`, emitting `replay_download_progress`
+/// along the way, and copies it to the configured output folder if there is one. Returns
+/// where the replay ended up.
+pub async fn download_replay(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    game_id: i64,
+) -> Result<ReplayDownloaded, AppError> {
+    let output_folder = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .replays
+        .output_folder
+        .clone();
+
+    // A replay that is already on disk only needs copying.
+    if get_metadata(remoting_client, game_id).await?.state != "watch" {
+        remoting_client
+            .post(
+                format!("/lol-replays/v1/rofls/{}/download/graceful", game_id),
+                serde_json::json!({ "componentType": "replay-button_match-history" }),
+            )
+            .await
+            .map_err(AppError::lcu_request)?;
+        info!("Downloading replay for game {}", game_id);
+        wait_for_download(app_handle, remoting_client, game_id).await?;
+    }
+
+    let mut path = find_replay(&get_replay_folder(remoting_client).await?, game_id)?;
+    if !output_folder.is_empty() {
+        let folder = PathBuf::from(output_folder);
+        std::fs::create_dir_all(&folder)?;
+        let target = folder.join(path.file_name().unwrap_or_default());
+        std::fs::copy(&path, &target)?;
+        path = target;
+    }
+
+    info!("Replay for game {} saved to {}", game_id, path.display());
+    let downloaded = ReplayDownloaded { game_id, path };
+    app_handle
+        .emit_all("replay_downloaded", &downloaded)
+        .unwrap();
+    Ok(downloaded)
+}
@@ -0,0 +1,222 @@
+use crate::{
+    champ_select::ChampSelectSession,
+    i18n,
+    lobby::{Lobby, Participant},
+    mastery::{self, ParticipantMastery},
+    match_history::{self, Game},
+    notifications::{self, NotificationKind},
+    obs, opgg,
+    ranked::{self, ParticipantRank, FLEX_QUEUE, SOLO_QUEUE},
+    riot_api,
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use serde_json::{json, Value};
+use shaco::rest::RESTClient;
+use tauri::AppHandle;
+use tracing::warn;
+
+const RECENT_GAMES: usize = 20;
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentForm {
+    pub games: usize,
+    pub wins: usize,
+    /// Share of recent games won, 0 to 1.
+    pub winrate: Option<f64>,
+    /// Average (kills + assists) / deaths, deaths counting as at least one.
+    pub kda: Option<f64>,
+    /// Champion ids played recently, most played first.
+    pub champions: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerReport {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    pub rank: Option<ParticipantRank>,
+    pub mastery: Option<ParticipantMastery>,
+    pub recent: RecentForm,
+    /// OP.GG's summoner profile as returned by its MCP server, `None` when the lookup failed.
+    pub opgg: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyReport {
+    pub region: String,
+    pub players: Vec<PlayerReport>,
+}
+
+fn recent_form(games: &[Game]) -> RecentForm {
+    let stats: Vec<_> = games
+        .iter()
+        .filter_map(|g| g.participants.first())
+        .collect();
+    if stats.is_empty() {
+        return RecentForm::default();
+    }
+
+    let wins = stats.iter().filter(|p| p.stats.win).count();
+    let kda = stats
+        .iter()
+        .map(|p| (p.stats.kills + p.stats.assists) as f64 / p.stats.deaths.max(1) as f64)
+        .sum::<f64>()
+        / stats.len() as f64;
+
+    let mut champions: Vec<(i64, usize)> = Vec::new();
+    for participant in &stats {
+        match champions
+            .iter_mut()
+            .find(|(id, _)| *id == participant.champion_id)
+        {
+            Some((_, count)) => *count += 1,
+            None => champions.push((participant.champion_id, 1)),
+        }
+    }
+    champions.sort_by(|a, b| b.1.cmp(&a.1));
+
+    RecentForm {
+        games: stats.len(),
+        wins,
+        winrate: Some(wins as f64 / stats.len() as f64),
+        kda: Some(kda),
+        champions: champions.into_iter().map(|(id, _)| id).collect(),
+    }
+}
+
+/// OP.GG profiles in lobby order, `None` for lookups that failed.
+async fn opgg_profiles(app_handle: &AppHandle, lobby: &Lobby, region: &str) -> Vec<Option<Value>> {
+    let arguments = lobby
+        .participants
+        .iter()
+        .map(|participant| {
+            json!({
+                "game_name": participant.game_name,
+                "tag_line": participant.game_tag,
+                "region": region,
+            })
+        })
+        .collect();
+
+    opgg::call_tool_batch(app_handle, "lol-summoner-search", arguments)
+        .await
+        .into_iter()
+        .zip(&lobby.participants)
+        .map(|(batch, participant)| {
+            if let Some(e) = &batch.error {
+                warn!(
+                    "Failed to fetch OP.GG profile for {}#{}: {}",
+                    participant.game_name, participant.game_tag, e
+                );
+            }
+            batch.result
+        })
+        .collect()
+}
+
+async fn recent_games(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    region: &str,
+    participant: &Participant,
+) -> Vec<Game> {
+    match match_history::get_match_history_with_fallback(
+        app_handle,
+        remoting_client,
+        region,
+        &participant.puuid,
+        RECENT_GAMES,
+    )
+    .await
+    {
+        Ok(games) => games,
+        Err(e) => {
+            warn!(
+                "Failed to fetch match history for {}#{}: {:?}",
+                participant.game_name, participant.game_tag, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Looks up solo queue ranks the LCU didn't return on the Riot API.
+async fn fill_missing_ranks(app_handle: &AppHandle, region: &str, ranks: &mut [ParticipantRank]) {
+    for rank in ranks.iter_mut().filter(|r| r.solo.is_none()) {
+        match riot_api::get_league_entries(app_handle, region, &rank.puuid).await {
+            Ok(entries) => {
+                rank.solo = entries.iter().find(|e| e.queue_type == SOLO_QUEUE).cloned();
+                if rank.flex.is_none() {
+                    rank.flex = entries.into_iter().find(|e| e.queue_type == FLEX_QUEUE);
+                }
+            }
+            Err(e) => warn!("Failed to fetch league entries from the Riot API: {}", e),
+        }
+    }
+}
+
+/// Everything we know about each player in one go, so the frontend doesn't have to send
+/// players to a website to size up the lobby.
+pub async fn get_lobby_report(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    session: Option<&ChampSelectSession>,
+    region: &str,
+) -> LobbyReport {
+    let (mut ranks, mastery, histories, profiles) = futures_util::join!(
+        ranked::get_lobby_ranks(remoting_client, lobby),
+        mastery::get_lobby_mastery(remoting_client, lobby, session),
+        join_all(lobby.participants.iter().map(|participant| recent_games(
+            app_handle,
+            remoting_client,
+            region,
+            participant
+        ))),
+        opgg_profiles(app_handle, lobby, region)
+    );
+    if riot_api::is_configured(app_handle).await {
+        fill_missing_ranks(app_handle, region, &mut ranks).await;
+    }
+
+    let players = lobby
+        .participants
+        .iter()
+        .zip(histories.iter().zip(profiles))
+        .map(|(participant, (games, opgg))| PlayerReport {
+            puuid: participant.puuid.clone(),
+            game_name: participant.game_name.clone(),
+            game_tag: participant.game_tag.clone(),
+            rank: ranks.iter().find(|r| r.puuid == participant.puuid).cloned(),
+            mastery: mastery
+                .iter()
+                .find(|m| m.puuid == participant.puuid)
+                .cloned(),
+            recent: recent_form(games),
+            opgg,
+        })
+        .collect();
+
+    let tr = i18n::translator(app_handle).await;
+    notifications::notify(
+        app_handle,
+        NotificationKind::LobbyReport,
+        &tr.t("notification.lobbyReport.title"),
+        &tr.format(
+            "notification.lobbyReport.body",
+            &[("count", lobby.participants.len().to_string())],
+        ),
+    )
+    .await;
+
+    let report = LobbyReport {
+        region: region.to_string(),
+        players,
+    };
+    obs::broadcast(app_handle, "lobby_report", &report).await;
+    report
+}
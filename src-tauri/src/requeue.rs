@@ -0,0 +1,125 @@
+use crate::{
+    error::AppError, gameflow::GameflowPhase, AppConfig, ManagedGameflowState, ManagedRequeue,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+/// The lobby needs a moment after play again before it accepts a queue search.
+const LOBBY_SETTLE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequeuePending {
+    pub ms_remaining: u64,
+    pub start_queue: bool,
+}
+
+async fn play_again(remoting_client: &RESTClient) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            "/lol-lobby/v2/play-again".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+async fn start_search(remoting_client: &RESTClient) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            "/lol-lobby/v2/lobby/matchmaking/search".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+/// Goes back to the lobby, and into queue when `start_queue` is set, after the configured
+/// delay. `requeue_pending` is emitted first so the frontend can offer a cancel button, and
+/// `requeue_done` at the end with whether we are in queue.
+pub async fn schedule_requeue(app_handle: AppHandle, remoting_client: RESTClient) {
+    let config = app_handle
+        .state::<AppConfig>()
+        .0
+        .lock()
+        .await
+        .requeue
+        .clone();
+    if !config.enabled {
+        return;
+    }
+
+    let delay = Duration::from_secs(config.delay_seconds as u64);
+    let deadline = Instant::now() + delay;
+    *app_handle.state::<ManagedRequeue>().0.lock().await = Some(deadline);
+
+    info!("Requeueing in {}s unless cancelled", delay.as_secs());
+    app_handle
+        .emit_all(
+            "requeue_pending",
+            RequeuePending {
+                ms_remaining: delay.as_millis() as u64,
+                start_queue: config.start_queue,
+            },
+        )
+        .unwrap();
+
+    tokio::time::sleep_until(deadline.into()).await;
+
+    // A cancel, or a newer requeue replacing this one, changes the pending deadline.
+    let requeue = app_handle.state::<ManagedRequeue>();
+    let mut requeue = requeue.0.lock().await;
+    if *requeue != Some(deadline) {
+        return;
+    }
+    *requeue = None;
+    drop(requeue);
+
+    // Leaving the end of game screen by hand makes play again pointless.
+    let phase = *app_handle.state::<ManagedGameflowState>().0.lock().await;
+    if phase != GameflowPhase::EndOfGame {
+        info!("Left the end of game screen, not requeueing");
+        return;
+    }
+
+    if let Err(e) = play_again(&remoting_client).await {
+        error!("Failed to play again: {}", e);
+        return;
+    }
+    if config.start_queue {
+        tokio::time::sleep(LOBBY_SETTLE).await;
+        if let Err(e) = start_search(&remoting_client).await {
+            // Only the party leader can start the search.
+            warn!("Failed to start the queue: {}", e);
+            app_handle.emit_all("requeue_done", false).unwrap();
+            return;
+        }
+    }
+
+    info!("Requeued");
+    app_handle
+        .emit_all("requeue_done", config.start_queue)
+        .unwrap();
+}
+
+/// Stops a requeue waiting out its delay. Returns whether there was anything to cancel.
+pub async fn cancel_requeue(app_handle: &AppHandle) -> bool {
+    let cancelled = app_handle
+        .state::<ManagedRequeue>()
+        .0
+        .lock()
+        .await
+        .take()
+        .is_some();
+
+    if cancelled {
+        info!("Requeue cancelled");
+        app_handle.emit_all("requeue_cancelled", ()).unwrap();
+    }
+    cancelled
+}
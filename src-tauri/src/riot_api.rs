@@ -0,0 +1,289 @@
+use crate::{
+    error::AppError,
+    match_history::{Game, GameParticipant, ParticipantStats, Timeline},
+    ranked::QueueStats,
+    region, AppConfig, ManagedRiotApi,
+};
+use futures_util::future::join_all;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+/// Limits of a personal development key: requests per window.
+const RATE_LIMITS: [(usize, Duration); 2] = [
+    (20, Duration::from_secs(1)),
+    (100, Duration::from_secs(120)),
+];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct RiotApiState {
+    client: reqwest::Client,
+    /// Start times of recent requests, oldest first.
+    requests: VecDeque<Instant>,
+}
+
+impl Default for RiotApiState {
+    fn default() -> Self {
+        RiotApiState {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            requests: VecDeque::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LeagueEntry {
+    queue_type: String,
+    tier: String,
+    rank: String,
+    league_points: i64,
+    wins: i64,
+    losses: i64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MatchDto {
+    info: MatchInfo,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MatchInfo {
+    game_id: i64,
+    game_creation: i64,
+    game_duration: i64,
+    queue_id: i64,
+    participants: Vec<MatchParticipant>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MatchParticipant {
+    puuid: String,
+    participant_id: i64,
+    champion_id: i64,
+    team_id: i64,
+    team_position: String,
+    win: bool,
+    kills: i64,
+    deaths: i64,
+    assists: i64,
+}
+
+pub async fn is_configured(app_handle: &AppHandle) -> bool {
+    let config = app_handle.state::<AppConfig>();
+    let config = config.0.lock().await;
+    !config.riot_api.api_key.is_empty()
+}
+
+/// Waits until another request fits in every rate limit window, then claims it.
+async fn wait_for_slot(app_handle: &AppHandle) {
+    loop {
+        let wait = {
+            let state = app_handle.state::<ManagedRiotApi>();
+            let mut state = state.0.lock().await;
+            let now = Instant::now();
+            let longest = RATE_LIMITS
+                .iter()
+                .map(|(_, w)| *w)
+                .max()
+                .unwrap_or_default();
+            while state
+                .requests
+                .front()
+                .is_some_and(|t| now.duration_since(*t) >= longest)
+            {
+                state.requests.pop_front();
+            }
+
+            let wait = RATE_LIMITS
+                .iter()
+                .filter_map(|(limit, window)| {
+                    let in_window: Vec<&Instant> = state
+                        .requests
+                        .iter()
+                        .filter(|t| now.duration_since(**t) < *window)
+                        .collect();
+                    (in_window.len() >= *limit).then(|| *window - now.duration_since(*in_window[0]))
+                })
+                .max();
+            if wait.is_none() {
+                state.requests.push_back(now);
+            }
+            wait
+        };
+
+        match wait {
+            Some(wait) => tokio::time::sleep(wait).await,
+            None => return,
+        }
+    }
+}
+
+async fn get<T: DeserializeOwned>(app_handle: &AppHandle, url: &str) -> Result<T, AppError> {
+    let api_key = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.riot_api.api_key.clone()
+    };
+    if api_key.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No Riot API key configured".to_string(),
+        ));
+    }
+    let client = app_handle
+        .state::<ManagedRiotApi>()
+        .0
+        .lock()
+        .await
+        .client
+        .clone();
+
+    // One retry when we got rate limited anyway, e.g. by another app using the same key.
+    for attempt in 0..2 {
+        wait_for_slot(app_handle).await;
+        debug!("Riot API request: {}", url);
+        let response = client
+            .get(url)
+            .header("X-Riot-Token", &api_key)
+            .send()
+            .await
+            .map_err(|e| AppError::Provider(format!("Riot API request failed: {}", e)))?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS && attempt == 0 {
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1);
+            warn!("Riot API rate limited, retrying in {}s", retry_after);
+            tokio::time::sleep(Duration::from_secs(retry_after)).await;
+            continue;
+        }
+
+        let response = response
+            .error_for_status()
+            .map_err(|e| AppError::Provider(format!("Riot API request failed: {}", e)))?;
+        return response
+            .json::<T>()
+            .await
+            .map_err(|e| AppError::Parse(e.to_string()));
+    }
+
+    Err(AppError::Provider(
+        "Riot API rate limit exceeded".to_string(),
+    ))
+}
+
+/// Ranked entries for a player, shaped like the LCU's queue stats.
+pub async fn get_league_entries(
+    app_handle: &AppHandle,
+    region: &str,
+    puuid: &str,
+) -> Result<Vec<QueueStats>, AppError> {
+    let url = format!(
+        "https://{}.api.riotgames.com/lol/league/v4/entries/by-puuid/{}",
+        region::platform_id(region),
+        puuid
+    );
+    let entries: Vec<LeagueEntry> = get(app_handle, &url).await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|e| QueueStats {
+            queue_type: e.queue_type,
+            tier: e.tier,
+            division: e.rank,
+            league_points: e.league_points,
+            wins: e.wins,
+            losses: e.losses,
+            ..QueueStats::default()
+        })
+        .collect())
+}
+
+/// Maps Riot API positions onto the lane and role pairs the LCU match history uses.
+fn timeline(team_position: &str) -> Timeline {
+    let (lane, role) = match team_position {
+        "UTILITY" => ("BOTTOM", "SUPPORT"),
+        "BOTTOM" => ("BOTTOM", "CARRY"),
+        position => (position, "SOLO"),
+    };
+    Timeline {
+        lane: lane.to_string(),
+        role: role.to_string(),
+    }
+}
+
+/// A player's recent games, shaped like the LCU match history so it can stand in for it.
+/// Like the LCU, each game only lists the requested player.
+pub async fn get_match_history(
+    app_handle: &AppHandle,
+    region: &str,
+    puuid: &str,
+    count: usize,
+) -> Result<Vec<Game>, AppError> {
+    let host = format!(
+        "https://{}.api.riotgames.com",
+        region::regional_route(&region::platform_id(region))
+    );
+    let ids: Vec<String> = get(
+        app_handle,
+        &format!(
+            "{}/lol/match/v5/matches/by-puuid/{}/ids?count={}",
+            host, puuid, count
+        ),
+    )
+    .await?;
+
+    let matches =
+        join_all(ids.iter().map(|id| {
+            get::<MatchDto>(app_handle, &format!("{}/lol/match/v5/matches/{}", host, id))
+        }))
+        .await;
+
+    Ok(matches
+        .into_iter()
+        .filter_map(|m| match m {
+            Ok(m) => Some(m.info),
+            Err(e) => {
+                warn!("Failed to fetch match from the Riot API: {}", e);
+                None
+            }
+        })
+        .map(|info| Game {
+            game_id: info.game_id,
+            game_creation: info.game_creation,
+            game_duration: info.game_duration,
+            queue_id: info.queue_id,
+            participants: info
+                .participants
+                .into_iter()
+                .filter(|p| p.puuid == puuid)
+                .map(|p| GameParticipant {
+                    participant_id: p.participant_id,
+                    champion_id: p.champion_id,
+                    team_id: p.team_id,
+                    timeline: timeline(&p.team_position),
+                    stats: ParticipantStats {
+                        win: p.win,
+                        kills: p.kills,
+                        deaths: p.deaths,
+                        assists: p.assists,
+                    },
+                })
+                .collect(),
+        })
+        .collect())
+}
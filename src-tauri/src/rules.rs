@@ -0,0 +1,215 @@
+use crate::{
+    autofill,
+    champ_select::ChampSelectSession,
+    config::{DodgeRulesConfig, RuleCondition},
+    dodge,
+    lobby::Lobby,
+    ranked, smurf, streamer, win_probability, ManagedChampSelectState, ManagedDodgeState,
+    ManagedStorage,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DodgeRuleTriggered {
+    pub game_id: u64,
+    pub rule: String,
+    pub reasons: Vec<String>,
+    /// Whether the last-second dodge was armed because of this rule.
+    pub armed: bool,
+}
+
+/// Everything the conditions look at. Expensive lookups are only made when a rule needs them.
+#[derive(Default)]
+struct RuleContext {
+    autofills: Vec<String>,
+    /// Season solo queue winrate in percent, games played and Riot ID per player.
+    winrates: Vec<(f64, i64, String)>,
+    blocklisted: Vec<String>,
+    smurf_scores: Vec<(u8, String)>,
+    win_probability: Option<f64>,
+}
+
+fn needs(rules: &DodgeRulesConfig, check: impl Fn(&RuleCondition) -> bool) -> bool {
+    rules.rules.iter().flat_map(|r| &r.conditions).any(check)
+}
+
+impl RuleContext {
+    async fn gather(
+        app_handle: &AppHandle,
+        remoting_client: &RESTClient,
+        lobby: &Lobby,
+        session: &ChampSelectSession,
+        rules: &DodgeRulesConfig,
+    ) -> Self {
+        let mut context = RuleContext::default();
+
+        if needs(rules, |c| matches!(c, RuleCondition::Autofills { .. })) {
+            context.autofills = autofill::get_autofill_flags(remoting_client, session)
+                .await
+                .into_iter()
+                .filter(|f| f.likely_autofilled)
+                .map(|f| f.assigned_position)
+                .collect();
+        }
+
+        if needs(rules, |c| {
+            matches!(
+                c,
+                RuleCondition::AverageWinrateBelow { .. }
+                    | RuleCondition::PlayerWinrateBelow { .. }
+            )
+        }) {
+            context.winrates = ranked::get_lobby_ranks(remoting_client, lobby)
+                .await
+                .into_iter()
+                .filter_map(|rank| {
+                    let solo = rank.solo?;
+                    Some((
+                        solo.winrate()? * 100.0,
+                        solo.games(),
+                        format!("{}#{}", rank.game_name, rank.game_tag),
+                    ))
+                })
+                .collect();
+        }
+
+        if needs(rules, |c| matches!(c, RuleCondition::Blocklisted)) {
+            let storage = app_handle.state::<ManagedStorage>();
+            let storage = storage.0.lock().await;
+            match storage.find_blocklisted(lobby) {
+                Ok(matches) => {
+                    context.blocklisted = matches
+                        .into_iter()
+                        .map(|m| format!("{}#{}", m.participant.game_name, m.participant.game_tag))
+                        .collect()
+                }
+                Err(e) => warn!("Failed to check the blocklist: {}", e),
+            }
+        }
+
+        if needs(rules, |c| {
+            matches!(c, RuleCondition::SmurfScoreAbove { .. })
+        }) {
+            context.smurf_scores = smurf::get_smurf_scores(remoting_client, lobby)
+                .await
+                .into_iter()
+                .map(|s| (s.score, format!("{}#{}", s.game_name, s.game_tag)))
+                .collect();
+        }
+
+        if needs(rules, |c| {
+            matches!(c, RuleCondition::WinProbabilityBelow { .. })
+        }) {
+            let estimate =
+                win_probability::get_win_probability(remoting_client, lobby, Some(session)).await;
+            context.win_probability = Some(estimate.probability);
+        }
+
+        context
+    }
+
+    /// Returns why the condition holds, or `None` if it doesn't.
+    fn check(&self, condition: &RuleCondition) -> Option<String> {
+        match condition {
+            RuleCondition::Autofills { at_least } => {
+                (self.autofills.len() >= *at_least).then(|| {
+                    format!(
+                        "{} likely autofilled ({})",
+                        self.autofills.len(),
+                        self.autofills.join(", ")
+                    )
+                })
+            }
+            RuleCondition::AverageWinrateBelow { percent } => {
+                if self.winrates.is_empty() {
+                    return None;
+                }
+                let average = self.winrates.iter().map(|(w, _, _)| w).sum::<f64>()
+                    / self.winrates.len() as f64;
+                (average < *percent).then(|| format!("Average teammate winrate is {:.0}%", average))
+            }
+            RuleCondition::PlayerWinrateBelow { percent, min_games } => self
+                .winrates
+                .iter()
+                .find(|(winrate, games, _)| winrate < percent && games >= min_games)
+                .map(|(winrate, games, name)| {
+                    format!(
+                        "{} has a {:.0}% winrate over {} games",
+                        name, winrate, games
+                    )
+                }),
+            RuleCondition::Blocklisted => (!self.blocklisted.is_empty())
+                .then(|| format!("Blocklisted: {}", self.blocklisted.join(", "))),
+            RuleCondition::SmurfScoreAbove { score } => self
+                .smurf_scores
+                .iter()
+                .find(|(s, _)| s > score)
+                .map(|(s, name)| format!("{} looks like a smurf (score {})", name, s)),
+            RuleCondition::WinProbabilityBelow { percent } => self
+                .win_probability
+                .filter(|p| p < percent)
+                .map(|p| format!("Estimated win probability is {:.0}%", p)),
+        }
+    }
+}
+
+/// Evaluates the configured dodge rules once per game, as soon as every teammate shows up in
+/// the lobby. Each rule that fires emits `dodge_rule_triggered`; in `auto` mode the
+/// last-second dodge is armed as well.
+pub async fn evaluate(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    rules: &DodgeRulesConfig,
+    allowed_queues: &[i64],
+) {
+    if rules.mode == "off" || rules.rules.is_empty() {
+        return;
+    }
+
+    let champ_select = app_handle.state::<ManagedChampSelectState>();
+    let Some(session) = champ_select.0.lock().await.clone() else {
+        return;
+    };
+    if lobby.participants.len() < session.my_team.len() {
+        return;
+    }
+
+    {
+        let dodge_state = app_handle.state::<ManagedDodgeState>();
+        let mut dodge_state = dodge_state.0.lock().await;
+        if dodge_state.rules_checked == Some(session.game_id) {
+            return;
+        }
+        dodge_state.rules_checked = Some(session.game_id);
+    }
+
+    let context = RuleContext::gather(app_handle, remoting_client, lobby, &session, rules).await;
+
+    for rule in &rules.rules {
+        let reasons: Option<Vec<String>> =
+            rule.conditions.iter().map(|c| context.check(c)).collect();
+        let Some(reasons) = reasons.filter(|_| !rule.conditions.is_empty()) else {
+            continue;
+        };
+
+        let armed = rules.mode == "auto"
+            && dodge::auto_arm(app_handle, remoting_client, allowed_queues, session.game_id).await;
+        info!("Dodge rule '{}' triggered, armed: {}", rule.name, armed);
+        streamer::emit_all(
+            app_handle,
+            "dodge_rule_triggered",
+            DodgeRuleTriggered {
+                game_id: session.game_id,
+                rule: rule.name.clone(),
+                reasons,
+                armed,
+            },
+        )
+        .await;
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    assets::{self, ChampionData},
+    champ_select::ChampSelectSession,
+    config::RuneConfig,
+    error::AppError,
+    opgg, AppConfig,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const OPGG_TOOL: &str = "lol-champion-analysis";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RunePage {
+    pub primary_style_id: i64,
+    pub sub_style_id: i64,
+    /// Keystone and runes in page order, followed by the three stat shards.
+    pub selected_perk_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct PerkPage {
+    id: i64,
+    name: String,
+    is_deletable: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunesApplied {
+    pub champion_id: i64,
+    pub position: String,
+    pub page_name: String,
+    pub page: RunePage,
+}
+
+/// OP.GG's name for an LCU position.
+fn opgg_position(position: &str) -> &str {
+    match position {
+        "middle" => "mid",
+        "bottom" => "adc",
+        "utility" => "support",
+        position => position,
+    }
+}
+
+fn ids(value: Option<&Value>) -> Vec<i64> {
+    value
+        .and_then(Value::as_array)
+        .map(|ids| ids.iter().filter_map(Value::as_i64).collect())
+        .unwrap_or_default()
+}
+
+/// Looks for the first rune page in a provider response, in either the LCU's shape or
+/// OP.GG's. MCP tools wrap their JSON in text content, which is parsed along the way.
+fn find_rune_page(value: &Value) -> Option<RunePage> {
+    match value {
+        Value::Object(map) => {
+            if map.contains_key("primaryStyleId") && map.contains_key("selectedPerkIds") {
+                if let Ok(page) = serde_json::from_value(value.clone()) {
+                    return Some(page);
+                }
+            }
+            if let (Some(primary), Some(secondary)) = (
+                map.get("primary_page_id").and_then(Value::as_i64),
+                map.get("secondary_page_id").and_then(Value::as_i64),
+            ) {
+                let mut perks = ids(map.get("primary_rune_ids"));
+                perks.extend(ids(map.get("secondary_rune_ids")));
+                perks.extend(ids(map.get("stat_mod_ids")));
+                return Some(RunePage {
+                    primary_style_id: primary,
+                    sub_style_id: secondary,
+                    selected_perk_ids: perks,
+                });
+            }
+            map.values().find_map(find_rune_page)
+        }
+        Value::Array(values) => values.iter().find_map(find_rune_page),
+        Value::String(text) => serde_json::from_str::<Value>(text)
+            .ok()
+            .filter(|v| v.is_object() || v.is_array())
+            .and_then(|v| find_rune_page(&v)),
+        _ => None,
+    }
+}
+
+/// Fetches build data for the champion and position from the configured source, shared by
+/// rune and item set imports.
+pub async fn fetch_build_data(
+    app_handle: &AppHandle,
+    source: &str,
+    url_template: &str,
+    champion: &ChampionData,
+    position: &str,
+) -> Result<Value, AppError> {
+    let response = match source {
+        "url" => {
+            let url = url_template
+                .replace("{champion}", &champion.key)
+                .replace("{champion_id}", &champion.id.to_string())
+                .replace("{position}", position);
+            reqwest::get(url)
+                .await
+                .map_err(|e| AppError::Provider(e.to_string()))?
+                .json::<Value>()
+                .await
+                .map_err(|e| AppError::Parse(e.to_string()))?
+        }
+        _ => {
+            opgg::call_tool_cached(
+                app_handle,
+                OPGG_TOOL,
+                json!({
+                    "champion": champion.key.to_uppercase(),
+                    "position": opgg_position(position),
+                    "game_mode": "ranked",
+                }),
+            )
+            .await?
+        }
+    };
+
+    Ok(response)
+}
+
+async fn fetch_page(
+    app_handle: &AppHandle,
+    config: &RuneConfig,
+    champion: &ChampionData,
+    position: &str,
+) -> Result<RunePage, AppError> {
+    let response = fetch_build_data(
+        app_handle,
+        &config.source,
+        &config.url_template,
+        champion,
+        position,
+    )
+    .await?;
+
+    find_rune_page(&response).ok_or_else(|| {
+        AppError::Provider(format!("No rune page for {} {}", champion.name, position))
+    })
+}
+
+/// Replaces the page named `page_name`, or creates it, and makes it the current page.
+pub async fn write_page(
+    remoting_client: &RESTClient,
+    page_name: &str,
+    page: &RunePage,
+) -> Result<(), AppError> {
+    let pages = remoting_client
+        .get("/lol-perks/v1/pages".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    let pages: Vec<PerkPage> = serde_json::from_value(pages)?;
+
+    for existing in pages
+        .iter()
+        .filter(|p| p.is_deletable && p.name == page_name)
+    {
+        remoting_client
+            .delete(format!("/lol-perks/v1/pages/{}", existing.id))
+            .await
+            .map_err(AppError::lcu_request)?;
+    }
+
+    remoting_client
+        .post(
+            "/lol-perks/v1/pages".to_string(),
+            json!({
+                "name": page_name,
+                "primaryStyleId": page.primary_style_id,
+                "subStyleId": page.sub_style_id,
+                "selectedPerkIds": page.selected_perk_ids,
+                "current": true,
+            }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(())
+}
+
+/// Writes the recommended rune page for the champion we just locked and emits
+/// `runes_applied`.
+pub async fn apply_for_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    champion_id: i64,
+) {
+    let config = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.runes.clone()
+    };
+    if !config.enabled {
+        return;
+    }
+
+    let champion = match assets::get_assets(app_handle).await {
+        Ok(assets) => match assets.champion(champion_id) {
+            Some(champion) => champion.clone(),
+            None => {
+                warn!("Unknown champion {}, not importing runes", champion_id);
+                return;
+            }
+        },
+        Err(e) => {
+            warn!("Champion data unavailable, not importing runes: {:?}", e);
+            return;
+        }
+    };
+    let position = session.local_position();
+
+    let page = match fetch_page(app_handle, &config, &champion, position).await {
+        Ok(page) => page,
+        Err(e) => {
+            warn!("Failed to fetch runes for {}: {}", champion.name, e);
+            return;
+        }
+    };
+    if let Err(e) = write_page(remoting_client, &config.page_name, &page).await {
+        warn!("Failed to write rune page: {}", e);
+        return;
+    }
+
+    info!("Imported runes for {} {}", champion.name, position);
+    app_handle
+        .emit_all(
+            "runes_applied",
+            RunesApplied {
+                champion_id,
+                position: position.to_string(),
+                page_name: config.page_name.clone(),
+                page,
+            },
+        )
+        .unwrap();
+}
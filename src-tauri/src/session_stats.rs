@@ -0,0 +1,215 @@
+use crate::{
+    ranked::{self, QueueStats, RankedStats, FLEX_QUEUE, SOLO_QUEUE},
+    summoner, ManagedSession,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+/// Ranked stats lag a few seconds behind the end of game screen.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const POLL_ATTEMPTS: u32 = 12;
+const RANKED_QUEUES: [&str; 2] = [SOLO_QUEUE, FLEX_QUEUE];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionGame {
+    pub queue_type: String,
+    pub win: bool,
+    /// LP gained or lost, counting promotions and demotions.
+    pub lp_change: i64,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i64,
+    /// Unix timestamp in seconds.
+    pub ended_at: i64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    /// Unix timestamp in seconds.
+    pub started_at: i64,
+    pub games_played: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub net_lp: i64,
+    /// Positive for a win streak, negative for a loss streak.
+    pub current_streak: i64,
+    pub longest_win_streak: usize,
+    pub longest_loss_streak: usize,
+    pub games: Vec<SessionGame>,
+}
+
+pub struct SessionState {
+    started_at: i64,
+    /// Ranked stats from before the game being played, to compare against once it ends.
+    before: Option<RankedStats>,
+    games: Vec<SessionGame>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        SessionState {
+            started_at: now(),
+            before: None,
+            games: Vec::new(),
+        }
+    }
+}
+
+impl SessionState {
+    pub fn stats(&self) -> SessionStats {
+        let mut stats = SessionStats {
+            started_at: self.started_at,
+            games_played: self.games.len(),
+            games: self.games.clone(),
+            ..SessionStats::default()
+        };
+
+        let mut streak: i64 = 0;
+        for game in &self.games {
+            stats.net_lp += game.lp_change;
+            if game.win {
+                stats.wins += 1;
+                streak = streak.max(0) + 1;
+                stats.longest_win_streak = stats.longest_win_streak.max(streak as usize);
+            } else {
+                stats.losses += 1;
+                streak = streak.min(0) - 1;
+                stats.longest_loss_streak = stats.longest_loss_streak.max(-streak as usize);
+            }
+        }
+        stats.current_streak = streak;
+
+        stats
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// LP on a single ladder across tiers and divisions, so a promotion counts as a gain. Master
+/// and above share one pool of LP.
+fn absolute_lp(queue: &QueueStats) -> Option<i64> {
+    let tier = ranked::tier_index(&queue.tier)?;
+    let master = ranked::tier_index("MASTER")?;
+    if tier >= master {
+        return Some(master as i64 * 400 + queue.league_points);
+    }
+
+    let division = match queue.division.as_str() {
+        "IV" => 0,
+        "III" => 1,
+        "II" => 2,
+        "I" => 3,
+        _ => 0,
+    };
+    Some(tier as i64 * 400 + division * 100 + queue.league_points)
+}
+
+/// The ranked game that was played between two snapshots, if any.
+fn finished_game(before: &RankedStats, after: &RankedStats) -> Option<SessionGame> {
+    RANKED_QUEUES.iter().find_map(|queue_type| {
+        let after = after.queue(queue_type)?;
+        let before = before.queue(queue_type);
+        let (wins, losses) = before.map_or((0, 0), |q| (q.wins, q.losses));
+        if after.games() <= wins + losses {
+            return None;
+        }
+
+        let lp_change = match (before.and_then(absolute_lp), absolute_lp(after)) {
+            (Some(before), Some(after)) => after - before,
+            _ => 0,
+        };
+        Some(SessionGame {
+            queue_type: queue_type.to_string(),
+            win: after.wins > wins,
+            lp_change,
+            tier: after.tier.clone(),
+            division: after.division.clone(),
+            league_points: after.league_points,
+            ended_at: now(),
+        })
+    })
+}
+
+async fn current_ranked_stats(remoting_client: &RESTClient) -> Option<RankedStats> {
+    let summoner = match summoner::get_current_summoner(remoting_client).await {
+        Ok(summoner) => summoner,
+        Err(e) => {
+            warn!("Failed to fetch our summoner: {:?}", e);
+            return None;
+        }
+    };
+    match ranked::get_ranked_stats(remoting_client, &summoner.puuid).await {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            warn!("Failed to fetch our ranked stats: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Remembers our ranked stats before a game starts.
+pub async fn snapshot_before_game(app_handle: AppHandle, remoting_client: RESTClient) {
+    let Some(stats) = current_ranked_stats(&remoting_client).await else {
+        return;
+    };
+
+    let session = app_handle.state::<ManagedSession>();
+    session.0.lock().await.before = Some(stats);
+}
+
+/// Polls our ranked stats after a ranked game until the result shows up, records it and
+/// emits `session_stats_updated`. Returns the session stats either way.
+pub async fn record_finished_game(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    ranked: bool,
+) -> SessionStats {
+    let session = app_handle.state::<ManagedSession>();
+    let before = session.0.lock().await.before.take();
+    let before = match before {
+        Some(before) if ranked => before,
+        Some(_) => return session.0.lock().await.stats(),
+        None => {
+            debug!("No ranked snapshot from before the game, skipping LP tracking");
+            return session.0.lock().await.stats();
+        }
+    };
+
+    for _ in 0..POLL_ATTEMPTS {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let Some(after) = current_ranked_stats(remoting_client).await else {
+            continue;
+        };
+        let Some(game) = finished_game(&before, &after) else {
+            continue;
+        };
+
+        info!(
+            "Ranked {} in {}, {:+} LP",
+            if game.win { "win" } else { "loss" },
+            game.queue_type,
+            game.lp_change
+        );
+        let mut session = session.0.lock().await;
+        session.games.push(game);
+        let stats = session.stats();
+        app_handle
+            .emit_all("session_stats_updated", &stats)
+            .unwrap();
+        return stats;
+    }
+
+    debug!("Ranked stats did not change after the game");
+    let stats = session.0.lock().await.stats();
+    stats
+}
@@ -0,0 +1,26 @@
+use crate::tray;
+use serde::Serialize;
+use tauri::{plugin::TauriPlugin, AppHandle, Manager, Wry};
+use tracing::info;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstance {
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+fn on_second_instance(app_handle: &AppHandle, args: Vec<String>, cwd: String) {
+    info!("Another instance was launched with {:?}", args);
+    tray::show_window(app_handle);
+    app_handle
+        .emit_all("second_instance", SecondInstance { args, cwd })
+        .unwrap();
+}
+
+/// Keeps a single instance running, since two would fight over the dodge state and emit
+/// every event twice. Launching again focuses the running window and forwards the new
+/// command line as `second_instance`.
+pub fn init() -> TauriPlugin<Wry> {
+    tauri_plugin_single_instance::init(on_second_instance)
+}
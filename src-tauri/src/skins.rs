@@ -0,0 +1,146 @@
+use crate::{config::RandomSkinConfig, error::AppError, AppConfig};
+use serde::{Deserialize, Serialize};
+use shaco::rest::RESTClient;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CarouselSkin {
+    id: i64,
+    champion_id: i64,
+    name: String,
+    unlocked: bool,
+    disabled: bool,
+    child_skins: Vec<CarouselChroma>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CarouselChroma {
+    id: i64,
+    name: String,
+    unlocked: bool,
+    disabled: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinSelected {
+    pub champion_id: i64,
+    pub skin_id: i64,
+    pub name: String,
+    pub is_chroma: bool,
+    /// How many skins and chromas the pick was made from.
+    pub candidates: usize,
+}
+
+/// Owned skins of the champion that aren't excluded, with their chromas when enabled.
+/// Excluding a skin also excludes its chromas.
+fn candidates(
+    skins: &[CarouselSkin],
+    champion_id: i64,
+    config: &RandomSkinConfig,
+) -> Vec<(i64, String, bool)> {
+    let excluded = config.excluded.get(&champion_id);
+    let is_excluded = |id: i64| excluded.is_some_and(|ids| ids.contains(&id));
+
+    let mut candidates = Vec::new();
+    for skin in skins
+        .iter()
+        .filter(|s| s.champion_id == champion_id && s.unlocked && !s.disabled)
+        .filter(|s| !is_excluded(s.id))
+    {
+        candidates.push((skin.id, skin.name.clone(), false));
+        if !config.include_chromas {
+            continue;
+        }
+        candidates.extend(
+            skin.child_skins
+                .iter()
+                .filter(|c| c.unlocked && !c.disabled && !is_excluded(c.id))
+                .map(|c| (c.id, c.name.clone(), true)),
+        );
+    }
+    candidates
+}
+
+/// Good enough randomness for picking a skin, without pulling in a crate for it.
+fn random_index(len: usize) -> usize {
+    RandomState::new().build_hasher().finish() as usize % len
+}
+
+async fn get_carousel_skins(remoting_client: &RESTClient) -> Result<Vec<CarouselSkin>, AppError> {
+    let skins = remoting_client
+        .get("/lol-champ-select/v1/skin-carousel-skins".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(serde_json::from_value(skins)?)
+}
+
+/// Chromas are selected the same way as skins, by their own id.
+pub async fn set_skin(remoting_client: &RESTClient, skin_id: i64) -> Result<(), AppError> {
+    remoting_client
+        .patch(
+            "/lol-champ-select/v1/session/my-selection".to_string(),
+            serde_json::json!({ "selectedSkinId": skin_id }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+/// Selects a random owned skin, or chroma, for the champion and emits `skin_selected`.
+pub async fn select_random_skin(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    config: &RandomSkinConfig,
+    champion_id: i64,
+) -> Result<SkinSelected, AppError> {
+    let skins = get_carousel_skins(remoting_client).await?;
+    let candidates = candidates(&skins, champion_id, config);
+    if candidates.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No skins to pick from for champion {}",
+            champion_id
+        )));
+    }
+
+    let (skin_id, name, is_chroma) = candidates[random_index(candidates.len())].clone();
+    set_skin(remoting_client, skin_id).await?;
+
+    info!("Selected {} out of {} skins", name, candidates.len());
+    let selected = SkinSelected {
+        champion_id,
+        skin_id,
+        name,
+        is_chroma,
+        candidates: candidates.len(),
+    };
+    app_handle.emit_all("skin_selected", &selected).unwrap();
+    Ok(selected)
+}
+
+/// Picks a random skin for the champion we just locked when `random_skin` is enabled.
+pub async fn apply_for_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    champion_id: i64,
+) {
+    let config = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.random_skin.clone()
+    };
+    if !config.enabled {
+        return;
+    }
+
+    if let Err(e) = select_random_skin(app_handle, remoting_client, &config, champion_id).await {
+        warn!("Failed to select a random skin: {}", e);
+    }
+}
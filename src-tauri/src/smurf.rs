@@ -0,0 +1,135 @@
+use crate::{
+    lobby::{Lobby, Participant},
+    ranked::{self, RankedStats, SOLO_QUEUE},
+    summoner,
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use tracing::warn;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SmurfScore {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    /// 0 to 100, how confident we are that this is a smurf.
+    pub score: u8,
+    pub reasons: Vec<String>,
+}
+
+/// Scores a player from their account level and solo queue record. Each signal adds to the
+/// score on its own, so a single strong signal is enough to stand out.
+fn score(summoner_level: Option<i64>, stats: &RankedStats) -> (u8, Vec<String>) {
+    let mut score = 0u32;
+    let mut reasons = Vec::new();
+
+    if let Some(level) = summoner_level {
+        if level < 60 {
+            score += 30;
+            reasons.push(format!("Low account level ({})", level));
+        } else if level < 100 {
+            score += 10;
+            reasons.push(format!("Account level under 100 ({})", level));
+        }
+    }
+
+    let solo = stats.queue(SOLO_QUEUE);
+    if let Some(solo) = solo {
+        let games = solo.games();
+        match solo.winrate() {
+            Some(winrate) if games >= 15 && winrate >= 0.65 => {
+                score += 35;
+                reasons.push(format!(
+                    "{:.0}% winrate over {} games",
+                    winrate * 100.0,
+                    games
+                ));
+            }
+            Some(winrate) if games >= 20 && winrate >= 0.58 => {
+                score += 15;
+                reasons.push(format!(
+                    "{:.0}% winrate over {} games",
+                    winrate * 100.0,
+                    games
+                ));
+            }
+            _ => {}
+        }
+
+        let tier = ranked::tier_index(&solo.tier);
+        let diamond = ranked::tier_index("DIAMOND");
+        if games > 0 && games < 50 && tier >= diamond {
+            score += 15;
+            reasons.push(format!("{} after only {} games", solo.tier, games));
+        }
+    }
+
+    let current = solo.and_then(|q| ranked::tier_index(&q.tier));
+    let previous = solo.and_then(|q| ranked::tier_index(&q.previous_season_highest_tier));
+    match (previous, current) {
+        (Some(previous), Some(current)) if current >= previous + 2 => {
+            score += 20;
+            reasons.push(format!(
+                "Climbed from {} last season to {}",
+                ranked::TIERS[previous],
+                ranked::TIERS[current]
+            ));
+        }
+        (None, Some(current)) if current >= ranked::tier_index("PLATINUM").unwrap() => {
+            score += 10;
+            reasons.push(format!(
+                "Unranked last season, {} now",
+                ranked::TIERS[current]
+            ));
+        }
+        _ => {}
+    }
+
+    (score.min(100) as u8, reasons)
+}
+
+async fn score_participant(remoting_client: &RESTClient, participant: &Participant) -> SmurfScore {
+    let (summoner, stats) = futures_util::join!(
+        summoner::get_summoner_by_puuid(remoting_client, &participant.puuid),
+        ranked::get_ranked_stats(remoting_client, &participant.puuid)
+    );
+
+    let summoner_level = match summoner {
+        Ok(summoner) => Some(summoner.summoner_level),
+        Err(e) => {
+            warn!(
+                "Failed to fetch summoner for {}#{}: {:?}",
+                participant.game_name, participant.game_tag, e
+            );
+            None
+        }
+    };
+    let stats = stats.unwrap_or_else(|e| {
+        warn!(
+            "Failed to fetch ranked stats for {}#{}: {:?}",
+            participant.game_name, participant.game_tag, e
+        );
+        RankedStats::default()
+    });
+
+    let (score, reasons) = score(summoner_level, &stats);
+    SmurfScore {
+        puuid: participant.puuid.clone(),
+        game_name: participant.game_name.clone(),
+        game_tag: participant.game_tag.clone(),
+        score,
+        reasons,
+    }
+}
+
+pub async fn get_smurf_scores(remoting_client: &RESTClient, lobby: &Lobby) -> Vec<SmurfScore> {
+    join_all(
+        lobby
+            .participants
+            .iter()
+            .map(|participant| score_participant(remoting_client, participant)),
+    )
+    .await
+}
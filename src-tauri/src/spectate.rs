@@ -0,0 +1,59 @@
+use crate::{error::AppError, ManagedFriends, ManagedStorage};
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+/// The name the spectator endpoint wants alongside the puuid, from the friend list or
+/// otherwise the most recent encounter.
+async fn spectate_name(app_handle: &AppHandle, puuid: &str) -> Result<String, AppError> {
+    let friend = app_handle
+        .state::<ManagedFriends>()
+        .0
+        .lock()
+        .await
+        .get(puuid)
+        .map(|f| f.game_name.clone());
+    if let Some(name) = friend {
+        return Ok(name);
+    }
+
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    storage
+        .get_encounters(puuid)?
+        .into_iter()
+        .next()
+        .map(|e| e.game_name)
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "{} is neither a friend nor a player we have played with",
+                puuid
+            ))
+        })
+}
+
+/// Launches the spectator client into the game `puuid` is playing. Only works while they
+/// are in a game that allows spectating.
+pub async fn spectate(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    puuid: &str,
+) -> Result<(), AppError> {
+    let name = spectate_name(app_handle, puuid).await?;
+
+    remoting_client
+        .post(
+            "/lol-spectator/v1/spectate/launch".to_string(),
+            serde_json::json!({
+                "allowObserveMode": "ALL",
+                "dropInSpectateGameId": name,
+                "gameQueueType": "",
+                "puuid": puuid,
+            }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    info!("Spectating {}", name);
+    Ok(())
+}
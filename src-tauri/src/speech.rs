@@ -0,0 +1,78 @@
+use crate::{
+    i18n::{self, Translator},
+    AppConfig, ManagedSpeech,
+};
+use std::sync::mpsc::{self, Sender};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, warn};
+use tts::Tts;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Announcement {
+    QueuePop,
+    Autofills(usize),
+    DodgeCountdown(u64),
+}
+
+impl Announcement {
+    /// Numbers stay digits so the voice reads them in its own language.
+    fn text(self, tr: &Translator) -> String {
+        match self {
+            Announcement::QueuePop => tr.t("speech.queuePop"),
+            Announcement::Autofills(1) => tr.t("speech.autofills.one"),
+            Announcement::Autofills(count) => {
+                tr.format("speech.autofills.other", &[("count", count.to_string())])
+            }
+            Announcement::DodgeCountdown(1) => tr.t("speech.dodgeCountdown.one"),
+            Announcement::DodgeCountdown(seconds) => tr.format(
+                "speech.dodgeCountdown.other",
+                &[("seconds", seconds.to_string())],
+            ),
+        }
+    }
+}
+
+/// Starts the thread that owns the system voice, which not every platform lets us share
+/// across threads, and returns the sender announcements are queued on.
+pub fn start() -> Sender<String> {
+    let (tx, rx) = mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        let mut tts = match Tts::default() {
+            Ok(tts) => tts,
+            Err(e) => {
+                error!("Text to speech is unavailable: {:?}", e);
+                return;
+            }
+        };
+        for text in rx {
+            if let Err(e) = tts.speak(&text, false) {
+                warn!("Failed to speak '{}': {:?}", text, e);
+            }
+        }
+    });
+    tx
+}
+
+/// Speaks `announcement` if announcements of its kind are turned on.
+pub async fn announce(app_handle: &AppHandle, announcement: Announcement) {
+    let enabled = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        let speech = &config.speech;
+        match announcement {
+            Announcement::QueuePop => speech.queue_pop,
+            Announcement::Autofills(_) => speech.autofills,
+            Announcement::DodgeCountdown(_) => speech.dodge_countdown,
+        }
+    };
+    if !enabled {
+        return;
+    }
+
+    let text = announcement.text(&i18n::translator(app_handle).await);
+    debug!("Announcing: {}", text);
+    let speech = app_handle.state::<ManagedSpeech>();
+    if speech.0.lock().await.send(text).is_err() {
+        warn!("Text to speech is unavailable");
+    }
+}
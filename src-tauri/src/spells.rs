@@ -0,0 +1,172 @@
+use crate::{
+    champ_select::{self, ChampSelectSession, Type},
+    config::SpellConfig,
+    error::AppError,
+    AppConfig,
+};
+use serde::Serialize;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+pub const FLASH: i64 = 4;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellsApplied {
+    pub champion_id: i64,
+    pub spell1_id: i64,
+    pub spell2_id: i64,
+    pub reasons: Vec<String>,
+}
+
+/// Picks our spells for the champion: champion overrides first, then the position, then
+/// counters for enemies already locked in, and finally Flash moved to the configured key.
+fn choose(
+    config: &SpellConfig,
+    session: &ChampSelectSession,
+    champion_id: i64,
+) -> Option<([i64; 2], Vec<String>)> {
+    let mut reasons = Vec::new();
+    let configured = match config.champions.get(&champion_id) {
+        Some(spells) => {
+            reasons.push("Champion override".to_string());
+            spells
+        }
+        None => {
+            let position = session.local_position();
+            reasons.push(format!("Spells for {}", position));
+            champ_select::priorities_for(&config.positions, position)?
+        }
+    };
+    let mut spells = match configured.as_slice() {
+        [first, second] => [*first, *second],
+        _ => return None,
+    };
+
+    let enemies: Vec<i64> = session
+        .their_team
+        .iter()
+        .map(|p| p.champion_id)
+        .filter(|id| *id != 0)
+        .collect();
+    for counter in &config.counters {
+        let Some(enemy) = counter
+            .enemy_champion_ids
+            .iter()
+            .find(|id| enemies.contains(id))
+        else {
+            continue;
+        };
+        if spells.contains(&counter.spell_id) {
+            continue;
+        }
+
+        // Never give up Flash for a counter.
+        let slot = if spells[1] == FLASH { 0 } else { 1 };
+        spells[slot] = counter.spell_id;
+        reasons.push(format!(
+            "Spell {} against champion {}",
+            counter.spell_id, enemy
+        ));
+    }
+
+    let flash_slot = match config.flash_key.as_str() {
+        "d" => Some(0),
+        "f" => Some(1),
+        _ => None,
+    };
+    if let Some(slot) = flash_slot {
+        if spells[1 - slot] == FLASH {
+            spells.swap(0, 1);
+            reasons.push(format!("Flash on {}", config.flash_key.to_uppercase()));
+        }
+    }
+
+    Some((spells, reasons))
+}
+
+pub async fn set_spells(remoting_client: &RESTClient, spells: [i64; 2]) -> Result<(), AppError> {
+    remoting_client
+        .patch(
+            "/lol-champ-select/v1/session/my-selection".to_string(),
+            serde_json::json!({ "spell1Id": spells[0], "spell2Id": spells[1] }),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+    Ok(())
+}
+
+/// Sets the configured spells once we lock in a champion.
+pub async fn apply_for_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    champion_id: i64,
+) {
+    let config = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.spells.clone()
+    };
+    if !config.enabled {
+        return;
+    }
+
+    let Some((spells, reasons)) = choose(&config, session, champion_id) else {
+        return;
+    };
+    let current = session.local_player().map(|p| [p.spell1_id, p.spell2_id]);
+    if current == Some(spells) {
+        return;
+    }
+
+    if let Err(e) = set_spells(remoting_client, spells).await {
+        warn!("Failed to set summoner spells: {}", e);
+        return;
+    }
+
+    info!("Set summoner spells {:?} ({})", spells, reasons.join(", "));
+    app_handle
+        .emit_all(
+            "spells_applied",
+            SpellsApplied {
+                champion_id,
+                spell1_id: spells[0],
+                spell2_id: spells[1],
+                reasons,
+            },
+        )
+        .unwrap();
+}
+
+/// Revisits our spells when an enemy locks in a champion one of the counters is for, as
+/// long as we have locked in already.
+pub async fn apply_for_enemy_lock(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    enemy_champion_id: i64,
+) {
+    let countered = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config
+            .spells
+            .counters
+            .iter()
+            .any(|c| c.enemy_champion_ids.contains(&enemy_champion_id))
+    };
+    if !countered || session.local_action(Type::Pick).is_some() {
+        return;
+    }
+
+    let Some(champion_id) = session
+        .local_player()
+        .map(|p| p.champion_id)
+        .filter(|id| *id != 0)
+    else {
+        return;
+    };
+    apply_for_lock(app_handle, remoting_client, session, champion_id).await;
+}
@@ -1,16 +1,59 @@
-use crate::{champ_select::handle_champ_select_start, AppConfig};
+use crate::{
+    audio::{self, Sound},
+    champ_select::handle_champ_select_start,
+    enemy_team,
+    error::AppError,
+    gameflow::GameflowPhase,
+    i18n,
+    notifications::{self, NotificationKind},
+    overlay, queue, requeue, session_stats,
+    speech::{self, Announcement},
+    AppConfig, ManagedGameflowState,
+};
 use shaco::rest::RESTClient;
 use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
 
-pub async fn get_gameflow_state(remoting_client: &RESTClient) -> String {
+pub async fn get_gameflow_state(remoting_client: &RESTClient) -> Result<String, AppError> {
     let gameflow_state = remoting_client
         .get("/lol-gameflow/v1/gameflow-phase".to_string())
         .await
-        .unwrap()
+        .map_err(AppError::lcu_request)?
         .to_string();
 
-    let cleaned_state = gameflow_state.replace('\"', "");
-    cleaned_state
+    Ok(gameflow_state.replace('\"', ""))
+}
+
+async fn accept_ready_check(app_handle: AppHandle, remoting_client: RESTClient, accept_delay: u32) {
+    tokio::time::sleep(std::time::Duration::from_millis(
+        (accept_delay as u64).saturating_sub(1000),
+    ))
+    .await;
+
+    // The ready check may have been declined or auto accept turned off while we were waiting.
+    let gameflow = app_handle.state::<ManagedGameflowState>();
+    if *gameflow.0.lock().await != GameflowPhase::ReadyCheck {
+        return;
+    }
+
+    let cfg = app_handle.state::<AppConfig>();
+    if !cfg.0.lock().await.auto_accept {
+        return;
+    }
+
+    match remoting_client
+        .post(
+            "/lol-matchmaking/v1/ready-check/accept".to_string(),
+            serde_json::json!({}),
+        )
+        .await
+    {
+        Ok(_) => {
+            info!("Accepted ready check");
+            app_handle.emit_all("ready_check_accepted", ()).unwrap();
+        }
+        Err(e) => warn!("Failed to accept ready check: {:?}", e),
+    }
 }
 
 pub async fn handle_client_state(
@@ -21,6 +64,17 @@ pub async fn handle_client_state(
 ) {
     match client_state.as_str() {
         "ChampSelect" => {
+            audio::play(app_handle, Sound::ChampSelectStart).await;
+            tauri::async_runtime::spawn(overlay::show_for_champ_select(
+                app_handle.clone(),
+                app_client.clone(),
+                remoting_client.clone(),
+            ));
+            tauri::async_runtime::spawn(session_stats::snapshot_before_game(
+                app_handle.clone(),
+                remoting_client.clone(),
+            ));
+
             let cloned_app_handle = app_handle.clone();
             let cloned_app_client = app_client.clone();
             let cloned_remoting = remoting_client.clone();
@@ -29,7 +83,7 @@ pub async fn handle_client_state(
                 tokio::time::sleep(std::time::Duration::from_secs(5)).await;
 
                 let cfg = cloned_app_handle.state::<AppConfig>();
-                let cfg = cfg.0.lock().await;
+                let cfg = cfg.0.lock().await.clone();
                 handle_champ_select_start(
                     &cloned_app_client,
                     &cloned_remoting,
@@ -39,26 +93,48 @@ pub async fn handle_client_state(
                 .await;
             });
         }
+        "InProgress" => {
+            tauri::async_runtime::spawn(enemy_team::reveal_enemy_team(
+                app_handle.clone(),
+                app_client.clone(),
+                remoting_client.clone(),
+            ));
+        }
+        "Matchmaking" => {
+            queue::start_polling(app_handle, remoting_client).await;
+        }
+        "EndOfGame" => {
+            tauri::async_runtime::spawn(requeue::schedule_requeue(
+                app_handle.clone(),
+                remoting_client.clone(),
+            ));
+        }
         "ReadyCheck" => {
+            let tr = i18n::translator(app_handle).await;
+            notifications::notify(
+                app_handle,
+                NotificationKind::QueuePop,
+                &tr.t("notification.queuePop.title"),
+                &tr.t("notification.queuePop.body"),
+            )
+            .await;
+            audio::play(app_handle, Sound::QueuePop).await;
+            speech::announce(app_handle, Announcement::QueuePop).await;
+
             let cfg = app_handle.state::<AppConfig>();
             let cfg = cfg.0.lock().await;
             if cfg.auto_accept {
-                tokio::time::sleep(std::time::Duration::from_millis(
-                    (cfg.accept_delay as u64) - 1000,
-                ))
-                .await;
-                let _resp = remoting_client
-                    .post(
-                        "/lol-matchmaking/v1/ready-check/accept".to_string(),
-                        serde_json::json!({}),
-                    )
-                    .await;
+                tauri::async_runtime::spawn(accept_ready_check(
+                    app_handle.clone(),
+                    remoting_client.clone(),
+                    cfg.accept_delay,
+                ));
             }
         }
         _ => {}
     }
 
-    println!("Client State Update: {}", client_state);
+    info!("Client State Update: {}", client_state);
     app_handle
         .emit_all("client_state_update", client_state)
         .unwrap();
@@ -0,0 +1,675 @@
+use crate::{
+    audio::{self, Sound},
+    champ_select,
+    end_of_game::EndOfGameStats,
+    error::AppError,
+    gameflow, i18n,
+    lobby::{Lobby, Participant},
+    notifications::{self, NotificationKind},
+    streamer, ManagedStorage,
+};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use shaco::rest::RESTClient;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS encounters (
+    puuid     TEXT    NOT NULL,
+    game_name TEXT    NOT NULL,
+    tag_line  TEXT    NOT NULL,
+    region    TEXT    NOT NULL,
+    game_id   INTEGER NOT NULL,
+    queue_id  INTEGER NOT NULL,
+    seen_at   INTEGER NOT NULL,
+    result    TEXT,
+    PRIMARY KEY (puuid, game_id)
+);
+CREATE INDEX IF NOT EXISTS encounters_game_id ON encounters (game_id);
+CREATE TABLE IF NOT EXISTS player_notes (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    puuid      TEXT    NOT NULL,
+    note       TEXT    NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS player_notes_puuid ON player_notes (puuid);
+CREATE TABLE IF NOT EXISTS player_tags (
+    puuid TEXT NOT NULL,
+    tag   TEXT NOT NULL,
+    PRIMARY KEY (puuid, tag)
+);
+CREATE TABLE IF NOT EXISTS blocklist (
+    id       INTEGER PRIMARY KEY AUTOINCREMENT,
+    puuid    TEXT,
+    riot_id  TEXT,
+    reason   TEXT    NOT NULL,
+    added_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS dodges (
+    game_id   INTEGER NOT NULL,
+    dodged_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS games (
+    game_id     INTEGER PRIMARY KEY,
+    queue_id    INTEGER NOT NULL,
+    game_length INTEGER NOT NULL,
+    result      TEXT,
+    ended_at    INTEGER NOT NULL,
+    scoreboard  TEXT    NOT NULL
+);
+CREATE TABLE IF NOT EXISTS game_players (
+    game_id      INTEGER NOT NULL,
+    puuid        TEXT    NOT NULL,
+    game_name    TEXT    NOT NULL,
+    tag_line     TEXT    NOT NULL,
+    team_id      INTEGER NOT NULL,
+    champion_id  INTEGER NOT NULL,
+    kills        INTEGER NOT NULL,
+    deaths       INTEGER NOT NULL,
+    assists      INTEGER NOT NULL,
+    creep_score  INTEGER NOT NULL,
+    gold         INTEGER NOT NULL,
+    damage       INTEGER NOT NULL,
+    vision_score INTEGER NOT NULL,
+    PRIMARY KEY (game_id, puuid)
+);
+CREATE INDEX IF NOT EXISTS game_players_puuid ON game_players (puuid);
+CREATE TABLE IF NOT EXISTS chat_messages (
+    message_id TEXT    NOT NULL,
+    game_id    INTEGER NOT NULL,
+    puuid      TEXT    NOT NULL,
+    sender     TEXT    NOT NULL,
+    body       TEXT    NOT NULL,
+    sent_at    TEXT    NOT NULL,
+    PRIMARY KEY (game_id, message_id)
+);
+CREATE INDEX IF NOT EXISTS chat_messages_puuid ON chat_messages (puuid);
+";
+
+pub struct Storage {
+    conn: Connection,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Encounter {
+    pub puuid: String,
+    pub game_name: String,
+    pub tag_line: String,
+    pub region: String,
+    pub game_id: i64,
+    pub queue_id: i64,
+    /// Unix timestamp in seconds.
+    pub seen_at: i64,
+    /// `win` or `loss` once the game has ended, `None` for dodged or unfinished games.
+    pub result: Option<String>,
+    /// Their line from the archived scoreboard, `None` until the game has ended.
+    pub stats: Option<EncounterStats>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EncounterStats {
+    pub champion_id: i64,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerNote {
+    pub id: i64,
+    pub note: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerAnnotations {
+    pub puuid: String,
+    pub notes: Vec<PlayerNote>,
+    pub tags: Vec<String>,
+    pub encounters: i64,
+}
+
+impl PlayerAnnotations {
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// A message from a champ select chat.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub message_id: String,
+    pub game_id: i64,
+    /// Empty when the sender could not be matched to a lobby player.
+    pub puuid: String,
+    pub sender: String,
+    pub body: String,
+    /// ISO 8601 timestamp from the chat service.
+    pub sent_at: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedPlayer {
+    pub id: i64,
+    pub puuid: Option<String>,
+    /// Lowercased `name#tag`, since Riot IDs are case insensitive.
+    pub riot_id: Option<String>,
+    pub reason: String,
+    pub added_at: i64,
+}
+
+impl BlockedPlayer {
+    pub fn matches(&self, participant: &Participant) -> bool {
+        let puuid_match = self
+            .puuid
+            .as_deref()
+            .is_some_and(|puuid| !participant.puuid.is_empty() && puuid == participant.puuid);
+        let riot_id_match = self.riot_id.as_deref().is_some_and(|riot_id| {
+            riot_id == normalize_riot_id(&participant.game_name, &participant.game_tag)
+        });
+
+        puuid_match || riot_id_match
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocklistMatch {
+    pub entry: BlockedPlayer,
+    pub participant: Participant,
+}
+
+fn normalize_riot_id(game_name: &str, tag_line: &str) -> String {
+    format!("{}#{}", game_name.trim(), tag_line.trim()).to_lowercase()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+impl Storage {
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Storage { conn })
+    }
+
+    /// Falls back to a throwaway database so the app keeps working if the file can't be opened.
+    pub fn open_or_in_memory(path: &Path) -> Self {
+        match Storage::open(path) {
+            Ok(storage) => storage,
+            Err(e) => {
+                error!("Failed to open {}: {}", path.display(), e);
+                let conn = Connection::open_in_memory().unwrap();
+                conn.execute_batch(SCHEMA).unwrap();
+                Storage { conn }
+            }
+        }
+    }
+
+    /// Records every participant except the local player. Seeing the same lobby again (the
+    /// champ select handler runs more than once per game) is a no-op.
+    pub fn record_encounters(
+        &self,
+        lobby: &Lobby,
+        local_puuid: &str,
+        region: &str,
+        game_id: i64,
+        queue_id: i64,
+    ) -> Result<usize, AppError> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT OR IGNORE INTO encounters
+                (puuid, game_name, tag_line, region, game_id, queue_id, seen_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+
+        let seen_at = now();
+        let mut inserted = 0;
+        for participant in &lobby.participants {
+            if participant.puuid.is_empty() || participant.puuid == local_puuid {
+                continue;
+            }
+
+            inserted += stmt.execute(params![
+                participant.puuid,
+                participant.game_name,
+                participant.game_tag,
+                region,
+                game_id,
+                queue_id,
+                seen_at
+            ])?;
+        }
+
+        Ok(inserted)
+    }
+
+    pub fn record_result(&self, game_id: i64, result: &str) -> Result<usize, AppError> {
+        Ok(self.conn.execute(
+            "UPDATE encounters SET result = ?1 WHERE game_id = ?2",
+            params![result, game_id],
+        )?)
+    }
+
+    /// Every game we shared with this player, newest first.
+    pub fn get_encounters(&self, puuid: &str) -> Result<Vec<Encounter>, AppError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT e.puuid, e.game_name, e.tag_line, e.region, e.game_id, e.queue_id, e.seen_at,
+                    e.result, p.champion_id, p.kills, p.deaths, p.assists
+             FROM encounters e
+             LEFT JOIN game_players p ON p.game_id = e.game_id AND p.puuid = e.puuid
+             WHERE e.puuid = ?1 ORDER BY e.seen_at DESC",
+        )?;
+
+        let encounters = stmt
+            .query_map(params![puuid], |row| {
+                Ok(Encounter {
+                    puuid: row.get(0)?,
+                    game_name: row.get(1)?,
+                    tag_line: row.get(2)?,
+                    region: row.get(3)?,
+                    game_id: row.get(4)?,
+                    queue_id: row.get(5)?,
+                    seen_at: row.get(6)?,
+                    result: row.get(7)?,
+                    stats: match row.get::<_, Option<i64>>(8)? {
+                        Some(champion_id) => Some(EncounterStats {
+                            champion_id,
+                            kills: row.get(9)?,
+                            deaths: row.get(10)?,
+                            assists: row.get(11)?,
+                        }),
+                        None => None,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(encounters)
+    }
+
+    /// Archives a finished game's scoreboard, both raw and one row per player so it can be
+    /// joined with the encounters.
+    pub fn record_game(&mut self, stats: &EndOfGameStats, raw: &Value) -> Result<(), AppError> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO games
+                (game_id, queue_id, game_length, result, ended_at, scoreboard)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                stats.game_id,
+                stats.queue_id,
+                stats.game_length,
+                stats.result(),
+                now(),
+                raw.to_string()
+            ],
+        )?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO game_players
+                    (game_id, puuid, game_name, tag_line, team_id, champion_id, kills, deaths,
+                     assists, creep_score, gold, damage, vision_score)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            )?;
+            for line in stats.scoreboard() {
+                stmt.execute(params![
+                    stats.game_id,
+                    line.puuid,
+                    line.game_name,
+                    line.tag_line,
+                    line.team_id,
+                    line.champion_id,
+                    line.kills,
+                    line.deaths,
+                    line.assists,
+                    line.creep_score,
+                    line.gold,
+                    line.damage_to_champions,
+                    line.vision_score
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn add_player_note(&self, puuid: &str, note: &str) -> Result<(), AppError> {
+        let note = note.trim();
+        if note.is_empty() {
+            return Err(AppError::InvalidInput("Note cannot be empty".to_string()));
+        }
+
+        self.conn.execute(
+            "INSERT INTO player_notes (puuid, note, created_at) VALUES (?1, ?2, ?3)",
+            params![puuid, note, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the player's tags. Tags are trimmed, blank ones dropped and duplicates merged.
+    pub fn set_player_tags(&mut self, puuid: &str, tags: &[String]) -> Result<(), AppError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM player_tags WHERE puuid = ?1", params![puuid])?;
+        {
+            let mut stmt = tx
+                .prepare_cached("INSERT OR IGNORE INTO player_tags (puuid, tag) VALUES (?1, ?2)")?;
+            for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                stmt.execute(params![puuid, tag])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_player_annotations(&self, puuid: &str) -> Result<PlayerAnnotations, AppError> {
+        let notes = self
+            .conn
+            .prepare_cached(
+                "SELECT id, note, created_at FROM player_notes
+                 WHERE puuid = ?1 ORDER BY created_at DESC, id DESC",
+            )?
+            .query_map(params![puuid], |row| {
+                Ok(PlayerNote {
+                    id: row.get(0)?,
+                    note: row.get(1)?,
+                    created_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tags = self
+            .conn
+            .prepare_cached("SELECT tag FROM player_tags WHERE puuid = ?1 ORDER BY tag")?
+            .query_map(params![puuid], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let encounters = self.conn.query_row(
+            "SELECT COUNT(*) FROM encounters WHERE puuid = ?1",
+            params![puuid],
+            |row| row.get(0),
+        )?;
+
+        Ok(PlayerAnnotations {
+            puuid: puuid.to_string(),
+            notes,
+            tags,
+            encounters,
+        })
+    }
+
+    /// Blocks a player by puuid, by `name#tag` Riot ID, or both.
+    pub fn add_to_blocklist(
+        &self,
+        puuid: Option<&str>,
+        riot_id: Option<&str>,
+        reason: &str,
+    ) -> Result<BlockedPlayer, AppError> {
+        let puuid = puuid.map(str::trim).filter(|p| !p.is_empty());
+        let riot_id = match riot_id.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(riot_id) => match riot_id.split_once('#') {
+                Some((name, tag)) if !name.trim().is_empty() && !tag.trim().is_empty() => {
+                    Some(normalize_riot_id(name, tag))
+                }
+                _ => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Riot ID must look like name#tag, got {}",
+                        riot_id
+                    )))
+                }
+            },
+            None => None,
+        };
+
+        if puuid.is_none() && riot_id.is_none() {
+            return Err(AppError::InvalidInput(
+                "A puuid or Riot ID is required".to_string(),
+            ));
+        }
+
+        let added_at = now();
+        self.conn.execute(
+            "INSERT INTO blocklist (puuid, riot_id, reason, added_at) VALUES (?1, ?2, ?3, ?4)",
+            params![puuid, riot_id, reason.trim(), added_at],
+        )?;
+
+        Ok(BlockedPlayer {
+            id: self.conn.last_insert_rowid(),
+            puuid: puuid.map(str::to_string),
+            riot_id,
+            reason: reason.trim().to_string(),
+            added_at,
+        })
+    }
+
+    pub fn remove_from_blocklist(&self, id: i64) -> Result<(), AppError> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM blocklist WHERE id = ?1", params![id])?;
+        if removed == 0 {
+            return Err(AppError::InvalidInput(format!(
+                "No blocklist entry with id {}",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn get_blocklist(&self) -> Result<Vec<BlockedPlayer>, AppError> {
+        let blocklist = self
+            .conn
+            .prepare_cached(
+                "SELECT id, puuid, riot_id, reason, added_at FROM blocklist ORDER BY added_at DESC",
+            )?
+            .query_map([], |row| {
+                Ok(BlockedPlayer {
+                    id: row.get(0)?,
+                    puuid: row.get(1)?,
+                    riot_id: row.get(2)?,
+                    reason: row.get(3)?,
+                    added_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(blocklist)
+    }
+
+    /// Returns whether the message was new.
+    pub fn record_chat_message(&self, message: &ChatMessage) -> Result<bool, AppError> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO chat_messages
+                (message_id, game_id, puuid, sender, body, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                message.message_id,
+                message.game_id,
+                message.puuid,
+                message.sender,
+                message.body,
+                message.sent_at,
+            ],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Everything the player said in champ selects we shared, newest first.
+    pub fn get_chat_messages(&self, puuid: &str) -> Result<Vec<ChatMessage>, AppError> {
+        let messages = self
+            .conn
+            .prepare_cached(
+                "SELECT message_id, game_id, puuid, sender, body, sent_at FROM chat_messages
+                 WHERE puuid = ?1 ORDER BY sent_at DESC",
+            )?
+            .query_map(params![puuid], |row| {
+                Ok(ChatMessage {
+                    message_id: row.get(0)?,
+                    game_id: row.get(1)?,
+                    puuid: row.get(2)?,
+                    sender: row.get(3)?,
+                    body: row.get(4)?,
+                    sent_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages)
+    }
+
+    pub fn record_dodge(&self, game_id: i64) -> Result<(), AppError> {
+        self.conn.execute(
+            "INSERT INTO dodges (game_id, dodged_at) VALUES (?1, ?2)",
+            params![game_id, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Dodges made through the app in the last `window_secs` seconds.
+    pub fn recent_dodges(&self, window_secs: i64) -> Result<u32, AppError> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM dodges WHERE dodged_at >= ?1",
+            params![now() - window_secs],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn find_blocklisted(&self, lobby: &Lobby) -> Result<Vec<BlocklistMatch>, AppError> {
+        let blocklist = self.get_blocklist()?;
+        let matches = lobby
+            .participants
+            .iter()
+            .filter_map(|participant| {
+                blocklist
+                    .iter()
+                    .find(|entry| entry.matches(participant))
+                    .map(|entry| BlocklistMatch {
+                        entry: entry.clone(),
+                        participant: participant.clone(),
+                    })
+            })
+            .collect();
+
+        Ok(matches)
+    }
+}
+
+/// Stores the current champ select lobby, tagged with the game and queue from the gameflow
+/// session.
+pub async fn record_lobby(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    local_puuid: &str,
+    region: &str,
+) {
+    let session = match gameflow::get_gameflow_session(remoting_client).await {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("Not recording encounters, no gameflow session: {:?}", e);
+            return;
+        }
+    };
+
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+    match storage.record_encounters(
+        lobby,
+        local_puuid,
+        region,
+        session.game_data.game_id as i64,
+        session.game_data.queue.id,
+    ) {
+        Ok(0) => {}
+        Ok(inserted) => debug!("Recorded {} new encounters", inserted),
+        Err(e) => error!("Failed to record encounters: {}", e),
+    }
+}
+
+/// Emits `player_annotations` with the notes and tags of everyone in the lobby we have
+/// written something about.
+pub async fn emit_lobby_annotations(app_handle: &AppHandle, lobby: &Lobby) {
+    let storage = app_handle.state::<ManagedStorage>();
+    let storage = storage.0.lock().await;
+
+    let mut annotations = Vec::new();
+    for participant in &lobby.participants {
+        match storage.get_player_annotations(&participant.puuid) {
+            Ok(a) if !a.is_empty() => annotations.push(a),
+            Ok(_) => {}
+            Err(e) => error!(
+                "Failed to load annotations for {}: {}",
+                participant.puuid, e
+            ),
+        }
+    }
+    drop(storage);
+
+    if !annotations.is_empty() {
+        info!("Found annotations for {} lobby players", annotations.len());
+        streamer::emit_all(app_handle, "player_annotations", annotations).await;
+    }
+}
+
+/// Returns the lobby members on the blocklist. The first time any are found in `game_id`,
+/// also emits `blocklisted_player_detected` for each of them, notifies and plays the
+/// blocklist sound for them at once.
+pub async fn check_blocklist(
+    app_handle: &AppHandle,
+    lobby: &Lobby,
+    game_id: Option<u64>,
+) -> Vec<BlocklistMatch> {
+    let storage = app_handle.state::<ManagedStorage>();
+    let matches = match storage.0.lock().await.find_blocklisted(lobby) {
+        Ok(matches) => matches,
+        Err(e) => {
+            error!("Failed to check the blocklist: {}", e);
+            return Vec::new();
+        }
+    };
+    if matches.is_empty() {
+        return matches;
+    }
+    if let Some(game_id) = game_id {
+        if !champ_select::first_time_for_game(app_handle, game_id, |h| &mut h.blocklist).await {
+            return matches;
+        }
+    }
+
+    for found in &matches {
+        warn!(
+            "Blocklisted player in lobby: {}#{} ({})",
+            found.participant.game_name, found.participant.game_tag, found.entry.reason
+        );
+        streamer::emit_all(app_handle, "blocklisted_player_detected", found).await;
+    }
+
+    let names = matches
+        .iter()
+        .map(|m| format!("{}#{}", m.participant.game_name, m.participant.game_tag))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let names = streamer::scrub_text(app_handle, &names).await;
+    notifications::notify(
+        app_handle,
+        NotificationKind::Blocklisted,
+        &i18n::t(app_handle, "notification.blocklisted.title").await,
+        &names,
+    )
+    .await;
+    audio::play(app_handle, Sound::Blocklisted).await;
+
+    matches
+}
@@ -0,0 +1,175 @@
+use crate::{lobby::Lobby, ManagedStreamer};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+/// Name and tag line fields that sit next to each other in the payloads we scrub.
+const ID_KEYS: [(&str, &str); 4] = [
+    ("gameName", "gameTag"),
+    ("gameName", "tagLine"),
+    ("riotIdGameName", "riotIdTagLine"),
+    ("game_name", "game_tag"),
+];
+
+struct Teammate {
+    puuid: String,
+    name: String,
+    tag: String,
+}
+
+impl Teammate {
+    fn matches(&self, puuid: Option<&str>, name: &str, tag: &str) -> bool {
+        if let Some(puuid) = puuid.filter(|p| !p.is_empty() && !self.puuid.is_empty()) {
+            return puuid == self.puuid;
+        }
+        !self.name.is_empty()
+            && self.name.eq_ignore_ascii_case(name)
+            && self.tag.eq_ignore_ascii_case(tag)
+    }
+}
+
+/// Kept apart from the config so scrubbing an event never waits on the config lock.
+#[derive(Default)]
+pub struct StreamerState {
+    pub enabled: bool,
+    /// In lobby order.
+    teammates: Vec<Teammate>,
+}
+
+impl StreamerState {
+    pub fn new(enabled: bool) -> Self {
+        StreamerState {
+            enabled,
+            teammates: Vec::new(),
+        }
+    }
+}
+
+fn placeholder(index: usize) -> String {
+    format!("Player {}", index + 1)
+}
+
+fn riot_id(name: &str, tag: &str) -> String {
+    format!("{}#{}", name, tag)
+}
+
+/// Replaces whole Riot IDs anywhere in `text`, and `text` itself when it is a bare name.
+fn scrub_str(text: &str, teammates: &[Teammate]) -> String {
+    if let Some(i) = teammates
+        .iter()
+        .position(|t| !t.name.is_empty() && text.eq_ignore_ascii_case(&t.name))
+    {
+        return placeholder(i);
+    }
+
+    let mut text = text.to_string();
+    for (i, teammate) in teammates.iter().enumerate() {
+        if !teammate.name.is_empty() {
+            text = text.replace(&riot_id(&teammate.name, &teammate.tag), &placeholder(i));
+        }
+    }
+    text
+}
+
+/// Replaces the name and tag line pairs in `map` that belong to a teammate, going by the
+/// puuid next to them when there is one. Returns the keys of every pair found, so their
+/// values aren't scrubbed again by name alone, which would hit players who merely share a
+/// name or a common tag like `EUW` with a teammate.
+fn scrub_ids(map: &mut Map<String, Value>, teammates: &[Teammate]) -> Vec<&'static str> {
+    let puuid = map.get("puuid").and_then(Value::as_str).map(str::to_string);
+    let mut paired = Vec::new();
+    for (name_key, tag_key) in ID_KEYS {
+        let (Some(Value::String(name)), Some(Value::String(tag))) =
+            (map.get(name_key), map.get(tag_key))
+        else {
+            continue;
+        };
+        paired.extend([name_key, tag_key]);
+
+        let teammate = teammates
+            .iter()
+            .position(|t| t.matches(puuid.as_deref(), name, tag));
+        if let Some(i) = teammate {
+            map.insert(name_key.to_string(), Value::String(placeholder(i)));
+            map.insert(tag_key.to_string(), Value::String(String::new()));
+        }
+    }
+    paired
+}
+
+fn scrub_value(value: &mut Value, teammates: &[Teammate]) {
+    match value {
+        Value::String(text) => *text = scrub_str(text, teammates),
+        Value::Array(items) => {
+            for item in items {
+                scrub_value(item, teammates);
+            }
+        }
+        Value::Object(map) => {
+            let paired = scrub_ids(map, teammates);
+            for (key, value) in map.iter_mut() {
+                if !paired.contains(&key.as_str()) {
+                    scrub_value(value, teammates);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub async fn set_enabled(app_handle: &AppHandle, enabled: bool) {
+    app_handle.state::<ManagedStreamer>().0.lock().await.enabled = enabled;
+}
+
+/// Remembers whose names to hide. Called whenever we fetch the lobby.
+pub async fn remember_lobby(app_handle: &AppHandle, lobby: &Lobby) {
+    if lobby.participants.is_empty() {
+        return;
+    }
+    let teammates = lobby
+        .participants
+        .iter()
+        .map(|p| Teammate {
+            puuid: p.puuid.clone(),
+            name: p.game_name.clone(),
+            tag: p.game_tag.clone(),
+        })
+        .collect();
+    app_handle
+        .state::<ManagedStreamer>()
+        .0
+        .lock()
+        .await
+        .teammates = teammates;
+}
+
+/// `payload` as JSON, with teammate names replaced by `Player 1` to `Player 5` while streamer
+/// mode is on.
+pub async fn scrub<T: Serialize>(app_handle: &AppHandle, payload: T) -> Value {
+    let mut value = serde_json::to_value(payload).unwrap_or(Value::Null);
+    let streamer = app_handle.state::<ManagedStreamer>();
+    let streamer = streamer.0.lock().await;
+    if streamer.enabled {
+        scrub_value(&mut value, &streamer.teammates);
+    }
+    value
+}
+
+/// Like `scrub`, for text we put together ourselves such as exports.
+pub async fn scrub_text(app_handle: &AppHandle, text: &str) -> String {
+    let streamer = app_handle.state::<ManagedStreamer>();
+    let streamer = streamer.0.lock().await;
+    if !streamer.enabled {
+        return text.to_string();
+    }
+    text.lines()
+        .map(|line| scrub_str(line, &streamer.teammates))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `emit_all` for events that can carry teammate names.
+pub async fn emit_all<T: Serialize>(app_handle: &AppHandle, event: &str, payload: T) {
+    let payload = scrub(app_handle, payload).await;
+    app_handle.emit_all(event, payload).unwrap();
+}
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 use shaco::rest::RESTClient;
 
@@ -32,14 +33,35 @@ pub struct RerollPoints {
     pub points_to_reroll: i64,
 }
 
-pub async fn get_current_summoner(remoting_client: &RESTClient) -> Summoner {
-    let summoner: Summoner = serde_json::from_value(
-        remoting_client
-            .get("/lol-summoner/v1/current-summoner".to_string())
-            .await
-            .unwrap(),
-    )
-    .unwrap();
+pub async fn get_current_summoner(remoting_client: &RESTClient) -> Result<Summoner, AppError> {
+    let summoner = remoting_client
+        .get("/lol-summoner/v1/current-summoner".to_string())
+        .await
+        .map_err(AppError::lcu_request)?;
 
-    summoner
+    Ok(serde_json::from_value(summoner)?)
+}
+
+/// The subset of summoner data available for any player, not just the local one.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SummonerIdentity {
+    pub game_name: String,
+    pub tag_line: String,
+    pub puuid: String,
+    pub summoner_id: i64,
+    pub summoner_level: i64,
+    pub profile_icon_id: i64,
+}
+
+pub async fn get_summoner_by_puuid(
+    remoting_client: &RESTClient,
+    puuid: &str,
+) -> Result<SummonerIdentity, AppError> {
+    let summoner = remoting_client
+        .get(format!("/lol-summoner/v2/summoners/puuid/{}", puuid))
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(serde_json::from_value(summoner)?)
 }
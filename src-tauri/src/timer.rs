@@ -0,0 +1,103 @@
+use crate::{
+    champ_select::ChampSelectSession, gameflow::GameflowPhase, AppConfig, ManagedGameflowState,
+    ManagedTimerState,
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::debug;
+
+const TICK: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+pub struct TimerState {
+    pub game_id: u64,
+    pub phase: String,
+    pub deadline: Option<Instant>,
+    pub warned: bool,
+    pub ticking: bool,
+}
+
+impl TimerState {
+    pub fn ms_remaining(&self) -> Option<u64> {
+        self.deadline
+            .map(|d| d.saturating_duration_since(Instant::now()).as_millis() as u64)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerUpdate {
+    pub phase: String,
+    pub ms_remaining: u64,
+}
+
+/// Syncs the countdown with the timer block of a champ select session and starts the tick
+/// task that emits `champ_select_timer` events if it is not running yet.
+pub async fn update_timer(app_handle: &AppHandle, session: &ChampSelectSession) {
+    let timer_state = app_handle.state::<ManagedTimerState>();
+    let mut timer_state = timer_state.0.lock().await;
+
+    if timer_state.game_id != session.game_id || timer_state.phase != session.timer.phase {
+        timer_state.warned = false;
+    }
+
+    timer_state.game_id = session.game_id;
+    timer_state.phase = session.timer.phase.clone();
+    timer_state.deadline = if session.timer.is_infinite {
+        None
+    } else {
+        Some(Instant::now() + Duration::from_millis(session.timer.adjusted_time_left_in_phase))
+    };
+
+    if !timer_state.ticking && timer_state.deadline.is_some() {
+        timer_state.ticking = true;
+        tauri::async_runtime::spawn(tick(app_handle.clone()));
+    }
+}
+
+async fn tick(app_handle: AppHandle) {
+    debug!("Champ select timer started");
+
+    loop {
+        tokio::time::sleep(TICK).await;
+
+        let gameflow = app_handle.state::<ManagedGameflowState>();
+        let in_champ_select = *gameflow.0.lock().await == GameflowPhase::ChampSelect;
+
+        let cfg = app_handle.state::<AppConfig>();
+        let warning_ms = cfg.0.lock().await.timer_warning_seconds as u64 * 1000;
+
+        let timer_state = app_handle.state::<ManagedTimerState>();
+        let mut timer_state = timer_state.0.lock().await;
+
+        let ms_remaining = match timer_state.ms_remaining() {
+            Some(ms_remaining) if in_champ_select => ms_remaining,
+            _ => {
+                timer_state.ticking = false;
+                break;
+            }
+        };
+
+        let update = TimerUpdate {
+            phase: timer_state.phase.clone(),
+            ms_remaining,
+        };
+
+        if warning_ms > 0 && ms_remaining <= warning_ms && !timer_state.warned {
+            timer_state.warned = true;
+            app_handle
+                .emit_all("champ_select_timer_warning", &update)
+                .unwrap();
+        }
+
+        if ms_remaining == 0 {
+            timer_state.deadline = None;
+        }
+
+        drop(timer_state);
+        app_handle.emit_all("champ_select_timer", update).unwrap();
+    }
+
+    debug!("Champ select timer stopped");
+}
@@ -0,0 +1,79 @@
+use crate::{champ_select::ChampSelectSession, error::AppError, AppConfig};
+use serde::Deserialize;
+use shaco::rest::RESTClient;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RequestKind {
+    Trade,
+    PickOrderSwap,
+}
+
+impl RequestKind {
+    fn path(&self) -> &'static str {
+        match self {
+            RequestKind::Trade => "trades",
+            RequestKind::PickOrderSwap => "pick-order-swaps",
+        }
+    }
+}
+
+/// Accepts or declines an incoming champion trade or pick order swap.
+pub async fn respond(
+    remoting_client: &RESTClient,
+    kind: RequestKind,
+    id: i64,
+    accept: bool,
+) -> Result<(), AppError> {
+    remoting_client
+        .post(
+            format!(
+                "/lol-champ-select/v1/session/{}/{}/{}",
+                kind.path(),
+                id,
+                if accept { "accept" } else { "decline" }
+            ),
+            serde_json::json!({}),
+        )
+        .await
+        .map_err(AppError::lcu_request)?;
+
+    Ok(())
+}
+
+/// Accepts an incoming pick order swap when `auto_accept_earlier_swaps` is on and the swap
+/// would move our pick earlier.
+pub async fn handle_pick_order_swap(
+    app_handle: &AppHandle,
+    remoting_client: &RESTClient,
+    session: &ChampSelectSession,
+    id: i64,
+    cell_id: i64,
+) {
+    let enabled = {
+        let config = app_handle.state::<AppConfig>();
+        let config = config.0.lock().await;
+        config.auto_accept_earlier_swaps
+    };
+    if !enabled {
+        return;
+    }
+
+    let (Some(theirs), Some(ours)) = (
+        session.pick_index(cell_id),
+        session.pick_index(session.local_player_cell_id),
+    ) else {
+        return;
+    };
+    if theirs >= ours {
+        info!("Leaving pick order swap {} alone, it moves us later", id);
+        return;
+    }
+
+    match respond(remoting_client, RequestKind::PickOrderSwap, id, true).await {
+        Ok(()) => info!("Accepted pick order swap {} from cell {}", id, cell_id),
+        Err(e) => warn!("Failed to accept pick order swap {}: {}", id, e),
+    }
+}
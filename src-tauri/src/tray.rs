@@ -0,0 +1,101 @@
+use crate::{commands, AppConfig};
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem,
+};
+use tracing::{info, warn};
+
+const STATUS: &str = "status";
+const OPEN_MULTI_SEARCH: &str = "open_multi_search";
+const ARM_DODGE: &str = "arm_dodge";
+const DODGE_NOW: &str = "dodge_now";
+const TOGGLE_AUTO_ACCEPT: &str = "toggle_auto_accept";
+const SHOW: &str = "show";
+const QUIT: &str = "quit";
+
+pub fn build() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(STATUS, status_title(false)).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(OPEN_MULTI_SEARCH, "Open multi-search"))
+        .add_item(CustomMenuItem::new(ARM_DODGE, "Arm dodge"))
+        .add_item(CustomMenuItem::new(DODGE_NOW, "Dodge now"))
+        .add_item(CustomMenuItem::new(TOGGLE_AUTO_ACCEPT, "Auto-accept"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(SHOW, "Show window"))
+        .add_item(CustomMenuItem::new(QUIT, "Quit"));
+
+    SystemTray::new().with_menu(menu)
+}
+
+fn status_title(connected: bool) -> &'static str {
+    if connected {
+        "League Client connected"
+    } else {
+        "League Client not connected"
+    }
+}
+
+pub fn set_connected(app_handle: &AppHandle, connected: bool) {
+    if let Err(e) = app_handle
+        .tray_handle()
+        .get_item(STATUS)
+        .set_title(status_title(connected))
+    {
+        warn!("Failed to update the tray status: {:?}", e);
+    }
+}
+
+/// Keeps the auto-accept checkmark in line with the config.
+pub fn set_auto_accept(app_handle: &AppHandle, enabled: bool) {
+    if let Err(e) = app_handle
+        .tray_handle()
+        .get_item(TOGGLE_AUTO_ACCEPT)
+        .set_selected(enabled)
+    {
+        warn!("Failed to update the tray auto-accept item: {:?}", e);
+    }
+}
+
+pub fn show_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+async fn run(app_handle: AppHandle, id: String) {
+    info!("Tray action: {}", id);
+    let result = match id.as_str() {
+        OPEN_MULTI_SEARCH => commands::open_opgg_link(app_handle).await,
+        ARM_DODGE => commands::enable_dodge(app_handle).await,
+        DODGE_NOW => commands::dodge(app_handle, None).await,
+        TOGGLE_AUTO_ACCEPT => {
+            let enabled = !app_handle.state::<AppConfig>().0.lock().await.auto_accept;
+            let cfg_handle = app_handle.clone();
+            commands::set_auto_accept(enabled, cfg_handle.state(), app_handle)
+                .await
+                .map(|_| ())
+        }
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        warn!("Tray action {} failed: {}", id, e);
+    }
+}
+
+pub fn handle_event(app_handle: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_window(app_handle),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            SHOW => show_window(app_handle),
+            QUIT => app_handle.exit(0),
+            _ => {
+                tauri::async_runtime::spawn(run(app_handle.clone(), id));
+            }
+        },
+        _ => {}
+    }
+}
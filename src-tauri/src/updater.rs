@@ -0,0 +1,108 @@
+use crate::{error::AppError, ManagedUpdateProgress};
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{updater::UpdateResponse, AppHandle, Manager, UpdaterEvent, Wry};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    pub latest_version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    /// `None` when the server doesn't send a content length.
+    pub total: Option<u64>,
+}
+
+async fn fetch(app_handle: &AppHandle) -> Result<UpdateResponse<Wry>, AppError> {
+    app_handle
+        .updater()
+        .check()
+        .await
+        .map_err(|e| AppError::Update(e.to_string()))
+}
+
+fn info(app_handle: &AppHandle, update: &UpdateResponse<Wry>) -> UpdateInfo {
+    UpdateInfo {
+        available: update.is_update_available(),
+        current_version: app_handle.package_info().version.to_string(),
+        latest_version: update.latest_version().to_string(),
+        notes: update.body().cloned(),
+        date: update.date().map(|d| d.to_string()),
+    }
+}
+
+pub async fn check_for_updates(app_handle: &AppHandle) -> Result<UpdateInfo, AppError> {
+    let update = fetch(app_handle).await?;
+    Ok(info(app_handle, &update))
+}
+
+/// Checks once at startup and emits `update_available` when there is a newer version.
+pub async fn check_on_startup(app_handle: AppHandle) {
+    match check_for_updates(&app_handle).await {
+        Ok(update) if update.available => {
+            info!("Update available: {}", update.latest_version);
+            app_handle.emit_all("update_available", update).unwrap();
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to check for updates: {}", e),
+    }
+}
+
+/// Downloads and installs the latest version, emitting `update_progress` while downloading,
+/// then restarts into it.
+pub async fn install_update(app_handle: &AppHandle) -> Result<(), AppError> {
+    let update = fetch(app_handle).await?;
+    if !update.is_update_available() {
+        return Err(AppError::Update(
+            "Already on the latest version".to_string(),
+        ));
+    }
+
+    info!("Installing update {}", update.latest_version());
+    app_handle
+        .state::<ManagedUpdateProgress>()
+        .0
+        .store(0, Ordering::Relaxed);
+    update
+        .download_and_install()
+        .await
+        .map_err(|e| AppError::Update(e.to_string()))?;
+
+    info!("Update installed, restarting");
+    app_handle.restart();
+    Ok(())
+}
+
+/// Forwards the updater's download progress to the frontend as `update_progress`.
+pub fn handle_event(app_handle: &AppHandle, event: UpdaterEvent) {
+    match event {
+        UpdaterEvent::DownloadProgress {
+            chunk_length,
+            content_length,
+        } => {
+            let progress = app_handle.state::<ManagedUpdateProgress>();
+            let downloaded =
+                progress.0.fetch_add(chunk_length as u64, Ordering::Relaxed) + chunk_length as u64;
+            app_handle
+                .emit_all(
+                    "update_progress",
+                    UpdateProgress {
+                        downloaded,
+                        total: content_length,
+                    },
+                )
+                .unwrap();
+        }
+        UpdaterEvent::Error(e) => error!("Updater error: {}", e),
+        _ => {}
+    }
+}
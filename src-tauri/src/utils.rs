@@ -1,6 +1,15 @@
-use crate::lobby::{Lobby, Participant};
+use crate::{
+    config::{BrowserConfig, Config, CustomProvider},
+    error::AppError,
+    lobby::{Lobby, Participant},
+    region,
+};
+use std::{path::Path, process::Command};
+use tracing::{error, info};
 use urlencoding::encode;
 
+const MAX_MULTI_SEARCH: usize = 5;
+
 pub fn create_opgg_link(summoners: &Vec<Participant>, region: &str) -> String {
     let base_url = format!("https://www.op.gg/multisearch/{}?summoners=", region);
     let mut link_path = String::new();
@@ -15,11 +24,21 @@ pub fn create_opgg_link(summoners: &Vec<Participant>, region: &str) -> String {
     format!("{}{}", base_url, encoded_path)
 }
 
+/// DeepLOL paths use upper case web regions, except the Middle East which it lists under its
+/// platform id.
+fn deeplol_region(region: &str) -> String {
+    match region {
+        "ME" => "ME1".to_string(),
+        _ => region.to_uppercase(),
+    }
+}
+
+/// DeepLOL wants Riot IDs as `name-tag` rather than `name#tag`.
 pub fn create_deeplol_link(summoners: &Vec<Participant>, region: &str) -> String {
-    let base_url = format!("https://deeplol.gg/multi/{}/", region);
+    let base_url = format!("https://www.deeplol.gg/multi/{}/", deeplol_region(region));
     let mut link_path = String::new();
     for summoner in summoners {
-        let full_tag = format!("{}#{}", summoner.game_name, summoner.game_tag);
+        let full_tag = format!("{}-{}", summoner.game_name, summoner.game_tag);
         link_path.push_str(&full_tag);
         link_path.push(',');
     }
@@ -29,8 +48,14 @@ pub fn create_deeplol_link(summoners: &Vec<Participant>, region: &str) -> String
     format!("{}{}", base_url, encoded_path)
 }
 
-pub fn create_ugg_link(summoners: &Vec<Participant>, region: String) -> String {
-    let base_url = format!("https://u.gg/multisearch?region={}", region.to_lowercase());
+/// u.gg keys regions by their platform id.
+fn ugg_region(region: &str) -> String {
+    region::platform_id(region)
+}
+
+/// u.gg wants Riot IDs as `name-tag` rather than `name#tag`.
+pub fn create_ugg_link(summoners: &Vec<Participant>, region: &str) -> String {
+    let base_url = format!("https://u.gg/multisearch?region={}", ugg_region(region));
     let mut link_path = String::new();
     for summoner in summoners {
         let full_tag = format!("{}-{}", summoner.game_name, summoner.game_tag);
@@ -57,9 +82,154 @@ pub fn create_tracker_link(summoners: &Vec<Participant>, region: &str) -> String
     format!("{}{}", base_url, encoded_path)
 }
 
-pub fn display_champ_select(lobby: &Lobby, region: &str, site: &String) {
+/// Porofessor's pregame page analyses the whole lobby, names go in the path as `name-tag`.
+pub fn create_porofessor_link(summoners: &Vec<Participant>, region: &str) -> String {
+    let base_url = format!("https://porofessor.gg/pregame/{}/", region.to_lowercase());
+    let mut link_path = String::new();
+    for summoner in summoners {
+        let full_tag = format!("{}-{}", summoner.game_name, summoner.game_tag);
+        link_path.push_str(&full_tag);
+        link_path.push(',');
+    }
+    link_path.pop();
+
+    let encoded_path = encode(&link_path);
+    format!("{}{}", base_url, encoded_path)
+}
+
+/// Mobalytics takes `name-tag` Riot IDs, each one encoded on its own and joined by plain
+/// commas, encoding the commas too makes it treat the lobby as a single name.
+pub fn create_mobalytics_link(summoners: &Vec<Participant>, region: &str) -> String {
+    let base_url = format!(
+        "https://mobalytics.gg/lol/multisearch?region={}",
+        region.to_lowercase()
+    );
+    let names: Vec<String> = summoners
+        .iter()
+        .map(|s| encode(&format!("{}-{}", s.game_name, s.game_tag)).into_owned())
+        .collect();
+
+    format!("{}&summoners={}", base_url, names.join(","))
+}
+
+pub fn create_lolalytics_link(summoners: &Vec<Participant>, region: &str) -> String {
+    let base_url = format!(
+        "https://lolalytics.com/lol/multi/?region={}",
+        region.to_lowercase()
+    );
+    let mut link_path = String::new();
+    for summoner in summoners {
+        let full_tag = format!("{}-{}", summoner.game_name, summoner.game_tag);
+        link_path.push_str(&full_tag);
+        link_path.push(',');
+    }
+    link_path.pop();
+
+    let encoded_path = encode(&link_path);
+    format!("{}&names={}", base_url, encoded_path)
+}
+
+/// Lolalytics build page for a champion, filtered to the lane it was assigned to when the
+/// queue has positions.
+pub fn create_lolalytics_champion_link(alias: &str, position: &str) -> String {
+    let base_url = format!("https://lolalytics.com/lol/{}/build/", alias.to_lowercase());
+    let lane = match position {
+        "top" => "top",
+        "jungle" => "jungle",
+        "middle" => "middle",
+        "bottom" => "bottom",
+        "utility" => "support",
+        _ => return base_url,
+    };
+
+    format!("{}?lane={}", base_url, lane)
+}
+
+/// League of Graphs has no multi-search, so each player gets their own profile page.
+pub fn create_leagueofgraphs_link(summoner: &Participant, region: &str) -> String {
+    let full_tag = format!("{}-{}", summoner.game_name, summoner.game_tag);
+    format!(
+        "https://www.leagueofgraphs.com/summoner/{}/{}",
+        region.to_lowercase(),
+        encode(&full_tag)
+    )
+}
+
+/// A single player's profile page on a built-in provider, `None` for sites without one.
+pub fn create_profile_link(site: &str, summoner: &Participant, region: &str) -> Option<String> {
+    let dashed = encode(&format!("{}-{}", summoner.game_name, summoner.game_tag)).into_owned();
+    let link = match site {
+        "opgg" => format!(
+            "https://www.op.gg/summoners/{}/{}",
+            region.to_lowercase(),
+            dashed
+        ),
+        "deeplol" => format!(
+            "https://www.deeplol.gg/summoner/{}/{}",
+            deeplol_region(region),
+            dashed
+        ),
+        "ugg" => format!(
+            "https://u.gg/lol/profile/{}/{}/overview",
+            ugg_region(region),
+            dashed
+        ),
+        "tracker" => format!(
+            "https://tracker.gg/lol/profile/riot/{}/{}/overview",
+            region,
+            encode(&format!("{}#{}", summoner.game_name, summoner.game_tag))
+        ),
+        "porofessor" => format!(
+            "https://porofessor.gg/live/{}/{}",
+            region.to_lowercase(),
+            dashed
+        ),
+        "mobalytics" => format!(
+            "https://mobalytics.gg/lol/profile/{}/{}/overview",
+            region.to_lowercase(),
+            dashed
+        ),
+        "leagueofgraphs" => create_leagueofgraphs_link(summoner, region),
+        _ => return None,
+    };
+
+    Some(link)
+}
+
+/// Renders a custom provider's URL template, one link per player when it uses `{riot_id}`.
+pub fn create_custom_links(
+    provider: &CustomProvider,
+    summoners: &[Participant],
+    region: &str,
+) -> Vec<String> {
+    let riot_ids: Vec<String> = summoners
+        .iter()
+        .map(|s| format!("{}#{}", s.game_name, s.game_tag))
+        .collect();
+    let link = provider
+        .url_template
+        .replace("{region}", region)
+        .replace("{names_csv}", &encode(&riot_ids.join(",")));
+
+    if !link.contains("{riot_id}") {
+        return vec![link];
+    }
+
+    riot_ids
+        .iter()
+        .map(|riot_id| link.replace("{riot_id}", &encode(riot_id)))
+        .collect()
+}
+
+pub fn display_champ_select(lobby: &Lobby, region: &str, config: &Config) -> Result<(), AppError> {
+    let site = &config.multi_provider;
     if lobby.participants.is_empty() {
-        return;
+        return Ok(());
+    }
+    if config.streamer_mode {
+        return Err(AppError::InvalidInput(
+            "Streamer mode is on, links with player names are not opened".to_string(),
+        ));
     }
 
     let mut team_string = String::new();
@@ -74,19 +244,102 @@ pub fn display_champ_select(lobby: &Lobby, region: &str, site: &String) {
         }
     }
 
-    println!("Team: {}", team_string);
-    let link = match site.as_str() {
-        "opgg" => create_opgg_link(&lobby.participants, region),
-        "deeplol" => create_deeplol_link(&lobby.participants, region),
-        "ugg" => create_ugg_link(&lobby.participants, format!("{}1", region)),
-        "tracker" => create_tracker_link(&lobby.participants, region),
-        _ => panic!("Invalid site"),
+    info!("Team: {}", team_string);
+    for group in multi_search_groups(lobby) {
+        if site == "leagueofgraphs" || config.per_player_providers.contains(site) {
+            for link in group
+                .iter()
+                .filter_map(|summoner| create_profile_link(site, summoner, region))
+            {
+                open_link(&link, &config.browser)?;
+            }
+            continue;
+        }
+
+        if let Some(provider) = config.custom_providers.iter().find(|p| &p.id == site) {
+            for link in create_custom_links(provider, &group, region) {
+                open_link(&link, &config.browser)?;
+            }
+            continue;
+        }
+
+        let link = match site.as_str() {
+            "opgg" => create_opgg_link(&group, region),
+            "deeplol" => create_deeplol_link(&group, region),
+            "ugg" => create_ugg_link(&group, region),
+            "tracker" => create_tracker_link(&group, region),
+            "porofessor" => create_porofessor_link(&group, region),
+            "mobalytics" => create_mobalytics_link(&group, region),
+            "lolalytics" => create_lolalytics_link(&group, region),
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "Unknown provider '{}'",
+                    site
+                )))
+            }
+        };
+
+        open_link(&link, &config.browser)?;
+    }
+
+    Ok(())
+}
+
+/// The command line flag that opens a private window, picked from the executable's name.
+fn private_flag(browser_path: &str) -> &'static str {
+    let name = Path::new(browser_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if name.contains("firefox") || name.contains("librewolf") {
+        "--private-window"
+    } else if name.contains("msedge") || name.contains("edge") {
+        "--inprivate"
+    } else if name.contains("opera") {
+        "--private"
+    } else {
+        "--incognito"
+    }
+}
+
+fn open_link(link: &str, browser: &BrowserConfig) -> Result<(), AppError> {
+    let result = if browser.path.is_empty() {
+        open::that(link)
+    } else {
+        let mut command = Command::new(&browser.path);
+        if browser.private {
+            command.arg(private_flag(&browser.path));
+        }
+        command.arg(link).spawn().map(|_| ())
     };
 
-    match open::that(&link) {
-        Ok(_) => {}
-        Err(_) => {
-            println!("Failed to open link in browser");
+    result.map_err(|e| {
+        error!("Failed to open link in browser: {}", e);
+        AppError::Io(format!("Failed to open browser: {}", e))
+    })
+}
+
+/// Multi-search pages only take five names, so bigger lobbies like Arena are split into
+/// several searches without ever splitting a subteam across two of them.
+fn multi_search_groups(lobby: &Lobby) -> Vec<Vec<Participant>> {
+    if lobby.subteams.is_empty() {
+        return lobby
+            .participants
+            .chunks(MAX_MULTI_SEARCH)
+            .map(|c| c.to_vec())
+            .collect();
+    }
+
+    let mut groups: Vec<Vec<Participant>> = Vec::new();
+    for subteam in &lobby.subteams {
+        match groups.last_mut() {
+            Some(group) if group.len() + subteam.participants.len() <= MAX_MULTI_SEARCH => {
+                group.extend(subteam.participants.iter().cloned());
+            }
+            _ => groups.push(subteam.participants.clone()),
         }
     }
+
+    groups
 }
@@ -0,0 +1,101 @@
+use crate::livegame::AllGameData;
+use serde::Serialize;
+
+/// Remakes can be called from 1:30 until 3:00 while a teammate is disconnected.
+const REMAKE_OPENS: f64 = 90.0;
+const REMAKE_CLOSES: f64 = 180.0;
+const EARLY_SURRENDER_OPENS: f64 = 15.0 * 60.0;
+const SURRENDER_OPENS: f64 = 20.0 * 60.0;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoteKind {
+    Remake,
+    EarlySurrender,
+    Surrender,
+}
+
+/// Emitted as `vote_alert` when a vote becomes possible.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteAlert {
+    pub kind: VoteKind,
+    pub game_time: f64,
+    /// Game time the vote can no longer be called at, `None` when it stays open.
+    pub closes_at: Option<f64>,
+    /// Teammates that look disconnected, for remakes.
+    pub afk_players: Vec<String>,
+}
+
+/// Neither the game client nor the LCU reports surrender or remake votes themselves, so this
+/// watches the game clock for the points where a vote can be called instead. A remake is
+/// only flagged when a teammate still has an empty inventory, which nobody connected has
+/// by the time the window opens.
+#[derive(Default)]
+pub struct VoteTracker {
+    remake: bool,
+    early_surrender: bool,
+    surrender: bool,
+}
+
+impl VoteTracker {
+    pub fn check(&mut self, data: &AllGameData) -> Vec<VoteAlert> {
+        let game_time = data.game_data.game_time;
+        let mut alerts = Vec::new();
+
+        // Checked on every poll inside the window, since a teammate can drop at any point.
+        if !self.remake && (REMAKE_OPENS..REMAKE_CLOSES).contains(&game_time) {
+            let afk_players = afk_teammates(data);
+            if !afk_players.is_empty() {
+                self.remake = true;
+                alerts.push(VoteAlert {
+                    kind: VoteKind::Remake,
+                    game_time,
+                    closes_at: Some(REMAKE_CLOSES),
+                    afk_players,
+                });
+            }
+        }
+
+        for (alerted, kind, opens) in [
+            (
+                &mut self.early_surrender,
+                VoteKind::EarlySurrender,
+                EARLY_SURRENDER_OPENS,
+            ),
+            (&mut self.surrender, VoteKind::Surrender, SURRENDER_OPENS),
+        ] {
+            // Only alert around the moment the vote opens, not when polling starts late.
+            if !*alerted && game_time >= opens {
+                *alerted = true;
+                if game_time < opens + 60.0 {
+                    alerts.push(VoteAlert {
+                        kind,
+                        game_time,
+                        closes_at: None,
+                        afk_players: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        alerts
+    }
+}
+
+fn afk_teammates(data: &AllGameData) -> Vec<String> {
+    let Some(team) = data
+        .all_players
+        .iter()
+        .find(|p| p.riot_id == data.active_player.riot_id)
+        .map(|p| p.team.as_str())
+    else {
+        return Vec::new();
+    };
+
+    data.all_players
+        .iter()
+        .filter(|p| p.team == team && !p.is_bot && p.items.is_empty())
+        .map(|p| p.riot_id.clone())
+        .collect()
+}
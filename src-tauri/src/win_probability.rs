@@ -0,0 +1,196 @@
+use crate::{
+    autofill::{self, AutofillFlag},
+    champ_select::ChampSelectSession,
+    lobby::Lobby,
+    mastery::{self, ParticipantMastery},
+    match_history,
+    ranked::{self, ParticipantRank},
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use shaco::rest::RESTClient;
+
+const RECENT_GAMES: usize = 10;
+/// Keep the estimate away from certainty, there is only so much a lobby can tell us.
+const MIN_PROBABILITY: f64 = 5.0;
+const MAX_PROBABILITY: f64 = 95.0;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Factor {
+    pub name: String,
+    /// Percentage points added to or taken from the 50% baseline.
+    pub impact: f64,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WinProbability {
+    /// Estimated chance to win in percent.
+    pub probability: f64,
+    pub factors: Vec<Factor>,
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+fn season_winrate_factor(ranks: &[ParticipantRank]) -> Option<Factor> {
+    let winrates: Vec<f64> = ranks
+        .iter()
+        .filter_map(|r| r.solo.as_ref())
+        .filter(|q| q.games() >= 10)
+        .filter_map(|q| q.winrate())
+        .collect();
+    let winrate = average(&winrates)?;
+
+    Some(Factor {
+        name: "Season winrate".to_string(),
+        impact: (winrate - 0.5) * 40.0,
+        detail: format!(
+            "Team averages {:.0}% in solo queue ({} players with 10+ games)",
+            winrate * 100.0,
+            winrates.len()
+        ),
+    })
+}
+
+/// Teammates ranked two or more tiers below the team's median tend to be the weak link.
+fn rank_spread_factor(ranks: &[ParticipantRank]) -> Option<Factor> {
+    let mut tiers: Vec<usize> = ranks
+        .iter()
+        .filter_map(|r| r.solo.as_ref())
+        .filter_map(|q| ranked::tier_index(&q.tier))
+        .collect();
+    if tiers.len() < 3 {
+        return None;
+    }
+
+    tiers.sort_unstable();
+    let median = tiers[tiers.len() / 2];
+    let below = tiers.iter().filter(|t| **t + 2 <= median).count();
+    if below == 0 {
+        return None;
+    }
+
+    Some(Factor {
+        name: "Rank spread".to_string(),
+        impact: below as f64 * -2.0,
+        detail: format!(
+            "{} teammates are two or more tiers below {}",
+            below,
+            ranked::TIERS[median]
+        ),
+    })
+}
+
+async fn recent_form_factor(remoting_client: &RESTClient, lobby: &Lobby) -> Option<Factor> {
+    let histories = join_all(lobby.participants.iter().map(|participant| {
+        match_history::get_match_history(remoting_client, &participant.puuid, RECENT_GAMES)
+    }))
+    .await;
+
+    let winrates: Vec<f64> = histories
+        .into_iter()
+        .flatten()
+        .filter(|games| !games.is_empty())
+        .map(|games| {
+            let wins = games
+                .iter()
+                .filter(|g| g.participants.first().is_some_and(|p| p.stats.win))
+                .count();
+            wins as f64 / games.len() as f64
+        })
+        .collect();
+    let winrate = average(&winrates)?;
+
+    Some(Factor {
+        name: "Recent form".to_string(),
+        impact: (winrate - 0.5) * 30.0,
+        detail: format!(
+            "Team won {:.0}% of their last {} games",
+            winrate * 100.0,
+            RECENT_GAMES
+        ),
+    })
+}
+
+fn mastery_factor(mastery: &[ParticipantMastery]) -> Option<Factor> {
+    let picked: Vec<_> = mastery.iter().filter_map(|m| m.picked.as_ref()).collect();
+    if picked.is_empty() {
+        return None;
+    }
+
+    let low = picked.iter().filter(|m| m.champion_points < 10_000).count();
+    let high = picked
+        .iter()
+        .filter(|m| m.champion_points >= 100_000)
+        .count();
+    if low == 0 && high == 0 {
+        return None;
+    }
+
+    Some(Factor {
+        name: "Champion mastery".to_string(),
+        impact: high as f64 * 1.5 - low as f64 * 2.0,
+        detail: format!(
+            "{} players on a champion they mastered, {} on one they barely played",
+            high, low
+        ),
+    })
+}
+
+fn autofill_factor(flags: &[AutofillFlag]) -> Option<Factor> {
+    let autofilled = flags.iter().filter(|f| f.likely_autofilled).count();
+    if autofilled == 0 {
+        return None;
+    }
+
+    Some(Factor {
+        name: "Autofill".to_string(),
+        impact: autofilled as f64 * -4.0,
+        detail: format!("{} teammates look autofilled", autofilled),
+    })
+}
+
+/// Estimates our chance to win from what we know about our own team. Each factor is a
+/// rough adjustment in percentage points on top of a coin flip.
+pub async fn get_win_probability(
+    remoting_client: &RESTClient,
+    lobby: &Lobby,
+    session: Option<&ChampSelectSession>,
+) -> WinProbability {
+    let (ranks, mastery, recent_form) = futures_util::join!(
+        ranked::get_lobby_ranks(remoting_client, lobby),
+        mastery::get_lobby_mastery(remoting_client, lobby, session),
+        recent_form_factor(remoting_client, lobby)
+    );
+    let autofill = match session {
+        Some(session) => autofill::get_autofill_flags(remoting_client, session).await,
+        None => Vec::new(),
+    };
+
+    let factors: Vec<Factor> = [
+        season_winrate_factor(&ranks),
+        rank_spread_factor(&ranks),
+        recent_form,
+        mastery_factor(&mastery),
+        autofill_factor(&autofill),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let probability = (50.0 + factors.iter().map(|f| f.impact).sum::<f64>())
+        .clamp(MIN_PROBABILITY, MAX_PROBABILITY);
+
+    WinProbability {
+        probability,
+        factors,
+    }
+}